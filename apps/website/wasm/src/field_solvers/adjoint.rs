@@ -0,0 +1,434 @@
+use num_complex::Complex64;
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::field_solvers::fdfd::{
+    complex_from_parts, rasterize_sources, FdfdPolarization, FdfdSimulation, FdfdSource, PmlSpec,
+};
+use crate::field_solvers::yee_grid::YeeGrid2D;
+use crate::lattice_calculations::voronoi_cells::Vector2D;
+
+/// Rectangular design region whose pixels interpolate between two permittivities.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DesignRegion {
+    pub center: Vector2D,
+    pub size: Vector2D,
+    pub epsilon_min: f64,
+    pub epsilon_max: f64,
+}
+
+/// Figure of merit evaluated on a line monitor (one of `size.x`, `size.y` is zero).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum FigureOfMerit {
+    /// Poynting flux Re ∫ (E* × H)·n̂ through a `FluxRegion`, times `weight`.
+    Flux {
+        center: Vector2D,
+        size: Vector2D,
+        #[serde(default = "unit_weight")]
+        weight: f64,
+    },
+    /// Mode-overlap power |∫ m* Ez dl|² against a sampled mode profile.
+    ModeOverlap {
+        center: Vector2D,
+        size: Vector2D,
+        mode_real: Vec<f64>,
+        #[serde(default)]
+        mode_imag: Vec<f64>,
+    },
+}
+
+fn unit_weight() -> f64 {
+    1.0
+}
+
+fn default_eta() -> f64 {
+    0.5
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct AdjointConfig {
+    pub grid: YeeGrid2D,
+    pub frequency: f64,
+    pub pml: PmlSpec,
+    /// Background permittivity in grid order; the design region overrides it.
+    pub epsilon: Vec<f64>,
+    pub sources: Vec<FdfdSource>,
+    pub design_region: DesignRegion,
+    /// Design density in [0, 1], one value per design pixel (x fastest).
+    pub density: Vec<f64>,
+    /// Radius of the conic density filter in Meep units (0 disables filtering).
+    #[serde(default)]
+    pub filter_radius: f64,
+    /// Steepness of the tanh projection (0 disables projection).
+    #[serde(default)]
+    pub beta: f64,
+    #[serde(default = "default_eta")]
+    pub eta: f64,
+    pub objective: FigureOfMerit,
+}
+
+#[derive(Serialize)]
+pub struct AdjointResult {
+    pub fom: f64,
+    pub gradient: Vec<f64>,
+    pub filtered_density: Vec<f64>,
+    pub projected_density: Vec<f64>,
+    pub design_nx: usize,
+    pub design_ny: usize,
+}
+
+/// Pixels covered by the design region, x fastest.
+struct DesignPixels {
+    nx: usize,
+    ny: usize,
+    pixels: Vec<(usize, usize)>,
+}
+
+impl DesignPixels {
+    fn new(grid: &YeeGrid2D, region: &DesignRegion) -> Result<Self, String> {
+        let inside = |coord: f64, center: f64, size: f64| (coord - center).abs() <= 0.5 * size + 1e-9;
+        let xs: Vec<usize> = (0..grid.nx())
+            .filter(|&i| inside(grid.x(i as f64), region.center.x, region.size.x))
+            .collect();
+        let ys: Vec<usize> = (0..grid.ny())
+            .filter(|&j| inside(grid.y(j as f64), region.center.y, region.size.y))
+            .collect();
+        if xs.is_empty() || ys.is_empty() {
+            return Err("design region does not cover any pixels".into());
+        }
+        let pixels = ys.iter().flat_map(|&j| xs.iter().map(move |&i| (i, j))).collect();
+        Ok(Self { nx: xs.len(), ny: ys.len(), pixels })
+    }
+}
+
+/// Normalised conic filter w(r) = max(0, 1 - r/R) on the design pixels.
+struct ConicFilter {
+    /// For each output pixel: (input pixel, weight) pairs.
+    weights: Vec<Vec<(usize, f64)>>,
+}
+
+impl ConicFilter {
+    fn new(design: &DesignPixels, radius: f64, dx: f64) -> Self {
+        let reach = if radius > 0.0 { (radius / dx).floor() as isize } else { 0 };
+        let mut weights = Vec::with_capacity(design.pixels.len());
+        for q in 0..design.ny as isize {
+            for p in 0..design.nx as isize {
+                let mut row = Vec::new();
+                for dq in -reach..=reach {
+                    for dp in -reach..=reach {
+                        let (pp, qq) = (p + dp, q + dq);
+                        if pp < 0 || qq < 0 || pp >= design.nx as isize || qq >= design.ny as isize {
+                            continue;
+                        }
+                        let r = ((dp * dp + dq * dq) as f64).sqrt() * dx;
+                        let w = if radius > 0.0 { 1.0 - r / radius } else { 1.0 };
+                        if w > 0.0 {
+                            row.push((qq as usize * design.nx + pp as usize, w));
+                        }
+                    }
+                }
+                let total: f64 = row.iter().map(|(_, w)| w).sum();
+                row.iter_mut().for_each(|(_, w)| *w /= total);
+                weights.push(row);
+            }
+        }
+        Self { weights }
+    }
+
+    fn apply(&self, x: &[f64]) -> Vec<f64> {
+        self.weights
+            .iter()
+            .map(|row| row.iter().map(|&(k, w)| w * x[k]).sum())
+            .collect()
+    }
+
+    fn apply_transpose(&self, g: &[f64]) -> Vec<f64> {
+        let mut out = vec![0.0; g.len()];
+        for (row, &gi) in self.weights.iter().zip(g) {
+            for &(k, w) in row {
+                out[k] += w * gi;
+            }
+        }
+        out
+    }
+}
+
+/// Meep's `tanh_projection(x, beta, eta)`.
+pub fn tanh_projection(x: f64, beta: f64, eta: f64) -> f64 {
+    if beta <= 0.0 {
+        return x;
+    }
+    ((beta * eta).tanh() + (beta * (x - eta)).tanh())
+        / ((beta * eta).tanh() + (beta * (1.0 - eta)).tanh())
+}
+
+fn tanh_projection_derivative(x: f64, beta: f64, eta: f64) -> f64 {
+    if beta <= 0.0 {
+        return 1.0;
+    }
+    let sech = 1.0 / (beta * (x - eta)).cosh();
+    beta * sech * sech / ((beta * eta).tanh() + (beta * (1.0 - eta)).tanh())
+}
+
+/// Line monitor through half-integer pixel positions: each sample couples the
+/// two pixels on either side of the line.
+struct LineMonitor {
+    pairs: Vec<(usize, usize)>,
+    /// Sample spacing along the line and pixel spacing across it.
+    dx: f64,
+}
+
+impl LineMonitor {
+    fn new(grid: &YeeGrid2D, center: Vector2D, size: Vector2D) -> Result<Self, String> {
+        let dx = grid.dx();
+        let half_index = |c: f64, length: f64, n: usize| {
+            let f = ((c + 0.5 * length) * grid.resolution - 1.0).round();
+            if f < 0.0 || f as usize + 1 >= n {
+                Err("monitor lies outside the cell".to_string())
+            } else {
+                Ok(f as usize)
+            }
+        };
+        let covered = |coord: &dyn Fn(usize) -> f64, n: usize, c: f64, s: f64| -> Vec<usize> {
+            (0..n).filter(|&k| (coord(k) - c).abs() <= 0.5 * s + 1e-9).collect()
+        };
+
+        let pairs: Vec<(usize, usize)> = if size.x == 0.0 {
+            let i = half_index(center.x, grid.size_x, grid.nx())?;
+            covered(&|j| grid.y(j as f64), grid.ny(), center.y, size.y)
+                .into_iter()
+                .map(|j| (grid.index(i, j), grid.index(i + 1, j)))
+                .collect()
+        } else if size.y == 0.0 {
+            let j = half_index(center.y, grid.size_y, grid.ny())?;
+            covered(&|i| grid.x(i as f64), grid.nx(), center.x, size.x)
+                .into_iter()
+                .map(|i| (grid.index(i, j), grid.index(i, j + 1)))
+                .collect()
+        } else {
+            return Err("monitor must be a line (one zero-size direction)".into());
+        };
+        if pairs.is_empty() {
+            return Err("monitor does not cover any pixels".into());
+        }
+        Ok(Self { pairs, dx })
+    }
+}
+
+/// Evaluate a figure of merit and its gradient g such that dF = Re(gᵀ dE).
+fn evaluate_fom(
+    objective: &FigureOfMerit,
+    grid: &YeeGrid2D,
+    omega: f64,
+    field: &[Complex64],
+) -> Result<(f64, Vec<Complex64>), String> {
+    let mut grad = vec![Complex64::new(0.0, 0.0); field.len()];
+    match objective {
+        FigureOfMerit::Flux { center, size, weight } => {
+            // P = Re Σ conj(Ēz) ∂ₙEz / (iω) dl, with Ēz and ∂ₙEz taken on the line
+            let monitor = LineMonitor::new(grid, *center, *size)?;
+            let derivative = 1.0 / (Complex64::new(0.0, omega) * monitor.dx);
+            let mut flux = 0.0;
+            for &(lo, hi) in &monitor.pairs {
+                let a = 0.5 * (field[lo] + field[hi]);
+                let b = (field[hi] - field[lo]) * derivative;
+                flux += (a.conj() * b).re * monitor.dx;
+                let scale = weight * monitor.dx;
+                grad[lo] += scale * (0.5 * b.conj() - a.conj() * derivative);
+                grad[hi] += scale * (0.5 * b.conj() + a.conj() * derivative);
+            }
+            Ok((weight * flux, grad))
+        }
+        FigureOfMerit::ModeOverlap { center, size, mode_real, mode_imag } => {
+            let monitor = LineMonitor::new(grid, *center, *size)?;
+            let mode = complex_from_parts(mode_real, mode_imag);
+            if mode.len() != monitor.pairs.len() {
+                return Err(format!(
+                    "mode profile has {} samples but the monitor covers {}",
+                    mode.len(),
+                    monitor.pairs.len()
+                ));
+            }
+            let overlap: Complex64 = monitor
+                .pairs
+                .iter()
+                .zip(&mode)
+                .map(|(&(lo, hi), m)| m.conj() * 0.5 * (field[lo] + field[hi]) * monitor.dx)
+                .sum();
+            for (&(lo, hi), m) in monitor.pairs.iter().zip(&mode) {
+                let g = overlap.conj() * m.conj() * monitor.dx;
+                grad[lo] += g;
+                grad[hi] += g;
+            }
+            Ok((overlap.norm_sqr(), grad))
+        }
+    }
+}
+
+pub fn adjoint_gradient_internal(config: &AdjointConfig) -> Result<AdjointResult, String> {
+    let grid = config.grid;
+    grid.validate()?;
+    if config.epsilon.len() != grid.pixel_count() {
+        return Err("epsilon does not match the grid size".into());
+    }
+    let design = DesignPixels::new(&grid, &config.design_region)?;
+    if config.density.len() != design.pixels.len() {
+        return Err(format!(
+            "density has {} entries but the design region covers {} pixels",
+            config.density.len(),
+            design.pixels.len()
+        ));
+    }
+
+    // ---- 1. density → filtered → projected → permittivity -----------------
+    let filter = ConicFilter::new(&design, config.filter_radius, grid.dx());
+    let filtered = filter.apply(&config.density);
+    let projected: Vec<f64> = filtered
+        .iter()
+        .map(|&x| tanh_projection(x, config.beta, config.eta))
+        .collect();
+
+    let region = &config.design_region;
+    let delta_eps = region.epsilon_max - region.epsilon_min;
+    let mut epsilon: Vec<Complex64> = config.epsilon.iter().map(|&e| Complex64::new(e, 0.0)).collect();
+    for (&(i, j), &rho) in design.pixels.iter().zip(&projected) {
+        epsilon[grid.index(i, j)] = Complex64::new(region.epsilon_min + rho * delta_eps, 0.0);
+    }
+
+    // ---- 2. forward and adjoint solves -------------------------------------
+    let simulation = FdfdSimulation {
+        grid,
+        frequency: config.frequency,
+        polarization: FdfdPolarization::Ez,
+        pml: config.pml,
+        epsilon,
+        anisotropy: Vec::new(),
+    };
+    let omega = simulation.omega();
+    let factorization = simulation.factor()?;
+    let current = rasterize_sources(&grid, &config.sources);
+    let field = factorization.solve(&simulation.rhs(&current));
+
+    let (fom, dfom_dfield) = evaluate_fom(&config.objective, &grid, omega, &field)?;
+    let adjoint = factorization.solve_transpose(&dfom_dfield);
+
+    // ---- 3. chain rule back to the raw density -----------------------------
+    // ∂A/∂ε_p = -ω² e_p e_pᵀ  ⇒  dF/dε_p = Re(λ_p ω² E_p)
+    let d_projected: Vec<f64> = design
+        .pixels
+        .iter()
+        .zip(&filtered)
+        .map(|(&(i, j), &x)| {
+            let p = grid.index(i, j);
+            let df_deps = (adjoint[p] * omega * omega * field[p]).re;
+            df_deps * delta_eps * tanh_projection_derivative(x, config.beta, config.eta)
+        })
+        .collect();
+    let gradient = filter.apply_transpose(&d_projected);
+
+    Ok(AdjointResult {
+        fom,
+        gradient,
+        filtered_density: filtered,
+        projected_density: projected,
+        design_nx: design.nx,
+        design_ny: design.ny,
+    })
+}
+
+/// Figure of merit and its adjoint gradient with respect to the design density
+#[wasm_bindgen]
+pub fn compute_adjoint_gradient(config: &JsValue) -> Result<JsValue, JsValue> {
+    let config: AdjointConfig = serde_wasm_bindgen::from_value(config.clone())?;
+    let result = adjoint_gradient_internal(&config).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config(objective: FigureOfMerit) -> AdjointConfig {
+        let grid = YeeGrid2D::new(4.0, 3.0, 10.0);
+        let design_region = DesignRegion {
+            center: Vector2D::new(0.0, 0.0),
+            size: Vector2D::new(0.6, 0.6),
+            epsilon_min: 1.0,
+            epsilon_max: 4.0,
+        };
+        let n_design = 6 * 6;
+        AdjointConfig {
+            grid,
+            frequency: 0.8,
+            pml: PmlSpec { thickness: 0.8, power: 2.0, r_asymptotic: 1e-10 },
+            epsilon: vec![1.0; grid.pixel_count()],
+            sources: vec![FdfdSource {
+                center: Vector2D::new(-0.9, 0.0),
+                size: Vector2D::new(0.0, 1.0),
+                amplitude_real: 1.0,
+                amplitude_imag: 0.0,
+            }],
+            design_region,
+            density: (0..n_design).map(|k| 0.3 + 0.4 * ((k * 7) % 11) as f64 / 11.0).collect(),
+            filter_radius: 0.25,
+            beta: 4.0,
+            eta: 0.5,
+            objective,
+        }
+    }
+
+    fn check_against_finite_differences(config: &AdjointConfig) {
+        let result = adjoint_gradient_internal(config).unwrap();
+        let h = 1e-5;
+        for &k in &[0usize, 14, 21, 35] {
+            let mut plus = config.clone();
+            plus.density[k] += h;
+            let mut minus = config.clone();
+            minus.density[k] -= h;
+            let fd = (adjoint_gradient_internal(&plus).unwrap().fom
+                - adjoint_gradient_internal(&minus).unwrap().fom)
+                / (2.0 * h);
+            let adj = result.gradient[k];
+            assert!(
+                (adj - fd).abs() <= 1e-4 * fd.abs().max(1e-3 * result.fom.abs()),
+                "pixel {}: adjoint {} vs finite difference {}",
+                k,
+                adj,
+                fd
+            );
+        }
+    }
+
+    #[test]
+    fn test_flux_gradient_matches_finite_differences() {
+        let config = test_config(FigureOfMerit::Flux {
+            center: Vector2D::new(0.9, 0.0),
+            size: Vector2D::new(0.0, 1.0),
+            weight: 1.0,
+        });
+        check_against_finite_differences(&config);
+    }
+
+    #[test]
+    fn test_mode_overlap_gradient_matches_finite_differences() {
+        let n = 10;
+        let mode_real: Vec<f64> = (0..n)
+            .map(|k| (-(k as f64 - 4.5).powi(2) / 8.0).exp())
+            .collect();
+        let config = test_config(FigureOfMerit::ModeOverlap {
+            center: Vector2D::new(0.9, 0.0),
+            size: Vector2D::new(0.0, 1.0),
+            mode_real,
+            mode_imag: vec![0.0; n],
+        });
+        check_against_finite_differences(&config);
+    }
+
+    #[test]
+    fn test_tanh_projection_limits() {
+        assert!((tanh_projection(0.0, 8.0, 0.5)).abs() < 1e-12);
+        assert!((tanh_projection(1.0, 8.0, 0.5) - 1.0).abs() < 1e-12);
+        assert!((tanh_projection(0.5, 8.0, 0.5) - 0.5).abs() < 1e-12);
+    }
+}
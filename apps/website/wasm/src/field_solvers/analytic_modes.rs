@@ -0,0 +1,836 @@
+use num_complex::Complex64;
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+use crate::field_solvers::fdfd::FdfdPolarization;
+use crate::field_solvers::mie_scattering::FieldComponentMap;
+use crate::field_solvers::transfer_matrix::LayerMedium;
+use crate::numerical_calculations::special_functions::{bessel_j_orders, bessel_k_scaled_orders};
+
+/// Waveguides with closed-form or transcendental dispersion relations. Slabs
+/// and surface plasmons are 2D, propagating along x with the layers stacked
+/// in y; fibres propagate along z.
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AnalyticWaveguide {
+    /// Core |y| < width/2 between `substrate` (below) and `cladding` (above),
+    /// which defaults to the substrate for a symmetric slab.
+    Slab {
+        width: f64,
+        core: LayerMedium,
+        substrate: LayerMedium,
+        #[serde(default)]
+        cladding: Option<LayerMedium>,
+    },
+    /// A single metal–dielectric interface with the metal in y < 0.
+    SurfacePlasmon { metal: LayerMedium, dielectric: LayerMedium },
+    /// Step-index fibre with an infinite cladding.
+    Fiber { radius: f64, core: LayerMedium, cladding: LayerMedium },
+}
+
+/// Field profiles at one frequency, sampled at pixel centres across `size`:
+/// along y for 2D guides and along the radius 0…size/2 for fibres.
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct ModeProfileSpec {
+    pub frequency: f64,
+    pub size: f64,
+    pub resolution: f64,
+}
+
+fn default_max_modes() -> usize {
+    8
+}
+
+fn default_max_azimuthal_order() -> usize {
+    3
+}
+
+#[derive(Deserialize)]
+pub struct AnalyticModeConfig {
+    pub waveguide: AnalyticWaveguide,
+    pub frequencies: Vec<f64>,
+    /// Mode orders kept per polarisation (slabs) or per azimuthal order and family (fibres).
+    #[serde(default = "default_max_modes")]
+    pub max_modes: usize,
+    /// Highest ν of the hybrid modes and l of the LP modes.
+    #[serde(default = "default_max_azimuthal_order")]
+    pub max_azimuthal_order: usize,
+    #[serde(default)]
+    pub profile: Option<ModeProfileSpec>,
+}
+
+#[derive(Serialize)]
+pub struct AnalyticModeBranch {
+    /// TE0, TM1 (slab convention, TE = Ez), SPP, HE11, EH11, TE01, TM01 or LP01.
+    pub label: String,
+    /// Out-of-plane component of slab and plasmon modes.
+    pub polarization: Option<FdfdPolarization>,
+    /// ν of hybrid fibre modes, l of LP modes, 0 otherwise.
+    pub azimuthal_order: usize,
+    /// Slab mode number from 0; fibre radial order from 1.
+    pub order: usize,
+    /// One entry per frequency, `None` where the mode is not guided.
+    pub n_eff: Vec<Option<f64>>,
+    /// Non-zero for surface plasmons only; the 1/e power propagation
+    /// length is 1 / (4π f Im n_eff).
+    pub n_eff_imag: Vec<Option<f64>>,
+}
+
+#[derive(Serialize)]
+pub struct AnalyticModeProfile {
+    pub label: String,
+    pub frequency: f64,
+    pub n_eff: f64,
+    pub positions: Vec<f64>,
+    /// Normalised to unit flux Re ∫ E × H* over the cross-section (per unit
+    /// length in 2D). Fibre components are radial functions: Er, Ez and Hphi
+    /// multiply cos νφ, Ephi, Hr and Hz multiply sin νφ (1 when ν = 0), and
+    /// the weakly-guided Ex and Hy of LP modes multiply cos lφ.
+    pub components: Vec<FieldComponentMap>,
+}
+
+#[derive(Serialize)]
+pub struct AnalyticModeResult {
+    pub frequencies: Vec<f64>,
+    /// V = k h √(n₁² − n₂²) with h the slab half-width or fibre radius and n₂
+    /// the higher cladding index; empty for surface plasmons.
+    pub v_number: Vec<f64>,
+    pub modes: Vec<AnalyticModeBranch>,
+    pub profiles: Vec<AnalyticModeProfile>,
+    pub warnings: Vec<String>,
+}
+
+/// One guided mode at one frequency; its fields are rebuilt from n_eff.
+struct ModeSolution {
+    label: String,
+    polarization: Option<FdfdPolarization>,
+    azimuthal_order: usize,
+    order: usize,
+    n_eff: Complex64,
+    family: FiberFamily,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum FiberFamily {
+    Planar,
+    Hybrid,
+    Lp,
+}
+
+/// Real permittivity of a guide layer; loss is reported once and dropped.
+fn real_epsilon(medium: &LayerMedium, frequency: f64, name: &str, warnings: &mut Vec<String>) -> f64 {
+    let eps = medium.epsilon_at(frequency);
+    if eps.im.abs() > 1e-12 * eps.re.abs() {
+        let message = format!("the loss of the {} is ignored; its modes assume Re ε", name);
+        if !warnings.contains(&message) {
+            warnings.push(message);
+        }
+    }
+    eps.re
+}
+
+/// Root of a continuous `f` whose sign differs at `lo` and `hi`.
+fn bisect(f: impl Fn(f64) -> f64, mut lo: f64, mut hi: f64) -> f64 {
+    let lo_positive = f(lo) > 0.0;
+    for _ in 0..200 {
+        let mid = 0.5 * (lo + hi);
+        if mid <= lo || mid >= hi {
+            break;
+        }
+        if (f(mid) > 0.0) == lo_positive {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    0.5 * (lo + hi)
+}
+
+/// Sign changes of `f` over the ascending samples `t`, refined by bisection.
+fn scan_roots(f: impl Fn(f64) -> f64, t: &[f64], limit: usize) -> Vec<f64> {
+    let values: Vec<f64> = t.iter().map(|&t| f(t)).collect();
+    let mut roots = Vec::new();
+    for i in 0..t.len() - 1 {
+        if roots.len() == limit {
+            break;
+        }
+        if values[i] == 0.0 {
+            roots.push(t[i]);
+        } else if values[i] * values[i + 1] < 0.0 {
+            roots.push(bisect(&f, t[i], t[i + 1]));
+        }
+    }
+    roots
+}
+
+/// Transverse phase φ = atan(r γ/κ) picked up at a slab boundary, with r = 1
+/// for Ez and ε_core/ε_clad for Hz.
+fn boundary_phase(polarization: FdfdPolarization, eps_core: f64, eps_clad: f64, gamma: f64, kappa: f64) -> f64 {
+    let ratio = match polarization {
+        FdfdPolarization::Ez => 1.0,
+        FdfdPolarization::Hz => eps_core / eps_clad,
+    };
+    (ratio * gamma / kappa).atan()
+}
+
+/// Guided modes of a slab from κd = mπ + φ_sub + φ_clad, solved in n² between
+/// the higher cladding and the core, where the left side minus the right
+/// decreases monotonically.
+fn slab_modes(width: f64, eps: [f64; 3], frequency: f64, max_modes: usize) -> Vec<ModeSolution> {
+    let [core, substrate, cladding] = eps;
+    let lower = substrate.max(cladding);
+    let k = 2.0 * PI * frequency;
+    let mut modes = Vec::new();
+    if core <= lower {
+        return modes;
+    }
+    for polarization in [FdfdPolarization::Ez, FdfdPolarization::Hz] {
+        for m in 0..max_modes {
+            let mismatch = |n2: f64| {
+                let kappa = k * (core - n2).max(0.0).sqrt();
+                let gamma_s = k * (n2 - substrate).max(0.0).sqrt();
+                let gamma_c = k * (n2 - cladding).max(0.0).sqrt();
+                kappa * width
+                    - boundary_phase(polarization, core, substrate, gamma_s, kappa)
+                    - boundary_phase(polarization, core, cladding, gamma_c, kappa)
+                    - m as f64 * PI
+            };
+            if mismatch(lower) <= 0.0 {
+                break;
+            }
+            let n2 = bisect(mismatch, lower, core);
+            let prefix = match polarization {
+                FdfdPolarization::Ez => "TE",
+                FdfdPolarization::Hz => "TM",
+            };
+            modes.push(ModeSolution {
+                label: format!("{}{}", prefix, m),
+                polarization: Some(polarization),
+                azimuthal_order: 0,
+                order: m,
+                n_eff: Complex64::new(n2.sqrt(), 0.0),
+                family: FiberFamily::Planar,
+            });
+        }
+    }
+    modes
+}
+
+/// The bound TM surface plasmon n² = ε_m ε_d / (ε_m + ε_d), when Re ε_m < −Re ε_d.
+fn plasmon_mode(eps_metal: Complex64, eps_dielectric: Complex64) -> Option<ModeSolution> {
+    if eps_metal.re >= -eps_dielectric.re {
+        return None;
+    }
+    let mut n_eff = (eps_metal * eps_dielectric / (eps_metal + eps_dielectric)).sqrt();
+    if n_eff.re < 0.0 {
+        n_eff = -n_eff;
+    }
+    Some(ModeSolution {
+        label: "SPP".into(),
+        polarization: Some(FdfdPolarization::Hz),
+        azimuthal_order: 0,
+        order: 0,
+        n_eff,
+        family: FiberFamily::Planar,
+    })
+}
+
+fn bessel_j_real(x: f64, max_order: usize) -> Vec<f64> {
+    bessel_j_orders(Complex64::new(x, 0.0), max_order).iter().map(|c| c.re).collect()
+}
+
+/// Jν′(x) = Jν₋₁ − (ν/x) Jν with J₋₁ = −J₁.
+fn j_derivative(j: &[f64], nu: usize, x: f64) -> f64 {
+    if nu == 0 { -j[1] } else { j[nu - 1] - nu as f64 / x * j[nu] }
+}
+
+/// Kν′(x) = −Kν₋₁ − (ν/x) Kν with K₋₁ = K₁; also valid for scaled values.
+fn k_derivative(k: &[f64], nu: usize, x: f64) -> f64 {
+    if nu == 0 { -k[1] } else { -k[nu - 1] - nu as f64 / x * k[nu] }
+}
+
+/// Exact step-index characteristic function in u at w = √(V² − u²), written
+/// without the poles of Jν′/(u Jν): the + root gives EHνm (TE0m for ν = 0)
+/// and the − root HEνm (TM0m), from Snyder & Love's
+/// (η + κ)(η + ρκ) = ν² (1/u² + 1/w²)(1/u² + ρ/w²), η = Jν′/(uJν), κ = Kν′/(wKν).
+fn hybrid_characteristic(nu: usize, u: f64, w: f64, rho: f64, plus: bool) -> f64 {
+    let j = bessel_j_real(u, nu + 1);
+    let k = bessel_k_scaled_orders(w, nu + 1);
+    let kappa = k_derivative(&k, nu, w) / (w * k[nu]);
+    let nf = nu as f64;
+    let q = nf * nf * (1.0 / (u * u) + 1.0 / (w * w)) * (1.0 / (u * u) + rho / (w * w));
+    let root = ((0.5 * (1.0 - rho) * kappa).powi(2) + q).sqrt();
+    let eta = -0.5 * (1.0 + rho) * kappa + if plus { root } else { -root };
+    j_derivative(&j, nu, u) / u - eta * j[nu]
+}
+
+/// Weakly-guided LP characteristic u J_{l+1}(u) K_l(w) − w K_{l+1}(w) J_l(u).
+fn lp_characteristic(l: usize, u: f64, w: f64) -> f64 {
+    let j = bessel_j_real(u, l + 1);
+    let k = bessel_k_scaled_orders(w, l + 1);
+    u * j[l + 1] * k[l] - w * k[l + 1] * j[l]
+}
+
+/// Hybrid and LP modes of a fibre, scanning u = V sin t with t from near 0 to
+/// just below π/2 (w → 0 at cutoff).
+fn fiber_modes(radius: f64, eps: [f64; 2], frequency: f64, max_modes: usize, max_order: usize) -> Vec<ModeSolution> {
+    let [core, cladding] = eps;
+    let mut modes = Vec::new();
+    if core <= cladding {
+        return modes;
+    }
+    let k = 2.0 * PI * frequency;
+    let v = k * radius * (core - cladding).sqrt();
+    let rho = cladding / core;
+    let samples = 40 + (20.0 * v).ceil() as usize;
+    let (lo, hi) = (1e-3, 0.5 * PI - 1e-7);
+    let t: Vec<f64> = (0..samples).map(|i| lo + (hi - lo) * i as f64 / (samples - 1) as f64).collect();
+    let mut push = |roots: Vec<f64>, label: &dyn Fn(usize) -> String, nu: usize, family: FiberFamily| {
+        for (m, t) in roots.into_iter().enumerate() {
+            let u = v * t.sin();
+            modes.push(ModeSolution {
+                label: label(m + 1),
+                polarization: None,
+                azimuthal_order: nu,
+                order: m + 1,
+                n_eff: Complex64::new((core - (u / (k * radius)).powi(2)).sqrt(), 0.0),
+                family,
+            });
+        }
+    };
+    for nu in 0..=max_order {
+        for plus in [false, true] {
+            let roots = scan_roots(|t| hybrid_characteristic(nu, v * t.sin(), v * t.cos(), rho, plus), &t, max_modes);
+            let prefix = match (nu, plus) {
+                (0, true) => "TE",
+                (0, false) => "TM",
+                (_, true) => "EH",
+                (_, false) => "HE",
+            };
+            push(roots, &|m| format!("{}{}{}", prefix, nu, m), nu, FiberFamily::Hybrid);
+        }
+        let roots = scan_roots(|t| lp_characteristic(nu, v * t.sin(), v * t.cos()), &t, max_modes);
+        push(roots, &|m| format!("LP{}{}", nu, m), nu, FiberFamily::Lp);
+    }
+    modes
+}
+
+/// Guide-specific permittivities at one frequency: core first, then the outer media.
+fn guide_epsilon(waveguide: &AnalyticWaveguide, frequency: f64, warnings: &mut Vec<String>) -> Vec<f64> {
+    match waveguide {
+        AnalyticWaveguide::Slab { core, substrate, cladding, .. } => vec![
+            real_epsilon(core, frequency, "core", warnings),
+            real_epsilon(substrate, frequency, "substrate", warnings),
+            real_epsilon(cladding.as_ref().unwrap_or(substrate), frequency, "cladding", warnings),
+        ],
+        AnalyticWaveguide::Fiber { core, cladding, .. } => vec![
+            real_epsilon(core, frequency, "core", warnings),
+            real_epsilon(cladding, frequency, "cladding", warnings),
+        ],
+        AnalyticWaveguide::SurfacePlasmon { .. } => Vec::new(),
+    }
+}
+
+fn modes_at(config: &AnalyticModeConfig, frequency: f64, warnings: &mut Vec<String>) -> Vec<ModeSolution> {
+    let eps = guide_epsilon(&config.waveguide, frequency, warnings);
+    match &config.waveguide {
+        AnalyticWaveguide::Slab { width, .. } => slab_modes(*width, [eps[0], eps[1], eps[2]], frequency, config.max_modes),
+        AnalyticWaveguide::Fiber { radius, .. } => {
+            fiber_modes(*radius, [eps[0], eps[1]], frequency, config.max_modes, config.max_azimuthal_order)
+        }
+        AnalyticWaveguide::SurfacePlasmon { metal, dielectric } => {
+            plasmon_mode(metal.epsilon_at(frequency), dielectric.epsilon_at(frequency)).into_iter().collect()
+        }
+    }
+}
+
+fn component(name: &str, values: &[Complex64], scale: f64) -> FieldComponentMap {
+    FieldComponentMap {
+        name: name.into(),
+        real: values.iter().map(|v| v.re * scale).collect(),
+        imag: values.iter().map(|v| v.im * scale).collect(),
+    }
+}
+
+/// Ez or Hz of a slab mode and its y-derivative, with the core field
+/// cos(κ(y + d/2) − φ_sub) continued by exponentials into the claddings.
+fn slab_profile(
+    width: f64,
+    eps: [f64; 3],
+    polarization: FdfdPolarization,
+    frequency: f64,
+    n_eff: f64,
+    positions: &[f64],
+) -> Vec<FieldComponentMap> {
+    let [core, substrate, cladding] = eps;
+    let omega = 2.0 * PI * frequency;
+    let beta = omega * n_eff;
+    let n2 = n_eff * n_eff;
+    let kappa = omega * (core - n2).sqrt();
+    let (gamma_s, gamma_c) = (omega * (n2 - substrate).sqrt(), omega * (n2 - cladding).sqrt());
+    let phi = boundary_phase(polarization, core, substrate, gamma_s, kappa);
+    let half = 0.5 * width;
+    let (bottom, top) = (phi.cos(), (kappa * width - phi).cos());
+    let sample = |y: f64| -> (f64, f64, f64) {
+        if y < -half {
+            let e = (gamma_s * (y + half)).exp();
+            (bottom * e, gamma_s * bottom * e, substrate)
+        } else if y > half {
+            let e = (-gamma_c * (y - half)).exp();
+            (top * e, -gamma_c * top * e, cladding)
+        } else {
+            let arg = kappa * (y + half) - phi;
+            (arg.cos(), -kappa * arg.sin(), core)
+        }
+    };
+    // ∫ψ² dy, divided by ε for Hz, in closed form per region
+    let weight = |eps: f64| match polarization {
+        FdfdPolarization::Ez => 1.0,
+        FdfdPolarization::Hz => 1.0 / eps,
+    };
+    let core_integral = half + ((2.0 * (kappa * width - phi)).sin() + (2.0 * phi).sin()) / (4.0 * kappa);
+    let integral = weight(core) * core_integral
+        + weight(substrate) * bottom * bottom / (2.0 * gamma_s)
+        + weight(cladding) * top * top / (2.0 * gamma_c);
+    let scale = 1.0 / (beta / omega * integral).sqrt();
+
+    let i = Complex64::new(0.0, 1.0);
+    let samples: Vec<(f64, f64, f64)> = positions.iter().map(|&y| sample(y)).collect();
+    let main: Vec<Complex64> = samples.iter().map(|s| Complex64::new(s.0, 0.0)).collect();
+    match polarization {
+        // Hx = −i ∂y Ez / ω, Hy = −β Ez / ω
+        FdfdPolarization::Ez => vec![
+            component("Ez", &main, scale),
+            component("Hx", &samples.iter().map(|s| -i * s.1 / omega).collect::<Vec<_>>(), scale),
+            component("Hy", &samples.iter().map(|s| Complex64::new(-beta * s.0 / omega, 0.0)).collect::<Vec<_>>(), scale),
+        ],
+        // Ex = i ∂y Hz / (ωε), Ey = β Hz / (ωε)
+        FdfdPolarization::Hz => vec![
+            component("Hz", &main, scale),
+            component("Ex", &samples.iter().map(|s| i * s.1 / (omega * s.2)).collect::<Vec<_>>(), scale),
+            component("Ey", &samples.iter().map(|s| Complex64::new(beta * s.0 / (omega * s.2), 0.0)).collect::<Vec<_>>(), scale),
+        ],
+    }
+}
+
+/// Hz = exp(−γ_d y) above the metal and exp(γ_m y) inside it.
+fn plasmon_profile(
+    eps_metal: Complex64,
+    eps_dielectric: Complex64,
+    frequency: f64,
+    n_eff: Complex64,
+    positions: &[f64],
+    warnings: &mut Vec<String>,
+) -> Vec<FieldComponentMap> {
+    let omega = 2.0 * PI * frequency;
+    let beta = omega * n_eff;
+    let decay = |eps: Complex64| {
+        let g = (beta * beta - omega * omega * eps).sqrt();
+        if g.re < 0.0 { -g } else { g }
+    };
+    let (gamma_d, gamma_m) = (decay(eps_dielectric), decay(eps_metal));
+    // Re ∫ Ey Hz* dy with Ey = β Hz / (ωε); the metal side carries negative power
+    let flux = (beta / (omega * eps_dielectric)).re / (2.0 * gamma_d.re) + (beta / (omega * eps_metal)).re / (2.0 * gamma_m.re);
+    let scale = if flux > 0.0 {
+        1.0 / flux.sqrt()
+    } else {
+        warnings.push("the surface plasmon carries no net forward power; its profile is not normalised".into());
+        1.0
+    };
+    let i = Complex64::new(0.0, 1.0);
+    let samples: Vec<(Complex64, Complex64, Complex64)> = positions
+        .iter()
+        .map(|&y| {
+            if y >= 0.0 {
+                let h = (-gamma_d * y).exp();
+                (h, -gamma_d * h, eps_dielectric)
+            } else {
+                let h = (gamma_m * y).exp();
+                (h, gamma_m * h, eps_metal)
+            }
+        })
+        .collect();
+    vec![
+        component("Hz", &samples.iter().map(|s| s.0).collect::<Vec<_>>(), scale),
+        component("Ex", &samples.iter().map(|s| i * s.1 / (omega * s.2)).collect::<Vec<_>>(), scale),
+        component("Ey", &samples.iter().map(|s| beta * s.0 / (omega * s.2)).collect::<Vec<_>>(), scale),
+    ]
+}
+
+/// Radial function Zν and its r-derivative, J_ν(ur/a) in the core continued by
+/// J_ν(u) K_ν(wr/a) / K_ν(w) outside.
+fn fiber_radial(nu: usize, u: f64, w: f64, radius: f64, r: f64) -> (f64, f64) {
+    let s = r / radius;
+    if s <= 1.0 {
+        let j = bessel_j_real(u * s, nu + 1);
+        (j[nu], u / radius * j_derivative(&j, nu, u * s))
+    } else {
+        // scaled K values carry exp(w s), so the ratio to K(w) needs exp(−w(s − 1))
+        let edge = bessel_j_real(u, nu)[nu] / bessel_k_scaled_orders(w, nu)[nu] * (-w * (s - 1.0)).exp();
+        let k = bessel_k_scaled_orders(w * s, nu + 1);
+        (edge * k[nu], edge * w / radius * k_derivative(&k, nu, w * s))
+    }
+}
+
+/// Fibre fields from Ez = A Zν cos νφ and Hz = B Zν sin νφ through
+/// E_t = (i/γ²)[β ∇_t Ez − ω ẑ × ∇_t Hz], H_t = (i/γ²)[β ∇_t Hz + ωε ẑ × ∇_t Ez],
+/// with B = −(β/ω) ν (1/u² + 1/w²) Jν(u) A / (Jν′(u)/u + κ Jν(u)) from the
+/// continuity of Eφ. LP modes use Ex = Zν and Hy = n_eff Ex.
+fn fiber_profile(
+    radius: f64,
+    eps: [f64; 2],
+    frequency: f64,
+    mode: &ModeSolution,
+    positions: &[f64],
+) -> Vec<FieldComponentMap> {
+    let [core, cladding] = eps;
+    let omega = 2.0 * PI * frequency;
+    let n_eff = mode.n_eff.re;
+    let beta = omega * n_eff;
+    let nu = mode.azimuthal_order;
+    let nf = nu as f64;
+    let u = omega * radius * (core - n_eff * n_eff).max(0.0).sqrt();
+    let w = omega * radius * (n_eff * n_eff - cladding).max(0.0).sqrt();
+    // angular integral of cos² νφ (or sin² νφ)
+    let theta = if nu == 0 { 2.0 * PI } else { PI };
+    // radial flux integral on a midpoint rule out to 40 decay lengths
+    let (inner, outer) = (2000, 4000);
+    let extent = 40.0 * radius / w.max(1e-3);
+    let quadrature: Vec<(f64, f64)> = (0..inner)
+        .map(|n| ((n as f64 + 0.5) * radius / inner as f64, radius / inner as f64))
+        .chain((0..outer).map(|n| (radius + (n as f64 + 0.5) * extent / outer as f64, extent / outer as f64)))
+        .collect();
+
+    if mode.family == FiberFamily::Lp {
+        let flux: f64 =
+            theta * n_eff * quadrature.iter().map(|&(r, dr)| fiber_radial(nu, u, w, radius, r).0.powi(2) * r * dr).sum::<f64>();
+        let scale = 1.0 / flux.sqrt();
+        let ex: Vec<Complex64> = positions.iter().map(|&r| Complex64::new(fiber_radial(nu, u, w, radius, r).0, 0.0)).collect();
+        let hy: Vec<Complex64> = ex.iter().map(|e| e * n_eff).collect();
+        return vec![component("Ex", &ex, scale), component("Hy", &hy, scale)];
+    }
+
+    let (a, b) = match (nu, mode.label.starts_with("TE")) {
+        (0, true) => (0.0, 1.0),
+        (0, false) => (1.0, 0.0),
+        _ => {
+            let j = bessel_j_real(u, nu + 1);
+            let k = bessel_k_scaled_orders(w, nu + 1);
+            let kappa = k_derivative(&k, nu, w) / (w * k[nu]);
+            let s = nf * (1.0 / (u * u) + 1.0 / (w * w)) * j[nu] / (j_derivative(&j, nu, u) / u + kappa * j[nu]);
+            (1.0, -beta / omega * s)
+        }
+    };
+    let i = Complex64::new(0.0, 1.0);
+    // [Er, Ephi, Ez, Hr, Hphi, Hz] radial functions at r
+    let fields = |r: f64| -> [Complex64; 6] {
+        let (z, dz) = fiber_radial(nu, u, w, radius, r);
+        let (eps, gamma2) = if r <= radius {
+            (core, (u / radius).powi(2))
+        } else {
+            (cladding, -(w / radius).powi(2))
+        };
+        let (ez, dez, hz, dhz) = (a * z, a * dz, b * z, b * dz);
+        let c = i / gamma2;
+        [
+            c * (beta * dez + omega * nf * hz / r),
+            c * (-beta * nf * ez / r - omega * dhz),
+            Complex64::new(ez, 0.0),
+            c * (beta * dhz + omega * eps * nf * ez / r),
+            c * (beta * nf * hz / r + omega * eps * dez),
+            Complex64::new(hz, 0.0),
+        ]
+    };
+    let flux: f64 = theta
+        * quadrature
+            .iter()
+            .map(|&(r, dr)| {
+                let f = fields(r);
+                (f[0] * f[4].conj() - f[1] * f[3].conj()).re * r * dr
+            })
+            .sum::<f64>();
+    let scale = 1.0 / flux.abs().sqrt();
+    let sampled: Vec<[Complex64; 6]> = positions.iter().map(|&r| fields(r)).collect();
+    ["Er", "Ephi", "Ez", "Hr", "Hphi", "Hz"]
+        .iter()
+        .enumerate()
+        .map(|(c, name)| component(name, &sampled.iter().map(|f| f[c]).collect::<Vec<_>>(), scale))
+        .collect()
+}
+
+fn mode_profile(
+    config: &AnalyticModeConfig,
+    frequency: f64,
+    mode: &ModeSolution,
+    positions: &[f64],
+    warnings: &mut Vec<String>,
+) -> Vec<FieldComponentMap> {
+    let eps = guide_epsilon(&config.waveguide, frequency, warnings);
+    match &config.waveguide {
+        AnalyticWaveguide::Slab { width, .. } => slab_profile(
+            *width,
+            [eps[0], eps[1], eps[2]],
+            mode.polarization.unwrap_or(FdfdPolarization::Ez),
+            frequency,
+            mode.n_eff.re,
+            positions,
+        ),
+        AnalyticWaveguide::SurfacePlasmon { metal, dielectric } => plasmon_profile(
+            metal.epsilon_at(frequency),
+            dielectric.epsilon_at(frequency),
+            frequency,
+            mode.n_eff,
+            positions,
+            warnings,
+        ),
+        AnalyticWaveguide::Fiber { radius, .. } => fiber_profile(*radius, [eps[0], eps[1]], frequency, mode, positions),
+    }
+}
+
+pub fn analytic_modes_internal(config: &AnalyticModeConfig) -> Result<AnalyticModeResult, String> {
+    if config.frequencies.is_empty() || config.frequencies.iter().any(|&f| f <= 0.0) {
+        return Err("frequencies must be positive".into());
+    }
+    match &config.waveguide {
+        AnalyticWaveguide::Slab { width, .. } if *width <= 0.0 => return Err("slab width must be positive".into()),
+        AnalyticWaveguide::Fiber { radius, .. } if *radius <= 0.0 => return Err("fibre radius must be positive".into()),
+        _ => {}
+    }
+    if let Some(p) = &config.profile {
+        if p.frequency <= 0.0 || p.size <= 0.0 || p.resolution <= 0.0 {
+            return Err("profile frequency, size and resolution must be positive".into());
+        }
+    }
+
+    let mut warnings = Vec::new();
+    let count = config.frequencies.len();
+    let mut v_number = Vec::new();
+    let mut modes: Vec<AnalyticModeBranch> = Vec::new();
+    let mut unguided = 0;
+    for (index, &frequency) in config.frequencies.iter().enumerate() {
+        let eps = guide_epsilon(&config.waveguide, frequency, &mut warnings);
+        let k = 2.0 * PI * frequency;
+        match &config.waveguide {
+            AnalyticWaveguide::Slab { width, .. } => {
+                v_number.push(0.5 * k * width * (eps[0] - eps[1].max(eps[2])).max(0.0).sqrt())
+            }
+            AnalyticWaveguide::Fiber { radius, .. } => {
+                let delta = (eps[0] - eps[1]) / (2.0 * eps[0]);
+                if delta > 0.01 && !warnings.iter().any(|w: &String| w.starts_with("LP modes")) {
+                    warnings.push(format!("LP modes assume weak guidance; Δ = {:.3} makes their n_eff approximate", delta));
+                }
+                v_number.push(k * radius * (eps[0] - eps[1]).max(0.0).sqrt())
+            }
+            AnalyticWaveguide::SurfacePlasmon { .. } => {}
+        }
+        let found = modes_at(config, frequency, &mut warnings);
+        if found.is_empty() {
+            unguided += 1;
+        }
+        for mode in found {
+            let position = match modes.iter().position(|b| b.label == mode.label) {
+                Some(p) => p,
+                None => {
+                    modes.push(AnalyticModeBranch {
+                        label: mode.label.clone(),
+                        polarization: mode.polarization,
+                        azimuthal_order: mode.azimuthal_order,
+                        order: mode.order,
+                        n_eff: vec![None; count],
+                        n_eff_imag: vec![None; count],
+                    });
+                    modes.len() - 1
+                }
+            };
+            modes[position].n_eff[index] = Some(mode.n_eff.re);
+            modes[position].n_eff_imag[index] = Some(mode.n_eff.im);
+        }
+    }
+    if unguided > 0 {
+        let reason = match config.waveguide {
+            AnalyticWaveguide::SurfacePlasmon { .. } => "no bound surface plasmon (Re ε_metal ≥ −Re ε_dielectric)",
+            _ => "no guided modes",
+        };
+        warnings.push(format!("{} at {} of {} frequencies", reason, unguided, count));
+    }
+    // fundamental modes first, judged at the highest frequency
+    let top = (0..count).max_by(|&a, &b| config.frequencies[a].total_cmp(&config.frequencies[b])).unwrap();
+    modes.sort_by(|a, b| {
+        let key = |m: &AnalyticModeBranch| m.n_eff[top].unwrap_or(f64::NEG_INFINITY);
+        key(b).total_cmp(&key(a))
+    });
+
+    let mut profiles = Vec::new();
+    if let Some(spec) = &config.profile {
+        let samples = (spec.size * spec.resolution).round().max(1.0) as usize;
+        let positions: Vec<f64> = match config.waveguide {
+            AnalyticWaveguide::Fiber { .. } => (0..samples / 2).map(|j| (j as f64 + 0.5) / spec.resolution).collect(),
+            _ => (0..samples).map(|j| -0.5 * spec.size + (j as f64 + 0.5) / spec.resolution).collect(),
+        };
+        let mut found = modes_at(config, spec.frequency, &mut warnings);
+        if found.is_empty() {
+            warnings.push(format!("no guided modes to profile at f = {}", spec.frequency));
+        }
+        found.sort_by(|a, b| b.n_eff.re.total_cmp(&a.n_eff.re));
+        for mode in &found {
+            profiles.push(AnalyticModeProfile {
+                label: mode.label.clone(),
+                frequency: spec.frequency,
+                n_eff: mode.n_eff.re,
+                components: mode_profile(config, spec.frequency, mode, &positions, &mut warnings),
+                positions: positions.clone(),
+            });
+        }
+    }
+
+    Ok(AnalyticModeResult { frequencies: config.frequencies.clone(), v_number, modes, profiles, warnings })
+}
+
+/// Analytic dispersion and field profiles of slab, surface-plasmon and step-index fibre modes
+#[wasm_bindgen]
+pub fn solve_analytic_modes(config: &JsValue) -> Result<JsValue, JsValue> {
+    let config: AnalyticModeConfig = serde_wasm_bindgen::from_value(config.clone())?;
+    let result = analytic_modes_internal(&config).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field_solvers::eigenmode_solver::{cross_section_modes, ModeSpec};
+
+    fn config(waveguide: AnalyticWaveguide, frequencies: Vec<f64>) -> AnalyticModeConfig {
+        AnalyticModeConfig {
+            waveguide,
+            frequencies,
+            max_modes: default_max_modes(),
+            max_azimuthal_order: default_max_azimuthal_order(),
+            profile: None,
+        }
+    }
+
+    fn branch<'a>(result: &'a AnalyticModeResult, label: &str) -> &'a AnalyticModeBranch {
+        result.modes.iter().find(|m| m.label == label).unwrap()
+    }
+
+    fn values(map: &FieldComponentMap) -> Vec<Complex64> {
+        map.real.iter().zip(&map.imag).map(|(&re, &im)| Complex64::new(re, im)).collect()
+    }
+
+    #[test]
+    fn test_slab_and_plasmon_modes() {
+        // symmetric slab against the finite-difference mode solver, which
+        // converges to it as the resolution grows
+        let slab = |cladding: Option<LayerMedium>, width: f64, frequencies: Vec<f64>| {
+            config(
+                AnalyticWaveguide::Slab {
+                    width,
+                    core: LayerMedium::constant(4.0),
+                    substrate: LayerMedium::constant(if cladding.is_some() { 2.25 } else { 1.0 }),
+                    cladding,
+                },
+                frequencies,
+            )
+        };
+        let mut cfg = slab(None, 1.0, vec![0.5]);
+        cfg.profile = Some(ModeProfileSpec { frequency: 0.5, size: 8.0, resolution: 100.0 });
+        let result = analytic_modes_internal(&cfg).unwrap();
+        let eps: Vec<f64> = (0..3200).map(|j| if (-4.0 + (j as f64 + 0.5) / 400.0).abs() < 0.5 { 4.0 } else { 1.0 }).collect();
+        for (polarization, labels) in [(FdfdPolarization::Ez, ["TE0", "TE1"]), (FdfdPolarization::Hz, ["TM0", "TM1"])] {
+            let numeric = cross_section_modes(&eps, 0.0025, polarization, ModeSpec::Frequency(0.5), 2).unwrap();
+            for (mode, label) in numeric.iter().zip(labels) {
+                assert!((branch(&result, label).n_eff[0].unwrap() - mode.n_eff).abs() < 1e-3, "{}", label);
+            }
+        }
+        // the fundamental TE profile carries unit flux −Re ∫ Ez Hy* dy
+        let te0 = result.profiles.iter().find(|p| p.label == "TE0").unwrap();
+        let (ez, hy) = (values(&te0.components[0]), values(&te0.components[2]));
+        let flux: f64 = ez.iter().zip(&hy).map(|(e, h)| -(e * h.conj()).re * 0.01).sum();
+        assert!((flux - 1.0).abs() < 1e-3);
+
+        // asymmetric slab: TE0 appears at tan(κd) = √((ε_s − ε_c)/(ε₁ − ε_s)), TM0 later
+        let cutoff = (1.25f64 / 1.75).sqrt().atan() / (2.0 * PI * 0.3 * 1.75f64.sqrt());
+        let result = analytic_modes_internal(&slab(Some(LayerMedium::constant(1.0)), 0.3, vec![0.98 * cutoff, 1.02 * cutoff])).unwrap();
+        let te0 = branch(&result, "TE0");
+        assert!(te0.n_eff[0].is_none() && te0.n_eff[1].unwrap() > 1.5);
+        assert!(result.modes.iter().all(|m| m.label == "TE0"));
+
+        // surface plasmon on a lossy metal, and none above the plasma resonance
+        let metal = LayerMedium { epsilon_imag: 1.0, ..LayerMedium::constant(-10.0) };
+        let mut cfg = config(AnalyticWaveguide::SurfacePlasmon { metal, dielectric: LayerMedium::constant(1.0) }, vec![0.5]);
+        cfg.profile = Some(ModeProfileSpec { frequency: 0.5, size: 20.0, resolution: 400.0 });
+        let result = analytic_modes_internal(&cfg).unwrap();
+        let eps_m = Complex64::new(-10.0, 1.0);
+        let expected = (eps_m / (eps_m + 1.0)).sqrt();
+        let spp = branch(&result, "SPP");
+        assert!((spp.n_eff[0].unwrap() - expected.re).abs() < 1e-12);
+        assert!((spp.n_eff_imag[0].unwrap() - expected.im).abs() < 1e-12);
+        let profile = &result.profiles[0];
+        let (hz, ey) = (values(&profile.components[0]), values(&profile.components[2]));
+        let flux: f64 = hz.iter().zip(&ey).map(|(h, e)| (e * h.conj()).re / 400.0).sum();
+        assert!((flux - 1.0).abs() < 1e-3);
+
+        let cfg = config(
+            AnalyticWaveguide::SurfacePlasmon { metal: LayerMedium::constant(-0.5), dielectric: LayerMedium::constant(1.0) },
+            vec![0.5],
+        );
+        let result = analytic_modes_internal(&cfg).unwrap();
+        assert!(result.modes.is_empty() && result.warnings.len() == 1);
+    }
+
+    #[test]
+    fn test_fiber_cutoffs_and_weak_guidance() {
+        // V = 2.3 and 2.5 straddle the J₀ zero 2.405 where the second mode group appears
+        let (n1, n2) = (1.45f64, 1.44f64);
+        let na = (n1 * n1 - n2 * n2).sqrt();
+        let frequencies = vec![2.3 / (2.0 * PI * na), 2.5 / (2.0 * PI * na)];
+        let mut cfg = config(
+            AnalyticWaveguide::Fiber {
+                radius: 1.0,
+                core: LayerMedium::constant(n1 * n1),
+                cladding: LayerMedium::constant(n2 * n2),
+            },
+            frequencies.clone(),
+        );
+        cfg.profile = Some(ModeProfileSpec { frequency: frequencies[1], size: 30.0, resolution: 200.0 });
+        let result = analytic_modes_internal(&cfg).unwrap();
+        assert!((result.v_number[0] - 2.3).abs() < 1e-12);
+        for label in ["TE01", "TM01", "HE21", "LP11"] {
+            let mode = branch(&result, label);
+            assert!(mode.n_eff[0].is_none() && mode.n_eff[1].is_some(), "{}", label);
+        }
+        assert_eq!(result.modes.len(), 6);
+        for f in 0..2 {
+            let (he11, lp01) = (branch(&result, "HE11").n_eff[f].unwrap(), branch(&result, "LP01").n_eff[f].unwrap());
+            assert!(he11 > n2 && he11 < n1);
+            assert!((he11 - lp01).abs() < 1e-4);
+        }
+        // the near-degenerate LP11 group stays within Δ² of the scalar value
+        let lp11 = branch(&result, "LP11").n_eff[1].unwrap();
+        for label in ["TE01", "TM01", "HE21"] {
+            assert!((branch(&result, label).n_eff[1].unwrap() - lp11).abs() < 1e-4);
+        }
+
+        // unit flux Θ ∫ Re(Er Hφ* − Eφ Hr*) r dr with Θ = π for ν = 1
+        let he11 = result.profiles.iter().find(|p| p.label == "HE11").unwrap();
+        let c: Vec<Vec<Complex64>> = he11.components.iter().map(values).collect();
+        let flux: f64 = PI
+            * he11
+                .positions
+                .iter()
+                .enumerate()
+                .map(|(j, r)| (c[0][j] * c[4][j].conj() - c[1][j] * c[3][j].conj()).re * r / 200.0)
+                .sum::<f64>();
+        assert!((flux - 1.0).abs() < 1e-3);
+        // across r = a, Eφ, Ez, Hφ and ε Er are continuous for every hybrid mode
+        let eps = [n1 * n1, n2 * n2];
+        for mode in modes_at(&cfg, frequencies[1], &mut Vec::new()).iter().filter(|m| m.family == FiberFamily::Hybrid) {
+            let c: Vec<Vec<Complex64>> =
+                fiber_profile(1.0, eps, frequencies[1], mode, &[1.0 - 1e-9, 1.0 + 1e-9]).iter().map(values).collect();
+            for (f, scale) in [(0, eps[1] / eps[0]), (1, 1.0), (2, 1.0), (4, 1.0), (5, 1.0)] {
+                assert!((c[f][0] - scale * c[f][1]).norm() < 1e-6, "{}", mode.label);
+            }
+        }
+    }
+}
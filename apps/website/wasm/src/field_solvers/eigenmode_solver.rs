@@ -0,0 +1,398 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+use crate::field_solvers::fdfd::FdfdPolarization;
+use crate::field_solvers::yee_grid::YeeGrid2D;
+use crate::lattice_calculations::voronoi_cells::Vector2D;
+use crate::numerical_calculations::tridiagonal_eigen::{tridiagonal_eigenvalue, tridiagonal_eigenvector};
+
+/// Mirror parity about the centre of the cross-section, defined on the
+/// electric field as in MPB: an `EVEN_Y` Ez mode has a symmetric Ez profile,
+/// an `EVEN_Y` Hz mode has an antisymmetric Hz profile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MirrorParity {
+    #[serde(rename = "EVEN_Y")]
+    Even,
+    #[serde(rename = "ODD_Y")]
+    Odd,
+}
+
+/// Parsed `eig_parity` string such as `"NO_PARITY"` or `"EVEN_Y+ODD_Z"`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EigenParity {
+    pub polarization: Option<FdfdPolarization>,
+    pub mirror: Option<MirrorParity>,
+}
+
+impl EigenParity {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut parity = EigenParity::default();
+        for token in text.split('+').map(|t| t.trim().to_uppercase()) {
+            match token.as_str() {
+                "" | "NO_PARITY" => {}
+                "EVEN_Z" | "TE" => parity.polarization = Some(FdfdPolarization::Hz),
+                "ODD_Z" | "TM" => parity.polarization = Some(FdfdPolarization::Ez),
+                "EVEN_Y" => parity.mirror = Some(MirrorParity::Even),
+                "ODD_Y" => parity.mirror = Some(MirrorParity::Odd),
+                other => return Err(format!("unknown parity '{}'", other)),
+            }
+        }
+        Ok(parity)
+    }
+}
+
+/// One guided or box mode of a 1D permittivity profile.
+#[derive(Clone, Debug, Serialize)]
+pub struct CrossSectionMode {
+    pub polarization: FdfdPolarization,
+    pub parity: Option<MirrorParity>,
+    pub frequency: f64,
+    /// Wavevector along the propagation direction in Meep units (cycles per unit length).
+    pub k: f64,
+    /// Propagation constant β = 2πk.
+    pub beta: f64,
+    pub n_eff: f64,
+    /// True when n_eff exceeds the cladding index at both ends of the line.
+    pub guided: bool,
+    /// Out-of-plane field component (Ez or Hz), unit L2 norm.
+    pub profile: Vec<f64>,
+}
+
+/// Whether the frequency or the wavevector is held fixed.
+#[derive(Clone, Copy, Debug)]
+pub enum ModeSpec {
+    /// `eig_match_freq = true`: find k at this frequency.
+    Frequency(f64),
+    /// `eig_match_freq = false`: find the frequency at this k (Meep units).
+    Wavevector(f64),
+}
+
+/// Half-point inverse permittivities with the edge pixels mirrored outward.
+fn inverse_faces(eps: &[f64]) -> Vec<f64> {
+    let n = eps.len();
+    (0..=n)
+        .map(|f| {
+            let a = eps[f.saturating_sub(1).min(n - 1)];
+            let b = eps[f.min(n - 1)];
+            0.5 * (1.0 / a + 1.0 / b)
+        })
+        .collect()
+}
+
+/// Build a symmetric tridiagonal matrix whose eigenvalues give β² (fixed ω,
+/// largest first) or ω² (fixed β, smallest first), together with the diagonal
+/// scaling that maps its eigenvectors back to the physical field.
+fn mode_matrix(eps: &[f64], dy: f64, polarization: FdfdPolarization, spec: ModeSpec) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+    let n = eps.len();
+    let inv_dy2 = 1.0 / (dy * dy);
+    let faces = inverse_faces(eps);
+    let mut d = vec![0.0; n];
+    let mut e = vec![0.0; n.saturating_sub(1)];
+    let mut back = vec![1.0; n];
+
+    match (polarization, spec) {
+        // (∂² + ω²ε) Ez = β² Ez
+        (FdfdPolarization::Ez, ModeSpec::Frequency(f)) => {
+            let w2 = (2.0 * PI * f).powi(2);
+            for i in 0..n {
+                d[i] = w2 * eps[i] - 2.0 * inv_dy2;
+            }
+            e.iter_mut().for_each(|v| *v = inv_dy2);
+        }
+        // ε^{-1/2} (-∂² + β²) ε^{-1/2} u = ω² u, Ez = ε^{-1/2} u
+        (FdfdPolarization::Ez, ModeSpec::Wavevector(k)) => {
+            let b2 = (2.0 * PI * k).powi(2);
+            for i in 0..n {
+                d[i] = (2.0 * inv_dy2 + b2) / eps[i];
+                back[i] = 1.0 / eps[i].sqrt();
+            }
+            for i in 0..n - 1 {
+                e[i] = -inv_dy2 / (eps[i] * eps[i + 1]).sqrt();
+            }
+        }
+        // ε^{1/2} (∂ ε⁻¹ ∂ + ω²) ε^{1/2} u = β² u, Hz = ε^{1/2} u
+        (FdfdPolarization::Hz, ModeSpec::Frequency(f)) => {
+            let w2 = (2.0 * PI * f).powi(2);
+            for i in 0..n {
+                d[i] = eps[i] * (w2 - (faces[i] + faces[i + 1]) * inv_dy2);
+                back[i] = eps[i].sqrt();
+            }
+            for i in 0..n - 1 {
+                e[i] = (eps[i] * eps[i + 1]).sqrt() * faces[i + 1] * inv_dy2;
+            }
+        }
+        // (-∂ ε⁻¹ ∂ + β²/ε) Hz = ω² Hz
+        (FdfdPolarization::Hz, ModeSpec::Wavevector(k)) => {
+            let b2 = (2.0 * PI * k).powi(2);
+            for i in 0..n {
+                d[i] = (faces[i] + faces[i + 1]) * inv_dy2 + b2 / eps[i];
+            }
+            for i in 0..n - 1 {
+                e[i] = -faces[i + 1] * inv_dy2;
+            }
+        }
+    }
+    (d, e, back)
+}
+
+fn mirror_parity(profile: &[f64], polarization: FdfdPolarization) -> MirrorParity {
+    let overlap: f64 = profile.iter().zip(profile.iter().rev()).map(|(a, b)| a * b).sum();
+    // Hz is a pseudo-scalar under the y mirror, so its symmetry is flipped
+    let symmetric = overlap >= 0.0;
+    match (polarization, symmetric) {
+        (FdfdPolarization::Ez, true) | (FdfdPolarization::Hz, false) => MirrorParity::Even,
+        _ => MirrorParity::Odd,
+    }
+}
+
+/// Solve for the modes of a single polarisation, ordered by band (1 = lowest frequency).
+pub fn cross_section_modes(
+    eps: &[f64],
+    dy: f64,
+    polarization: FdfdPolarization,
+    spec: ModeSpec,
+    count: usize,
+) -> Result<Vec<CrossSectionMode>, String> {
+    let n = eps.len();
+    if n < 3 {
+        return Err("cross-section needs at least three samples".into());
+    }
+    if eps.iter().any(|&e| e <= 0.0) {
+        return Err("mode solver requires positive permittivity along the line".into());
+    }
+    let (d, e, back) = mode_matrix(eps, dy, polarization, spec);
+    let cladding = eps[0].min(eps[n - 1]).sqrt();
+
+    let mut modes = Vec::new();
+    for band in 0..count.min(n) {
+        let k_index = match spec {
+            ModeSpec::Frequency(_) => n - 1 - band,
+            ModeSpec::Wavevector(_) => band,
+        };
+        let lambda = tridiagonal_eigenvalue(&d, &e, k_index)?;
+        let (frequency, k) = match spec {
+            ModeSpec::Frequency(f) => {
+                if lambda <= 0.0 {
+                    break; // remaining modes are evanescent along the propagation axis
+                }
+                (f, lambda.sqrt() / (2.0 * PI))
+            }
+            ModeSpec::Wavevector(k) => (lambda.max(0.0).sqrt() / (2.0 * PI), k),
+        };
+
+        let raw = tridiagonal_eigenvector(&d, &e, lambda)?;
+        let mut profile: Vec<f64> = raw.iter().zip(&back).map(|(v, s)| v * s).collect();
+        let norm = profile.iter().map(|v| v * v).sum::<f64>().sqrt();
+        // fix the sign so the largest lobe is positive
+        let peak = profile.iter().cloned().fold(0.0f64, |m, v| if v.abs() > m.abs() { v } else { m });
+        let sign = if peak < 0.0 { -1.0 } else { 1.0 };
+        profile.iter_mut().for_each(|v| *v *= sign / norm);
+
+        let n_eff = if frequency > 0.0 { k / frequency } else { 0.0 };
+        modes.push(CrossSectionMode {
+            polarization,
+            parity: Some(mirror_parity(&profile, polarization)),
+            frequency,
+            k,
+            beta: 2.0 * PI * k,
+            n_eff,
+            guided: n_eff > cladding,
+            profile,
+        });
+    }
+    Ok(modes)
+}
+
+/// Pick mode `band` (1-based) satisfying `parity`, merging both polarisations
+/// when no z-parity is requested.
+pub fn find_eigenmode(
+    eps: &[f64],
+    dy: f64,
+    spec: ModeSpec,
+    band: usize,
+    parity: EigenParity,
+) -> Result<CrossSectionMode, String> {
+    if band == 0 {
+        return Err("eig_band starts at 1".into());
+    }
+    let polarizations = match parity.polarization {
+        Some(p) => vec![p],
+        None => vec![FdfdPolarization::Ez, FdfdPolarization::Hz],
+    };
+    // parity filtering discards roughly half of the candidates
+    let count = if parity.mirror.is_some() { 2 * band + 2 } else { band };
+
+    let mut candidates = Vec::new();
+    for polarization in polarizations {
+        candidates.extend(cross_section_modes(eps, dy, polarization, spec, count)?);
+    }
+    if let Some(mirror) = parity.mirror {
+        candidates.retain(|m| m.parity == Some(mirror));
+    } else {
+        candidates.iter_mut().for_each(|m| m.parity = None);
+    }
+    match spec {
+        ModeSpec::Frequency(_) => candidates.sort_by(|a, b| b.k.total_cmp(&a.k)),
+        ModeSpec::Wavevector(_) => candidates.sort_by(|a, b| a.frequency.total_cmp(&b.frequency)),
+    }
+    candidates
+        .into_iter()
+        .nth(band - 1)
+        .ok_or_else(|| format!("band {} with the requested parity was not found", band))
+}
+
+fn default_band() -> usize {
+    1
+}
+
+fn default_parity() -> String {
+    "NO_PARITY".into()
+}
+
+fn default_match_freq() -> bool {
+    true
+}
+
+fn zero_kpoint() -> Vector2D {
+    Vector2D::new(0.0, 0.0)
+}
+
+/// Inputs of an `EigenModeSource` preview: the rasterised cell plus the
+/// source line (one of `size.x`, `size.y` must be zero).
+#[derive(Deserialize)]
+pub struct EigenmodeConfig {
+    pub grid: YeeGrid2D,
+    pub epsilon: Vec<f64>,
+    pub center: Vector2D,
+    pub size: Vector2D,
+    pub frequency: f64,
+    #[serde(default = "default_band")]
+    pub eig_band: usize,
+    #[serde(default = "default_parity")]
+    pub eig_parity: String,
+    #[serde(default = "default_match_freq")]
+    pub eig_match_freq: bool,
+    #[serde(default = "zero_kpoint")]
+    pub eig_kpoint: Vector2D,
+}
+
+#[derive(Serialize)]
+pub struct EigenmodeResult {
+    pub mode: CrossSectionMode,
+    /// Positions along the source line of each profile sample.
+    pub positions: Vec<f64>,
+    /// Sampled permittivity along the source line.
+    pub epsilon: Vec<f64>,
+}
+
+/// Sample the rasterised permittivity along a source line; returns the
+/// positions, ε values and the component of `kpoint` normal to the line.
+pub fn sample_source_line(
+    grid: &YeeGrid2D,
+    epsilon: &[f64],
+    center: Vector2D,
+    size: Vector2D,
+    kpoint: Vector2D,
+) -> Result<(Vec<f64>, Vec<f64>, f64), String> {
+    grid.validate()?;
+    if epsilon.len() != grid.pixel_count() {
+        return Err("epsilon does not match the grid size".into());
+    }
+    let (ci, cj) = grid.nearest_pixel(center);
+    let within = |coord: f64, c: f64, s: f64| (coord - c).abs() <= 0.5 * s + 1e-9;
+
+    if size.x == 0.0 && size.y > 0.0 {
+        let rows: Vec<usize> = (0..grid.ny()).filter(|&j| within(grid.y(j as f64), center.y, size.y)).collect();
+        let positions = rows.iter().map(|&j| grid.y(j as f64)).collect();
+        let eps = rows.iter().map(|&j| epsilon[grid.index(ci, j)]).collect();
+        Ok((positions, eps, kpoint.x))
+    } else if size.y == 0.0 && size.x > 0.0 {
+        let cols: Vec<usize> = (0..grid.nx()).filter(|&i| within(grid.x(i as f64), center.x, size.x)).collect();
+        let positions = cols.iter().map(|&i| grid.x(i as f64)).collect();
+        let eps = cols.iter().map(|&i| epsilon[grid.index(i, cj)]).collect();
+        Ok((positions, eps, kpoint.y))
+    } else {
+        Err("eigenmode source must be a line in 2D".into())
+    }
+}
+
+pub fn solve_eigenmode_internal(config: &EigenmodeConfig) -> Result<EigenmodeResult, String> {
+    let (positions, epsilon, k_normal) =
+        sample_source_line(&config.grid, &config.epsilon, config.center, config.size, config.eig_kpoint)?;
+    let spec = if config.eig_match_freq {
+        ModeSpec::Frequency(config.frequency)
+    } else {
+        ModeSpec::Wavevector(k_normal)
+    };
+    let parity = EigenParity::parse(&config.eig_parity)?;
+    let mode = find_eigenmode(&epsilon, config.grid.dx(), spec, config.eig_band, parity)?;
+    Ok(EigenmodeResult { mode, positions, epsilon })
+}
+
+/// Solve the waveguide mode excited by an EigenModeSource in a 2D cell
+#[wasm_bindgen]
+pub fn solve_eigenmode_2d(config: &JsValue) -> Result<JsValue, JsValue> {
+    let config: EigenmodeConfig = serde_wasm_bindgen::from_value(config.clone())?;
+    let result = solve_eigenmode_internal(&config).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slab_profile(n_core: f64, n_clad: f64, width: f64, span: f64, resolution: f64) -> Vec<f64> {
+        let n = (span * resolution).round() as usize;
+        (0..n)
+            .map(|i| {
+                let y = -0.5 * span + (i as f64 + 0.5) / resolution;
+                if y.abs() <= 0.5 * width { n_core * n_core } else { n_clad * n_clad }
+            })
+            .collect()
+    }
+
+    // symmetric slab TE/TM dispersion: tan(κw/2) = r γ/κ (even) or -r κ/γ (odd)
+    fn slab_mismatch(n_eff: f64, f: f64, n1: f64, n2: f64, w: f64, ratio: f64, even: bool) -> f64 {
+        let k0 = 2.0 * PI * f;
+        let kappa = k0 * (n1 * n1 - n_eff * n_eff).sqrt();
+        let gamma = k0 * (n_eff * n_eff - n2 * n2).sqrt();
+        let t = (0.5 * kappa * w).tan();
+        if even { t - ratio * gamma / kappa } else { t + ratio * kappa / gamma }
+    }
+
+    #[test]
+    fn test_fundamental_ez_mode_matches_slab_theory() {
+        let (n1, n2, w, f) = (3.4, 1.44, 0.5, 1.0 / 1.55);
+        let eps = slab_profile(n1, n2, w, 6.0, 80.0);
+        let parity = EigenParity::parse("EVEN_Y+ODD_Z").unwrap();
+        let mode = find_eigenmode(&eps, 1.0 / 80.0, ModeSpec::Frequency(f), 1, parity).unwrap();
+        assert!(mode.guided);
+        assert_eq!(mode.polarization, FdfdPolarization::Ez);
+        // the residual of the analytic equation changes sign near the numerical n_eff
+        let lo = slab_mismatch(mode.n_eff - 0.01, f, n1, n2, w, 1.0, true);
+        let hi = slab_mismatch(mode.n_eff + 0.01, f, n1, n2, w, 1.0, true);
+        assert!(lo * hi < 0.0, "n_eff = {}", mode.n_eff);
+    }
+
+    #[test]
+    fn test_parity_selection_and_round_trip() {
+        let eps = slab_profile(2.0, 1.0, 1.0, 8.0, 40.0);
+        let f = 0.8;
+        let odd = find_eigenmode(&eps, 1.0 / 40.0, ModeSpec::Frequency(f), 1, EigenParity::parse("ODD_Y+ODD_Z").unwrap()).unwrap();
+        let even = find_eigenmode(&eps, 1.0 / 40.0, ModeSpec::Frequency(f), 1, EigenParity::parse("EVEN_Y+ODD_Z").unwrap()).unwrap();
+        assert!(even.k > odd.k);
+        assert_eq!(odd.parity, Some(MirrorParity::Odd));
+
+        // solving at the returned k recovers the original frequency
+        let back = find_eigenmode(&eps, 1.0 / 40.0, ModeSpec::Wavevector(odd.k), 1, EigenParity::parse("ODD_Y+ODD_Z").unwrap()).unwrap();
+        assert!((back.frequency - f).abs() < 1e-6);
+
+        let te = find_eigenmode(&eps, 1.0 / 40.0, ModeSpec::Frequency(f), 1, EigenParity::parse("ODD_Y+EVEN_Z").unwrap()).unwrap();
+        let te_back = find_eigenmode(&eps, 1.0 / 40.0, ModeSpec::Wavevector(te.k), 1, EigenParity::parse("ODD_Y+EVEN_Z").unwrap()).unwrap();
+        assert!((te_back.frequency - f).abs() < 1e-6);
+        // fundamental TE mode has a symmetric Hz profile
+        assert_eq!(te.polarization, FdfdPolarization::Hz);
+        assert!(te.k < even.k);
+    }
+}
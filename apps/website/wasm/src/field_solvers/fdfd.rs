@@ -0,0 +1,576 @@
+use num_complex::Complex64;
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+use crate::field_solvers::yee_grid::YeeGrid2D;
+use crate::lattice_calculations::matrix_calculations::Vector3D;
+use crate::lattice_calculations::voronoi_cells::Vector2D;
+use crate::material_calculations::tensor_permittivity::MaterialTensor;
+use crate::numerical_calculations::banded_solver::{BandedLu, BandedMatrix};
+
+/// Field polarisation of a 2D simulation, named after the out-of-plane component.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FdfdPolarization {
+    /// Ez, Hx, Hy (Meep `ODD_Z`), driven by an electric current Jz.
+    Ez,
+    /// Hz, Ex, Ey (Meep `EVEN_Z`), driven by a magnetic current Kz.
+    Hz,
+}
+
+/// Stretched-coordinate PML applied on all four edges of the cell.
+///
+/// The conductivity follows Meep's profile σ(u) = σ₀ uᵖ with σ₀ chosen so that
+/// the round-trip reflection at normal incidence equals `R_asymptotic`.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct PmlSpec {
+    pub thickness: f64,
+    #[serde(default = "default_pml_power")]
+    pub power: f64,
+    #[serde(rename = "R_asymptotic", default = "default_r_asymptotic")]
+    pub r_asymptotic: f64,
+}
+
+fn default_pml_power() -> f64 {
+    2.0
+}
+
+fn default_r_asymptotic() -> f64 {
+    1e-15
+}
+
+impl PmlSpec {
+    /// Peak conductivity σ₀ = -(p + 1) ln R / (2 d).
+    pub fn sigma_max(&self) -> f64 {
+        if self.thickness <= 0.0 {
+            return 0.0;
+        }
+        -(self.power + 1.0) * self.r_asymptotic.ln() / (2.0 * self.thickness)
+    }
+
+    /// Conductivity at coordinate `x` on an axis of length `length` centred at 0.
+    pub fn sigma(&self, x: f64, length: f64) -> f64 {
+        if self.thickness <= 0.0 {
+            return 0.0;
+        }
+        let depth = (self.thickness - (x + 0.5 * length)).max(x - 0.5 * length + self.thickness);
+        self.sigma_at_depth(depth)
+    }
+
+    /// Conductivity at a distance `depth` into the layer from its inner edge.
+    pub fn sigma_at_depth(&self, depth: f64) -> f64 {
+        if self.thickness <= 0.0 {
+            return 0.0;
+        }
+        let u = (depth / self.thickness).clamp(0.0, 1.0);
+        self.sigma_max() * u.powf(self.power)
+    }
+
+    /// Complex stretch factor s = 1 + iσ/ω for fields ∝ exp(-iωt).
+    pub fn stretch(&self, x: f64, length: f64, omega: f64) -> Complex64 {
+        Complex64::new(1.0, self.sigma(x, length) / omega)
+    }
+}
+
+/// Rectangular current source in the style of Meep's `Source(center, size, amplitude)`.
+///
+/// Zero-size directions are treated as δ-functions, i.e. the amplitude is
+/// divided by the grid spacing along each of them.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FdfdSource {
+    pub center: Vector2D,
+    #[serde(default = "zero_vector")]
+    pub size: Vector2D,
+    #[serde(default = "unit_amplitude")]
+    pub amplitude_real: f64,
+    #[serde(default)]
+    pub amplitude_imag: f64,
+}
+
+fn zero_vector() -> Vector2D {
+    Vector2D::new(0.0, 0.0)
+}
+
+fn unit_amplitude() -> f64 {
+    1.0
+}
+
+/// Map between grid indices and unknowns; the shorter axis runs fastest to
+/// keep the bandwidth of the system matrix minimal.
+#[derive(Clone, Copy, Debug)]
+pub struct GridOrdering {
+    nx: usize,
+    ny: usize,
+    x_fast: bool,
+}
+
+impl GridOrdering {
+    pub fn new(grid: &YeeGrid2D) -> Self {
+        let (nx, ny) = (grid.nx(), grid.ny());
+        Self { nx, ny, x_fast: nx <= ny }
+    }
+
+    pub fn bandwidth(&self) -> usize {
+        if self.x_fast { self.nx } else { self.ny }
+    }
+
+    pub fn unknown(&self, i: usize, j: usize) -> usize {
+        if self.x_fast { j * self.nx + i } else { i * self.ny + j }
+    }
+
+    /// Reorder a grid-indexed vector (`j * nx + i`) into unknown order.
+    pub fn to_unknowns(&self, v: &[Complex64]) -> Vec<Complex64> {
+        let mut out = vec![Complex64::new(0.0, 0.0); v.len()];
+        for j in 0..self.ny {
+            for i in 0..self.nx {
+                out[self.unknown(i, j)] = v[j * self.nx + i];
+            }
+        }
+        out
+    }
+
+    /// Reorder an unknown-ordered vector back into grid order.
+    pub fn to_grid(&self, v: &[Complex64]) -> Vec<Complex64> {
+        let mut out = vec![Complex64::new(0.0, 0.0); v.len()];
+        for j in 0..self.ny {
+            for i in 0..self.nx {
+                out[j * self.nx + i] = v[self.unknown(i, j)];
+            }
+        }
+        out
+    }
+}
+
+/// A single-frequency 2D problem: grid, rasterised permittivity and PML.
+#[derive(Clone, Debug)]
+pub struct FdfdSimulation {
+    pub grid: YeeGrid2D,
+    pub frequency: f64,
+    pub polarization: FdfdPolarization,
+    pub pml: PmlSpec,
+    /// Relative permittivity per pixel in grid order.
+    pub epsilon: Vec<Complex64>,
+    /// Lossless anisotropic permittivity per pixel in grid order, replacing
+    /// `epsilon` when non-empty. Couplings to z are dropped: Ez sees ε_zz
+    /// and Hz the in-plane block of ε⁻¹.
+    pub anisotropy: Vec<MaterialTensor>,
+}
+
+/// Factorised system matrix, reusable for many right-hand sides.
+pub struct FdfdFactorization {
+    ordering: GridOrdering,
+    lu: BandedLu,
+}
+
+impl FdfdFactorization {
+    /// Solve `A x = b` with `b` and `x` in grid order.
+    pub fn solve(&self, rhs: &[Complex64]) -> Vec<Complex64> {
+        self.ordering.to_grid(&self.lu.solve(&self.ordering.to_unknowns(rhs)))
+    }
+
+    /// Solve `Aᵀ x = b` with `b` and `x` in grid order.
+    pub fn solve_transpose(&self, rhs: &[Complex64]) -> Vec<Complex64> {
+        self.ordering.to_grid(&self.lu.solve_transpose(&self.ordering.to_unknowns(rhs)))
+    }
+}
+
+impl FdfdSimulation {
+    pub fn omega(&self) -> f64 {
+        2.0 * PI * self.frequency
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        self.grid.validate()?;
+        if self.frequency <= 0.0 {
+            return Err("frequency must be positive".into());
+        }
+        let (name, length) = if self.anisotropy.is_empty() {
+            ("epsilon", self.epsilon.len())
+        } else {
+            ("anisotropy", self.anisotropy.len())
+        };
+        if length != self.grid.pixel_count() {
+            return Err(format!("{} has {} entries but the grid has {} pixels", name, length, self.grid.pixel_count()));
+        }
+        Ok(())
+    }
+
+    /// Per-pixel (b, a_xx, a_yy, a_xy) of the operator below.
+    fn coefficients(&self) -> Result<Vec<[Complex64; 4]>, String> {
+        let one = Complex64::new(1.0, 0.0);
+        let zero = Complex64::new(0.0, 0.0);
+        if self.anisotropy.is_empty() {
+            return Ok(self
+                .epsilon
+                .iter()
+                .map(|&e| match self.polarization {
+                    FdfdPolarization::Ez => [e, one, one, zero],
+                    FdfdPolarization::Hz => [one, 1.0 / e, 1.0 / e, zero],
+                })
+                .collect());
+        }
+        self.anisotropy
+            .iter()
+            .map(|tensor| {
+                Ok(match self.polarization {
+                    FdfdPolarization::Ez => [tensor.matrix.data[2][2].into(), one, one, zero],
+                    FdfdPolarization::Hz => {
+                        // D = (i/ω)(∂y Hz, −∂x Hz) and E = ε⁻¹ D, so a is ε⁻¹
+                        // turned by 90°: (η_yy, η_xx, −η_xy)
+                        let eta = tensor.inverse()?.matrix.data;
+                        [one, eta[1][1].into(), eta[0][0].into(), (-eta[0][1]).into()]
+                    }
+                })
+            })
+            .collect()
+    }
+
+    /// Assemble the operator `-∇·(a∇) - ω² b` in stretched coordinates, with
+    /// (a, b) = (1, ε) for Ez and (1/ε, 1) for Hz. Fields vanish outside the cell.
+    /// The off-diagonal a_xy of an anisotropic Hz problem couples diagonal
+    /// neighbours through centred differences.
+    pub fn assemble(&self) -> Result<(BandedMatrix, GridOrdering), String> {
+        self.validate()?;
+        let grid = &self.grid;
+        let (nx, ny) = (grid.nx(), grid.ny());
+        let ordering = GridOrdering::new(grid);
+        let coefficients = self.coefficients()?;
+        let mixed = coefficients.iter().any(|c| c[3] != Complex64::new(0.0, 0.0));
+        let band = ordering.bandwidth() + usize::from(mixed);
+        let mut a = BandedMatrix::new(nx * ny, band, band);
+
+        let omega = self.omega();
+        let inv_dx2 = 1.0 / (grid.dx() * grid.dx());
+
+        // stretch factors on integer and half-integer positions
+        let sx: Vec<Complex64> = (0..nx).map(|i| self.pml.stretch(grid.x(i as f64), grid.size_x, omega)).collect();
+        let sxh: Vec<Complex64> = (0..nx).map(|i| self.pml.stretch(grid.x(i as f64 + 0.5), grid.size_x, omega)).collect();
+        let sy: Vec<Complex64> = (0..ny).map(|j| self.pml.stretch(grid.y(j as f64), grid.size_y, omega)).collect();
+        let syh: Vec<Complex64> = (0..ny).map(|j| self.pml.stretch(grid.y(j as f64 + 0.5), grid.size_y, omega)).collect();
+
+        let coefficient = |i: usize, j: usize, k: usize| coefficients[grid.index(i, j)][k];
+        // coefficient of the divergence term between two pixels
+        let face = |p: Complex64, q: Complex64| 0.5 * (p + q);
+
+        for j in 0..ny {
+            for i in 0..nx {
+                let row = ordering.unknown(i, j);
+                let (ax, ay) = (coefficient(i, j, 1), coefficient(i, j, 2));
+
+                let cx_plus = face(ax, if i + 1 < nx { coefficient(i + 1, j, 1) } else { ax }) * inv_dx2 / (sx[i] * sxh[i]);
+                let cx_minus = face(ax, if i > 0 { coefficient(i - 1, j, 1) } else { ax }) * inv_dx2
+                    / (sx[i] * if i > 0 { sxh[i - 1] } else { self.pml.stretch(grid.x(i as f64 - 0.5), grid.size_x, omega) });
+                let cy_plus = face(ay, if j + 1 < ny { coefficient(i, j + 1, 2) } else { ay }) * inv_dx2 / (sy[j] * syh[j]);
+                let cy_minus = face(ay, if j > 0 { coefficient(i, j - 1, 2) } else { ay }) * inv_dx2
+                    / (sy[j] * if j > 0 { syh[j - 1] } else { self.pml.stretch(grid.y(j as f64 - 0.5), grid.size_y, omega) });
+
+                let mass = omega * omega * coefficient(i, j, 0);
+                a.add(row, row, cx_plus + cx_minus + cy_plus + cy_minus - mass)?;
+                if mixed {
+                    // −∂x(a_xy ∂y H) − ∂y(a_xy ∂x H) onto the four diagonal neighbours
+                    let scale = -0.25 * inv_dx2 / (sx[i] * sy[j]);
+                    for (di, dj) in [(1isize, 1isize), (1, -1), (-1, 1), (-1, -1)] {
+                        let (ni, nj) = (i as isize + di, j as isize + dj);
+                        if ni < 0 || nj < 0 || ni >= nx as isize || nj >= ny as isize {
+                            continue;
+                        }
+                        let (ni, nj) = (ni as usize, nj as usize);
+                        let a_xy = coefficient(ni, j, 3) + coefficient(i, nj, 3);
+                        a.add(row, ordering.unknown(ni, nj), scale * (di * dj) as f64 * a_xy)?;
+                    }
+                }
+                if i + 1 < nx {
+                    a.add(row, ordering.unknown(i + 1, j), -cx_plus)?;
+                }
+                if i > 0 {
+                    a.add(row, ordering.unknown(i - 1, j), -cx_minus)?;
+                }
+                if j + 1 < ny {
+                    a.add(row, ordering.unknown(i, j + 1), -cy_plus)?;
+                }
+                if j > 0 {
+                    a.add(row, ordering.unknown(i, j - 1), -cy_minus)?;
+                }
+            }
+        }
+        Ok((a, ordering))
+    }
+
+    pub fn factor(&self) -> Result<FdfdFactorization, String> {
+        let (a, ordering) = self.assemble()?;
+        Ok(FdfdFactorization { ordering, lu: a.factor()? })
+    }
+
+    /// Right-hand side iω J for a current density given in grid order.
+    pub fn rhs(&self, current: &[Complex64]) -> Vec<Complex64> {
+        let i_omega = Complex64::new(0.0, self.omega());
+        current.iter().map(|&j| i_omega * j).collect()
+    }
+}
+
+/// Deposit Meep-style region sources onto the grid as a current density.
+pub fn rasterize_sources(grid: &YeeGrid2D, sources: &[FdfdSource]) -> Vec<Complex64> {
+    let mut current = vec![Complex64::new(0.0, 0.0); grid.pixel_count()];
+    let dx = grid.dx();
+
+    for source in sources {
+        let amplitude = Complex64::new(source.amplitude_real, source.amplitude_imag);
+        let (ci, cj) = grid.nearest_pixel(source.center);
+
+        let span = |center: f64, size: f64, n: usize, coord: &dyn Fn(f64) -> f64, nearest: usize| {
+            if size <= 0.0 {
+                return (vec![nearest], 1.0 / dx);
+            }
+            let cells: Vec<usize> = (0..n)
+                .filter(|&k| (coord(k as f64) - center).abs() <= 0.5 * size + 1e-12)
+                .collect();
+            if cells.is_empty() { (vec![nearest], 1.0 / dx) } else { (cells, 1.0) }
+        };
+        let (xs, fx) = span(source.center.x, source.size.x, grid.nx(), &|i| grid.x(i), ci);
+        let (ys, fy) = span(source.center.y, source.size.y, grid.ny(), &|j| grid.y(j), cj);
+
+        for &j in &ys {
+            for &i in &xs {
+                current[grid.index(i, j)] += amplitude * fx * fy;
+            }
+        }
+    }
+    current
+}
+
+#[derive(Deserialize)]
+pub struct FdfdConfig {
+    pub grid: YeeGrid2D,
+    /// Frequency of the `ContinuousSource` driving the simulation.
+    pub frequency: f64,
+    pub polarization: FdfdPolarization,
+    #[serde(default)]
+    pub epsilon: Vec<f64>,
+    #[serde(default)]
+    pub epsilon_imag: Vec<f64>,
+    /// Per-pixel anisotropic ε in Meep's diag/offdiag form, used instead of
+    /// `epsilon` when given; an empty `epsilon_offdiag` means a diagonal tensor.
+    #[serde(default)]
+    pub epsilon_diag: Vec<Vector3D>,
+    #[serde(default)]
+    pub epsilon_offdiag: Vec<Vector3D>,
+    pub pml: PmlSpec,
+    #[serde(default)]
+    pub sources: Vec<FdfdSource>,
+    /// Optional explicit current distribution in grid order (added to `sources`).
+    #[serde(default)]
+    pub current_real: Vec<f64>,
+    #[serde(default)]
+    pub current_imag: Vec<f64>,
+}
+
+#[derive(Serialize)]
+pub struct FdfdResult {
+    pub nx: usize,
+    pub ny: usize,
+    pub frequency: f64,
+    pub polarization: FdfdPolarization,
+    pub real: Vec<f64>,
+    pub imag: Vec<f64>,
+}
+
+/// Combine real and imaginary parts, treating a missing imaginary array as zero.
+pub fn complex_from_parts(real: &[f64], imag: &[f64]) -> Vec<Complex64> {
+    real.iter()
+        .enumerate()
+        .map(|(k, &re)| Complex64::new(re, imag.get(k).copied().unwrap_or(0.0)))
+        .collect()
+}
+
+/// Pair per-pixel diagonal and off-diagonal entries into tensors.
+pub fn anisotropy_from_parts(diag: &[Vector3D], offdiag: &[Vector3D]) -> Result<Vec<MaterialTensor>, String> {
+    if !offdiag.is_empty() && offdiag.len() != diag.len() {
+        return Err("epsilon_offdiag must be empty or match epsilon_diag".into());
+    }
+    Ok(diag
+        .iter()
+        .enumerate()
+        .map(|(k, &d)| MaterialTensor::from_meep(d, offdiag.get(k).copied().unwrap_or(Vector3D::new(0.0, 0.0, 0.0))))
+        .collect())
+}
+
+pub fn solve_fdfd_internal(config: &FdfdConfig) -> Result<FdfdResult, String> {
+    let simulation = FdfdSimulation {
+        grid: config.grid,
+        frequency: config.frequency,
+        polarization: config.polarization,
+        pml: config.pml,
+        epsilon: complex_from_parts(&config.epsilon, &config.epsilon_imag),
+        anisotropy: anisotropy_from_parts(&config.epsilon_diag, &config.epsilon_offdiag)?,
+    };
+
+    let mut current = rasterize_sources(&config.grid, &config.sources);
+    if !config.current_real.is_empty() {
+        if config.current_real.len() != current.len() {
+            return Err("current distribution does not match the grid size".into());
+        }
+        for (c, extra) in current.iter_mut().zip(complex_from_parts(&config.current_real, &config.current_imag)) {
+            *c += extra;
+        }
+    }
+
+    let field = simulation.factor()?.solve(&simulation.rhs(&current));
+    Ok(FdfdResult {
+        nx: config.grid.nx(),
+        ny: config.grid.ny(),
+        frequency: config.frequency,
+        polarization: config.polarization,
+        real: field.iter().map(|c| c.re).collect(),
+        imag: field.iter().map(|c| c.im).collect(),
+    })
+}
+
+/// Solve a 2D continuous-wave problem in the frequency domain
+#[wasm_bindgen]
+pub fn solve_fdfd_2d(config: &JsValue) -> Result<JsValue, JsValue> {
+    let config: FdfdConfig = serde_wasm_bindgen::from_value(config.clone())?;
+    let result = solve_fdfd_internal(&config).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vacuum_simulation(polarization: FdfdPolarization) -> FdfdSimulation {
+        let grid = YeeGrid2D::new(8.1, 8.1, 10.0);
+        FdfdSimulation {
+            grid,
+            frequency: 1.0,
+            polarization,
+            pml: PmlSpec { thickness: 1.5, power: 2.0, r_asymptotic: 1e-12 },
+            epsilon: vec![Complex64::new(1.0, 0.0); grid.pixel_count()],
+            anisotropy: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_point_source_is_symmetric_and_decays() {
+        let sim = vacuum_simulation(FdfdPolarization::Ez);
+        let grid = sim.grid;
+        let source = FdfdSource {
+            center: Vector2D::new(0.0, 0.0),
+            size: zero_vector(),
+            amplitude_real: 1.0,
+            amplitude_imag: 0.0,
+        };
+        let current = rasterize_sources(&grid, &[source]);
+        let field = sim.factor().unwrap().solve(&sim.rhs(&current));
+
+        let (ci, cj) = grid.nearest_pixel(Vector2D::new(0.0, 0.0));
+        let at = |i: usize, j: usize| field[grid.index(i, j)];
+        // four-fold symmetry around the source pixel
+        assert!((at(ci + 10, cj) - at(ci - 10, cj)).norm() < 1e-8);
+        assert!((at(ci + 10, cj) - at(ci, cj + 10)).norm() < 1e-8);
+        // a cylindrical wave decays like 1/sqrt(r) in the interior
+        let near = at(ci + 5, cj).norm();
+        let far = at(ci + 20, cj).norm();
+        assert!(far < near);
+        assert!((far / near - (5.0f64 / 20.0).sqrt()).abs() < 0.15);
+        // and is strongly absorbed in the PML
+        assert!(at(1, cj).norm() < 0.05 * far);
+    }
+
+    #[test]
+    fn test_line_source_plane_wave_wavelength() {
+        // a line source in a uniform medium launches plane waves whose phase
+        // advance matches the discrete dispersion relation sin(k̃Δ/2) = kΔ/2
+        let mut sim = vacuum_simulation(FdfdPolarization::Hz);
+        let n: f64 = 1.5;
+        sim.epsilon = vec![Complex64::new(n * n, 0.0); sim.grid.pixel_count()];
+        sim.pml.thickness = 1.0;
+        let grid = sim.grid;
+        let source = FdfdSource {
+            center: Vector2D::new(-2.0, 0.0),
+            size: Vector2D::new(0.0, 8.0),
+            amplitude_real: 1.0,
+            amplitude_imag: 0.0,
+        };
+        let current = rasterize_sources(&grid, &[source]);
+        let field = sim.factor().unwrap().solve(&sim.rhs(&current));
+
+        let j = grid.ny() / 2;
+        let (i0, _) = grid.nearest_pixel(Vector2D::new(-1.0, 0.0));
+        let (i1, _) = grid.nearest_pixel(Vector2D::new(1.0, 0.0));
+        let phase = (field[grid.index(i1, j)] / field[grid.index(i0, j)]).arg();
+        let dx = grid.dx();
+        let k_discrete = 2.0 / dx * (PI * n * dx).asin();
+        let expected = k_discrete * (i1 - i0) as f64 * dx;
+        let wrapped = (phase - expected).rem_euclid(2.0 * PI);
+        let error = wrapped.min(2.0 * PI - wrapped);
+        assert!(error < 0.05, "phase error {}", error);
+    }
+
+    #[test]
+    fn test_rotated_uniaxial_medium_follows_extraordinary_index() {
+        use crate::lattice_calculations::matrix_calculations::Matrix3x3;
+        // optic axis c = (cos 30°, sin 30°, 0): a wave along φ has D along
+        // t = (−sin φ, cos φ) and 1/n² = 1/ε_o + (1/ε_e − 1/ε_o)(c·t)²
+        let (eps_o, eps_e) = (2.25, 4.0);
+        let rotation = Matrix3x3::rotation(Vector3D::new(0.0, 0.0, 1.0), 30f64.to_radians()).unwrap();
+        let tensor = MaterialTensor::from_principal([eps_e, eps_o, eps_o], &rotation);
+        let index = |phi: f64| {
+            let ct = -30f64.to_radians().cos() * phi.sin() + 30f64.to_radians().sin() * phi.cos();
+            (1.0 / eps_o + (1.0 / eps_e - 1.0 / eps_o) * ct * ct).powf(-0.5)
+        };
+
+        // a line source along y: Hz sees 1/η_yy rather than ε_yy, Ez sees ε_zz
+        for (polarization, n) in [(FdfdPolarization::Hz, index(0.0)), (FdfdPolarization::Ez, eps_o.sqrt())] {
+            let mut sim = vacuum_simulation(polarization);
+            sim.anisotropy = vec![tensor; sim.grid.pixel_count()];
+            sim.pml.thickness = 1.0;
+            let grid = sim.grid;
+            let source = FdfdSource {
+                center: Vector2D::new(-2.0, 0.0),
+                size: Vector2D::new(0.0, 8.0),
+                amplitude_real: 1.0,
+                amplitude_imag: 0.0,
+            };
+            let field = sim.factor().unwrap().solve(&sim.rhs(&rasterize_sources(&grid, &[source])));
+            let j = grid.ny() / 2;
+            let (i0, _) = grid.nearest_pixel(Vector2D::new(-1.0, 0.0));
+            let (i1, _) = grid.nearest_pixel(Vector2D::new(1.0, 0.0));
+            let phase = (field[grid.index(i1, j)] / field[grid.index(i0, j)]).arg();
+            let dx = grid.dx();
+            let expected = 2.0 / dx * (PI * n * dx).asin() * (i1 - i0) as f64 * dx;
+            let wrapped = (phase - expected).rem_euclid(2.0 * PI);
+            assert!(wrapped.min(2.0 * PI - wrapped) < 0.05, "{:?}: phase error {}", polarization, wrapped);
+        }
+
+        // an oblique Hz plane wave at the extraordinary index solves the
+        // interior equations up to the O((kΔ)²) discretisation error
+        let grid = YeeGrid2D::new(2.0, 2.0, 40.0);
+        let sim = FdfdSimulation {
+            grid,
+            frequency: 1.0,
+            polarization: FdfdPolarization::Hz,
+            pml: PmlSpec { thickness: 0.0, power: 2.0, r_asymptotic: 1e-12 },
+            epsilon: Vec::new(),
+            anisotropy: vec![tensor; grid.pixel_count()],
+        };
+        let omega = sim.omega();
+        let residual = |phi: f64, n: f64| {
+            let k = [omega * n * phi.cos(), omega * n * phi.sin()];
+            let wave: Vec<Complex64> = (0..grid.pixel_count())
+                .map(|p| Complex64::from_polar(1.0, k[0] * grid.x((p % grid.nx()) as f64) + k[1] * grid.y((p / grid.nx()) as f64)))
+                .collect();
+            let (a, ordering) = sim.assemble().unwrap();
+            let applied = ordering.to_grid(&a.multiply(&ordering.to_unknowns(&wave)));
+            (1..grid.ny() - 1)
+                .flat_map(|j| (1..grid.nx() - 1).map(move |i| grid.index(i, j)))
+                .map(|p| applied[p].norm())
+                .fold(0.0, f64::max)
+                / (omega * omega)
+        };
+        for phi in [60f64.to_radians(), 120f64.to_radians()] {
+            assert!(residual(phi, index(phi)) < 0.02, "{}", residual(phi, index(phi)));
+            // n is 1.59 at 60° and 2 at 120°, so swapping them fails
+            assert!(residual(phi, index(PI - phi)) > 0.1, "{}", residual(phi, index(PI - phi)));
+        }
+    }
+}
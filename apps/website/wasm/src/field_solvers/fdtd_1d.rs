@@ -0,0 +1,381 @@
+use num_complex::Complex64;
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+use crate::field_solvers::fdfd::PmlSpec;
+
+/// Slab of (possibly nonlinear) dielectric, following Meep's convention
+/// D = ε E + χ⁽²⁾ E² + χ⁽³⁾ E³ for the single field component of a 1D cell.
+/// Susceptibilities are in Meep units, not SI.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct NonlinearSlab {
+    pub center: f64,
+    pub thickness: f64,
+    #[serde(default = "unit_epsilon")]
+    pub epsilon: f64,
+    #[serde(default, rename = "E_chi2", alias = "chi2")]
+    pub chi2: f64,
+    #[serde(default, rename = "E_chi3", alias = "chi3")]
+    pub chi3: f64,
+}
+
+fn unit_epsilon() -> f64 {
+    1.0
+}
+
+impl NonlinearSlab {
+    fn contains(&self, z: f64) -> bool {
+        (z - self.center).abs() <= 0.5 * self.thickness
+    }
+}
+
+/// Pointwise constitutive relation at one E node.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct NodeMedium {
+    epsilon: f64,
+    chi2: f64,
+    chi3: f64,
+}
+
+impl NodeMedium {
+    /// Invert D = εE + χ₂E² + χ₃E³ by Newton iteration from the linear guess.
+    fn field(&self, d: f64) -> f64 {
+        let mut e = d / self.epsilon;
+        if self.chi2 == 0.0 && self.chi3 == 0.0 {
+            return e;
+        }
+        for _ in 0..20 {
+            let residual = self.epsilon * e + self.chi2 * e * e + self.chi3 * e * e * e - d;
+            let slope = self.epsilon + 2.0 * self.chi2 * e + 3.0 * self.chi3 * e * e;
+            let step = residual / slope;
+            e -= step;
+            if step.abs() <= 1e-15 * e.abs().max(1e-300) {
+                break;
+            }
+        }
+        e
+    }
+}
+
+/// Continuous-wave current sheet J = amplitude · cos(2πft), switched on over
+/// `ramp_time`. As in Meep, the radiated plane waves have amplitude |J|/2.
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct ContinuousSource1D {
+    pub position: f64,
+    pub frequency: f64,
+    #[serde(default = "unit_amplitude")]
+    pub amplitude: f64,
+    #[serde(default)]
+    pub ramp_time: Option<f64>,
+}
+
+fn unit_amplitude() -> f64 {
+    1.0
+}
+
+fn default_courant() -> f64 {
+    0.5
+}
+
+fn default_harmonics() -> usize {
+    3
+}
+
+fn default_averaging_periods() -> f64 {
+    20.0
+}
+
+#[derive(Deserialize)]
+pub struct Fdtd1dConfig {
+    /// Cell length along z, centred at 0.
+    pub length: f64,
+    pub resolution: f64,
+    #[serde(default = "default_courant")]
+    pub courant: f64,
+    pub pml: PmlSpec,
+    #[serde(default)]
+    pub slabs: Vec<NonlinearSlab>,
+    pub source: ContinuousSource1D,
+    pub run_time: f64,
+    /// Positions at which harmonic amplitudes are measured.
+    #[serde(default)]
+    pub monitors: Vec<f64>,
+    /// Harmonics 1..=n of the source frequency are analysed.
+    #[serde(default = "default_harmonics")]
+    pub harmonics: usize,
+    /// Length of the final DFT window in source periods.
+    #[serde(default = "default_averaging_periods")]
+    pub averaging_periods: f64,
+}
+
+#[derive(Serialize)]
+pub struct HarmonicAmplitude {
+    pub order: usize,
+    pub frequency: f64,
+    pub amplitude: f64,
+    pub phase: f64,
+    /// |E_n|² / |E_1|², the power relative to the fundamental in vacuum.
+    pub relative_power: f64,
+}
+
+#[derive(Serialize)]
+pub struct MonitorHarmonics {
+    pub position: f64,
+    pub harmonics: Vec<HarmonicAmplitude>,
+}
+
+#[derive(Serialize)]
+pub struct Fdtd1dResult {
+    pub dz: f64,
+    pub dt: f64,
+    pub steps: usize,
+    pub z: Vec<f64>,
+    /// Ex at the final time step.
+    pub ex: Vec<f64>,
+    pub monitors: Vec<MonitorHarmonics>,
+}
+
+/// 1D Yee scheme for (Ex, Hy) propagating along z with graded-conductivity
+/// PML at both ends. E lives on integer nodes, H on half nodes.
+struct Fdtd1d {
+    nodes: usize,
+    dz: f64,
+    dt: f64,
+    media: Vec<NodeMedium>,
+    sigma_e: Vec<f64>,
+    sigma_h: Vec<f64>,
+    d: Vec<f64>,
+    e: Vec<f64>,
+    h: Vec<f64>,
+}
+
+impl Fdtd1d {
+    fn new(config: &Fdtd1dConfig) -> Result<Self, String> {
+        if config.length <= 0.0 || config.resolution <= 0.0 {
+            return Err("length and resolution must be positive".into());
+        }
+        if config.courant <= 0.0 || config.courant > 1.0 {
+            return Err("Courant number must lie in (0, 1] in 1D".into());
+        }
+        let cells = (config.length * config.resolution).round().max(2.0) as usize;
+        let dz = config.length / cells as f64;
+        let z = |k: f64| -0.5 * config.length + k * dz;
+        let interior = 0.5 * config.length - config.pml.thickness;
+        if config.slabs.iter().any(|s| (s.center.abs() + 0.5 * s.thickness) > interior) {
+            return Err("slabs must not overlap the PML".into());
+        }
+        if config.slabs.iter().any(|s| s.epsilon <= 0.0) {
+            return Err("slab permittivity must be positive".into());
+        }
+
+        let nodes = cells + 1;
+        let media = (0..nodes)
+            .map(|k| {
+                let zk = z(k as f64);
+                config
+                    .slabs
+                    .iter()
+                    .rev()
+                    .find(|s| s.contains(zk))
+                    .map_or(NodeMedium { epsilon: 1.0, chi2: 0.0, chi3: 0.0 }, |s| NodeMedium {
+                        epsilon: s.epsilon,
+                        chi2: s.chi2,
+                        chi3: s.chi3,
+                    })
+            })
+            .collect();
+        Ok(Self {
+            nodes,
+            dz,
+            dt: config.courant * dz,
+            media,
+            sigma_e: (0..nodes).map(|k| config.pml.sigma(z(k as f64), config.length)).collect(),
+            sigma_h: (0..cells).map(|k| config.pml.sigma(z(k as f64 + 0.5), config.length)).collect(),
+            d: vec![0.0; nodes],
+            e: vec![0.0; nodes],
+            h: vec![0.0; cells],
+        })
+    }
+
+    /// Advance H by one step, then D and E; `current` is injected at node `ks`.
+    fn step(&mut self, ks: usize, current: f64) {
+        let r = self.dt / self.dz;
+        for k in 0..self.h.len() {
+            let s = 0.5 * self.sigma_h[k] * self.dt;
+            self.h[k] = ((1.0 - s) * self.h[k] - r * (self.e[k + 1] - self.e[k])) / (1.0 + s);
+        }
+        // the end nodes stay zero (PEC behind the PML)
+        for k in 1..self.nodes - 1 {
+            let s = 0.5 * self.sigma_e[k] * self.dt;
+            let mut curl = r * (self.h[k] - self.h[k - 1]);
+            if k == ks {
+                curl += self.dt * current / self.dz;
+            }
+            self.d[k] = ((1.0 - s) * self.d[k] - curl) / (1.0 + s);
+            self.e[k] = self.media[k].field(self.d[k]);
+        }
+    }
+}
+
+/// Amplitude of the second harmonic after a phase-matched slab of length L
+/// in the undepleted-pump limit: |E₂| = ω χ⁽²⁾ A² L / (2n), from
+/// 2iK dE₂/dz = −Ω² χ⁽²⁾ A² / 2 with K = nΩ, Ω = 2ω.
+pub fn undepleted_shg_amplitude(frequency: f64, chi2: f64, pump_amplitude: f64, length: f64, index: f64) -> f64 {
+    let omega = 2.0 * PI * frequency;
+    omega * chi2 * pump_amplitude * pump_amplitude * length / (2.0 * index)
+}
+
+pub fn run_fdtd_1d_internal(config: &Fdtd1dConfig) -> Result<Fdtd1dResult, String> {
+    let mut sim = Fdtd1d::new(config)?;
+    let source = config.source;
+    if source.frequency <= 0.0 {
+        return Err("source frequency must be positive".into());
+    }
+    let node_of = |z: f64| ((z + 0.5 * config.length) / sim.dz).round() as isize;
+    let ks = node_of(source.position);
+    if ks <= 0 || ks as usize >= sim.nodes - 1 {
+        return Err("source lies outside the cell".into());
+    }
+    let monitor_nodes: Vec<usize> = config
+        .monitors
+        .iter()
+        .map(|&z| {
+            let k = node_of(z);
+            if k < 0 || k as usize >= sim.nodes {
+                Err(format!("monitor at {} lies outside the cell", z))
+            } else {
+                Ok(k as usize)
+            }
+        })
+        .collect::<Result<_, _>>()?;
+
+    let omega = 2.0 * PI * source.frequency;
+    let period = 1.0 / source.frequency;
+    let ramp = source.ramp_time.unwrap_or(5.0 * period);
+    let steps = (config.run_time / sim.dt).ceil() as usize;
+    // DFT over a whole number of periods at the end of the run
+    let window_steps = ((config.averaging_periods * period / sim.dt).round() as usize).min(steps);
+    let dft_start = steps - window_steps;
+    let harmonics = config.harmonics.max(1);
+    let mut dft = vec![vec![Complex64::new(0.0, 0.0); harmonics]; monitor_nodes.len()];
+
+    for n in 0..steps {
+        // D and E are advanced to t = (n + 1) dt with the current at (n + ½) dt
+        let t = (n as f64 + 0.5) * sim.dt;
+        let envelope = if t < ramp { (0.5 * PI * t / ramp).sin().powi(2) } else { 1.0 };
+        sim.step(ks as usize, source.amplitude * envelope * (omega * t).cos());
+
+        if n >= dft_start {
+            let t_e = (n + 1) as f64 * sim.dt;
+            for (acc, &k) in dft.iter_mut().zip(&monitor_nodes) {
+                for (h, a) in acc.iter_mut().enumerate() {
+                    *a += sim.e[k] * Complex64::from_polar(1.0, (h + 1) as f64 * omega * t_e) * sim.dt;
+                }
+            }
+        }
+    }
+
+    let window = window_steps as f64 * sim.dt;
+    let monitors = config
+        .monitors
+        .iter()
+        .zip(&dft)
+        .map(|(&position, acc)| {
+            let amplitudes: Vec<Complex64> = acc.iter().map(|a| 2.0 * a / window).collect();
+            let fundamental = amplitudes[0].norm_sqr().max(1e-300);
+            MonitorHarmonics {
+                position,
+                harmonics: amplitudes
+                    .iter()
+                    .enumerate()
+                    .map(|(h, a)| HarmonicAmplitude {
+                        order: h + 1,
+                        frequency: (h + 1) as f64 * source.frequency,
+                        amplitude: a.norm(),
+                        phase: a.arg(),
+                        relative_power: a.norm_sqr() / fundamental,
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+
+    Ok(Fdtd1dResult {
+        dz: sim.dz,
+        dt: sim.dt,
+        steps,
+        z: (0..sim.nodes).map(|k| -0.5 * config.length + k as f64 * sim.dz).collect(),
+        ex: sim.e,
+        monitors,
+    })
+}
+
+/// Run a 1D FDTD simulation with χ(2)/χ(3) slabs and report harmonic amplitudes
+#[wasm_bindgen]
+pub fn run_fdtd_1d(config: &JsValue) -> Result<JsValue, JsValue> {
+    let config: Fdtd1dConfig = serde_wasm_bindgen::from_value(config.clone())?;
+    let result = run_fdtd_1d_internal(&config).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SLAB_LENGTH: f64 = 5.0;
+
+    fn slab_config(pump: f64, chi2: f64, chi3: f64) -> Fdtd1dConfig {
+        Fdtd1dConfig {
+            length: 12.0,
+            resolution: 40.0,
+            courant: 0.5,
+            pml: PmlSpec { thickness: 1.5, power: 2.0, r_asymptotic: 1e-15 },
+            slabs: vec![NonlinearSlab { center: 0.0, thickness: SLAB_LENGTH, epsilon: 1.0, chi2, chi3 }],
+            // the current sheet radiates |J| / 2 in each direction
+            source: ContinuousSource1D { position: -3.5, frequency: 1.0, amplitude: 2.0 * pump, ramp_time: Some(5.0) },
+            run_time: 40.0,
+            monitors: vec![3.5],
+            harmonics: 2,
+            averaging_periods: 20.0,
+        }
+    }
+
+    #[test]
+    fn test_shg_matches_undepleted_pump() {
+        let chi2 = 0.1;
+        let mut second = Vec::new();
+        for pump in [0.025, 0.05] {
+            let result = run_fdtd_1d_internal(&slab_config(pump, chi2, 0.0)).unwrap();
+            let harmonics = &result.monitors[0].harmonics;
+            assert!((harmonics[0].amplitude - pump).abs() < 1e-2 * pump);
+
+            let expected = undepleted_shg_amplitude(1.0, chi2, harmonics[0].amplitude, SLAB_LENGTH, 1.0);
+            assert!(
+                (harmonics[1].amplitude - expected).abs() < 0.03 * expected,
+                "pump {}: |E2| = {}, expected {}",
+                pump,
+                harmonics[1].amplitude,
+                expected
+            );
+            second.push(harmonics[1].relative_power);
+        }
+        // conversion efficiency grows with the square of the pump amplitude
+        assert!((second[1] / second[0] - 4.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_kerr_self_phase_modulation() {
+        let (pump, chi3) = (0.3, 0.1);
+        let linear = run_fdtd_1d_internal(&slab_config(pump, 0.0, 0.0)).unwrap();
+        let kerr = run_fdtd_1d_internal(&slab_config(pump, 0.0, chi3)).unwrap();
+        let phase = |r: &Fdtd1dResult| r.monitors[0].harmonics[0].phase;
+        let mut shift = (phase(&linear) - phase(&kerr)).rem_euclid(2.0 * PI);
+        if shift > PI {
+            shift -= 2.0 * PI;
+        }
+        // Δn = (3/8) χ⁽³⁾ A² for a real field of amplitude A
+        let expected = 2.0 * PI * SLAB_LENGTH * 3.0 / 8.0 * chi3 * pump * pump;
+        assert!((shift.abs() - expected).abs() < 0.05 * expected, "shift {} expected {}", shift, expected);
+    }
+}
@@ -0,0 +1,498 @@
+use num_complex::Complex64;
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+use crate::field_solvers::fdfd::FdfdPolarization;
+use crate::field_solvers::transfer_matrix::LayerMedium;
+use crate::field_solvers::yee_grid::YeeGrid2D;
+use crate::numerical_calculations::special_functions::{
+    bessel_j_orders, bessel_j_scaled_orders, cylinder_derivatives, cylinder_log_derivatives, hankel_orders,
+    riccati_log_derivatives, riccati_psi, riccati_xi,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScattererShape {
+    /// Infinite cylinder along z, lit along +x (a 2D `Cylinder`).
+    Cylinder,
+    /// Sphere lit by x̂ exp(ikz).
+    Sphere,
+}
+
+/// Square map of the total field centred on the scatterer: the xy plane for
+/// cylinders, the xz plane for spheres.
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct MieNearFieldSpec {
+    pub frequency: f64,
+    pub size: f64,
+    pub resolution: f64,
+}
+
+fn default_polarization() -> FdfdPolarization {
+    FdfdPolarization::Ez
+}
+
+fn unit_intensity() -> f64 {
+    1.0
+}
+
+#[derive(Deserialize)]
+pub struct MieConfig {
+    pub shape: ScattererShape,
+    pub radius: f64,
+    pub material: LayerMedium,
+    /// Lossless surrounding medium; vacuum by default.
+    #[serde(default)]
+    pub background: Option<LayerMedium>,
+    /// Cylinders only: Ez (E along the axis) or Hz.
+    #[serde(default = "default_polarization")]
+    pub polarization: FdfdPolarization,
+    pub frequencies: Vec<f64>,
+    /// Incident flux per unit area (length in 2D), which turns cross-sections
+    /// into the fluxes a closed flux box around the scatterer would record.
+    #[serde(default = "unit_intensity")]
+    pub incident_intensity: f64,
+    #[serde(default)]
+    pub near_field: Option<MieNearFieldSpec>,
+}
+
+#[derive(Serialize)]
+pub struct FieldComponentMap {
+    pub name: String,
+    pub real: Vec<f64>,
+    pub imag: Vec<f64>,
+}
+
+#[derive(Serialize)]
+pub struct MieNearField {
+    pub nx: usize,
+    pub ny: usize,
+    /// Pixel-centre coordinates; the second axis is y for cylinders and z for spheres.
+    pub x: Vec<f64>,
+    pub y: Vec<f64>,
+    /// Row-major with x fastest, as on the Yee grid.
+    pub components: Vec<FieldComponentMap>,
+    /// Total |E|² (|Hz|² for the Hz polarisation).
+    pub intensity: Vec<f64>,
+}
+
+#[derive(Serialize)]
+pub struct MieResult {
+    pub frequencies: Vec<f64>,
+    /// Size parameter x = k r in the background.
+    pub size_parameter: Vec<f64>,
+    /// Cross-sections: a length for cylinders (per unit length), an area for spheres.
+    pub scattering_cross_section: Vec<f64>,
+    pub extinction_cross_section: Vec<f64>,
+    pub absorption_cross_section: Vec<f64>,
+    /// Efficiencies σ / 2r for cylinders and σ / πr² for spheres.
+    pub scattering_efficiency: Vec<f64>,
+    pub extinction_efficiency: Vec<f64>,
+    pub absorption_efficiency: Vec<f64>,
+    /// σ times the incident intensity: the scattered-field flux out of a box
+    /// around the scatterer, and minus the total-field flux into it.
+    pub scattered_flux: Vec<f64>,
+    pub absorbed_flux: Vec<f64>,
+    pub orders: Vec<usize>,
+    pub near_field: Option<MieNearField>,
+    pub warnings: Vec<String>,
+}
+
+/// Wiscombe's term count x + 4x^{1/3} + 2.
+fn order_count(x: f64) -> usize {
+    (x + 4.0 * x.cbrt() + 2.0).ceil() as usize
+}
+
+/// Scattering coefficients of one frequency: `scattered` multiplies the
+/// outgoing waves and `internal` the regular waves inside, per order.
+struct MieCoefficients {
+    k: f64,
+    m: Complex64,
+    /// Cylinder: bₙ (Ez) or aₙ (Hz) and the axial field Jₙ(x) − bₙHₙ(x) of
+    /// each order on the surface, n ≥ 0.
+    /// Sphere: (aₙ, bₙ) and (dₙ, cₙ) in Bohren & Huffman's notation, n ≥ 1.
+    scattered: Vec<(Complex64, Complex64)>,
+    internal: Vec<(Complex64, Complex64)>,
+}
+
+fn cylinder_coefficients(k: f64, x: f64, m: Complex64, polarization: FdfdPolarization) -> MieCoefficients {
+    let orders = order_count(x);
+    let mx = m * x;
+    let g = cylinder_log_derivatives(mx, orders);
+    let j_out = bessel_j_orders(Complex64::new(x, 0.0), orders);
+    let dj_out = cylinder_derivatives(&j_out, Complex64::new(x, 0.0));
+    let h = hankel_orders(x, orders);
+    let dh = cylinder_derivatives(&h, Complex64::new(x, 0.0));
+    let mut scattered = Vec::with_capacity(orders + 1);
+    let mut internal = Vec::with_capacity(orders + 1);
+    for n in 0..=orders {
+        // continuity of the axial field and of ∂ᵣ of it, divided by ε for Hz
+        let factor = match polarization {
+            FdfdPolarization::Ez => m * g[n],
+            FdfdPolarization::Hz => g[n] / m,
+        };
+        let b = (dj_out[n] - factor * j_out[n]) / (dh[n] - factor * h[n]);
+        let surface = j_out[n] - b * h[n];
+        scattered.push((b, b));
+        internal.push((surface, surface));
+    }
+    MieCoefficients { k, m, scattered, internal }
+}
+
+fn sphere_coefficients(k: f64, x: f64, m: Complex64) -> MieCoefficients {
+    let orders = order_count(x);
+    let mx = m * x;
+    let d = riccati_log_derivatives(mx, orders);
+    let psi_in = riccati_psi(mx, orders);
+    let psi = riccati_psi(Complex64::new(x, 0.0), orders);
+    let xi = riccati_xi(x, orders);
+    let mut scattered = vec![(Complex64::new(0.0, 0.0), Complex64::new(0.0, 0.0))];
+    let mut internal = scattered.clone();
+    for n in 1..=orders {
+        let ratio = n as f64 / x;
+        let a = ((d[n] / m + ratio) * psi[n] - psi[n - 1]) / ((d[n] / m + ratio) * xi[n] - xi[n - 1]);
+        let b = ((m * d[n] + ratio) * psi[n] - psi[n - 1]) / ((m * d[n] + ratio) * xi[n] - xi[n - 1]);
+        // tangential H (N waves) and E (M waves) at the surface
+        let dn = (psi[n] - a * xi[n]) / psi_in[n];
+        let cn = m * (psi[n] - b * xi[n]) / psi_in[n];
+        scattered.push((a, b));
+        internal.push((dn, cn));
+    }
+    MieCoefficients { k, m, scattered, internal }
+}
+
+/// Total field of a cylinder at (x, y): the axial component, for either polarisation.
+fn cylinder_field(coefficients: &MieCoefficients, radius: f64, x: f64, y: f64) -> Complex64 {
+    let r = x.hypot(y);
+    let phi = y.atan2(x);
+    let k = coefficients.k;
+    let orders = coefficients.scattered.len() - 1;
+    // Σₙ over ±n folds into 2 iⁿ fₙ cos nφ since f₋ₙ = (−1)ⁿ fₙ and b₋ₙ = bₙ
+    let fold = |terms: &dyn Fn(usize) -> Complex64| -> Complex64 {
+        (0..=orders)
+            .map(|n| {
+                let weight = if n == 0 { 1.0 } else { 2.0 * (n as f64 * phi).cos() };
+                Complex64::new(0.0, 1.0).powu(n as u32) * weight * terms(n)
+            })
+            .sum()
+    };
+    if r < radius {
+        // Jₙ(mkr)/Jₙ(mkR) from the scaled functions, so that lossy or
+        // metallic cores with |Im mkR| in the hundreds stay finite
+        let (inner, surface) = (coefficients.m * k * r, coefficients.m * k * radius);
+        let (j, j_surface) = (bessel_j_scaled_orders(inner, orders), bessel_j_scaled_orders(surface, orders));
+        let growth = (inner.im.abs() - surface.im.abs()).exp();
+        fold(&|n| {
+            if j_surface[n].norm() == 0.0 {
+                Complex64::new(0.0, 0.0)
+            } else {
+                coefficients.internal[n].0 * j[n] / j_surface[n] * growth
+            }
+        })
+    } else {
+        let h = hankel_orders(k * r, orders);
+        Complex64::from_polar(1.0, k * x) - fold(&|n| coefficients.scattered[n].0 * h[n])
+    }
+}
+
+/// Angular functions πₙ(cos θ) and τₙ(cos θ), n = 0…N.
+fn angular_functions(mu: f64, orders: usize) -> (Vec<f64>, Vec<f64>) {
+    let mut pi = vec![0.0, 1.0];
+    for n in 2..=orders {
+        pi.push(((2 * n - 1) as f64 * mu * pi[n - 1] - n as f64 * pi[n - 2]) / (n - 1) as f64);
+    }
+    pi.truncate(orders + 1);
+    let tau = (0..=orders).map(|n| if n == 0 { 0.0 } else { n as f64 * mu * pi[n] - (n + 1) as f64 * pi[n - 1] }).collect();
+    (pi, tau)
+}
+
+/// Total E of a sphere at a point, from the vector spherical harmonics
+/// M_o1n and N_e1n with Eₙ = iⁿ (2n+1)/(n(n+1)): outside, the incident wave
+/// plus Σ Eₙ (i aₙ N⁽³⁾ − bₙ M⁽³⁾); inside, Σ Eₙ (cₙ M⁽¹⁾ − i dₙ N⁽¹⁾).
+fn sphere_field(coefficients: &MieCoefficients, radius: f64, point: [f64; 3]) -> [Complex64; 3] {
+    let [x, y, z] = point;
+    let r = (x * x + y * y + z * z).sqrt().max(1e-9 * radius);
+    let (theta, phi) = ((z / r).clamp(-1.0, 1.0).acos(), y.atan2(x));
+    let (st, ct) = theta.sin_cos();
+    let (sp, cp) = phi.sin_cos();
+    let orders = coefficients.scattered.len() - 1;
+    let (pi, tau) = angular_functions(ct, orders);
+    let inside = r < radius;
+    let rho = if inside { coefficients.m * coefficients.k * r } else { Complex64::new(coefficients.k * r, 0.0) };
+    // Riccati functions ψ or ξ, giving z = ψ/ρ and [ρz]′/ρ
+    let radial = if inside { riccati_psi(rho, orders) } else { riccati_xi(rho.re, orders) };
+    let (mut e_r, mut e_theta, mut e_phi) = (Complex64::new(0.0, 0.0), Complex64::new(0.0, 0.0), Complex64::new(0.0, 0.0));
+    let i = Complex64::new(0.0, 1.0);
+    for n in 1..=orders {
+        let nf = n as f64;
+        let en = i.powu(n as u32) * (2.0 * nf + 1.0) / (nf * (nf + 1.0));
+        let zn = radial[n] / rho;
+        let dzn = (radial[n - 1] - nf * radial[n] / rho) / rho;
+        let (m_coefficient, n_coefficient) = if inside {
+            (coefficients.internal[n].1, -i * coefficients.internal[n].0)
+        } else {
+            (-coefficients.scattered[n].1, i * coefficients.scattered[n].0)
+        };
+        // M_o1n = cos φ πₙ z θ̂ − sin φ τₙ z φ̂
+        // N_e1n = cos φ n(n+1) sin θ πₙ z/ρ r̂ + cos φ τₙ [ρz]′/ρ θ̂ − sin φ πₙ [ρz]′/ρ φ̂
+        e_r += en * n_coefficient * cp * nf * (nf + 1.0) * st * pi[n] * zn / rho;
+        e_theta += en * (m_coefficient * cp * pi[n] * zn + n_coefficient * cp * tau[n] * dzn);
+        e_phi += en * (-m_coefficient * sp * tau[n] * zn - n_coefficient * sp * pi[n] * dzn);
+    }
+    let mut field = [
+        e_r * st * cp + e_theta * ct * cp - e_phi * sp,
+        e_r * st * sp + e_theta * ct * sp + e_phi * cp,
+        e_r * ct - e_theta * st,
+    ];
+    if !inside {
+        field[0] += Complex64::from_polar(1.0, coefficients.k * z);
+    }
+    field
+}
+
+fn near_field_map(config: &MieConfig, coefficients: &MieCoefficients, spec: &MieNearFieldSpec) -> Result<MieNearField, String> {
+    let grid = YeeGrid2D::new(spec.size, spec.size, spec.resolution);
+    grid.validate()?;
+    let (nx, ny) = (grid.nx(), grid.ny());
+    let x: Vec<f64> = (0..nx).map(|i| grid.x(i as f64)).collect();
+    let y: Vec<f64> = (0..ny).map(|j| grid.y(j as f64)).collect();
+    let mut components = Vec::new();
+    let mut intensity = Vec::with_capacity(nx * ny);
+    match config.shape {
+        ScattererShape::Cylinder => {
+            let values: Vec<Complex64> = y
+                .iter()
+                .flat_map(|&yj| x.iter().map(move |&xi| (xi, yj)))
+                .map(|(xi, yj)| cylinder_field(coefficients, config.radius, xi, yj))
+                .collect();
+            intensity.extend(values.iter().map(|v| v.norm_sqr()));
+            let name = match config.polarization {
+                FdfdPolarization::Ez => "Ez",
+                FdfdPolarization::Hz => "Hz",
+            };
+            components.push(FieldComponentMap {
+                name: name.into(),
+                real: values.iter().map(|v| v.re).collect(),
+                imag: values.iter().map(|v| v.im).collect(),
+            });
+        }
+        ScattererShape::Sphere => {
+            let fields: Vec<[Complex64; 3]> = y
+                .iter()
+                .flat_map(|&zj| x.iter().map(move |&xi| [xi, 0.0, zj]))
+                .map(|p| sphere_field(coefficients, config.radius, p))
+                .collect();
+            intensity.extend(fields.iter().map(|f| f.iter().map(|c| c.norm_sqr()).sum::<f64>()));
+            for (c, name) in ["Ex", "Ey", "Ez"].iter().enumerate() {
+                components.push(FieldComponentMap {
+                    name: name.to_string(),
+                    real: fields.iter().map(|f| f[c].re).collect(),
+                    imag: fields.iter().map(|f| f[c].im).collect(),
+                });
+            }
+        }
+    }
+    Ok(MieNearField { nx, ny, x, y, components, intensity })
+}
+
+pub fn mie_scattering_internal(config: &MieConfig) -> Result<MieResult, String> {
+    if config.radius <= 0.0 {
+        return Err("radius must be positive".into());
+    }
+    if config.frequencies.iter().any(|&f| f <= 0.0) {
+        return Err("frequencies must be positive".into());
+    }
+    let background = config.background.clone().unwrap_or_else(|| LayerMedium::constant(1.0));
+    let mut warnings = Vec::new();
+    let coefficients_at = |frequency: f64| -> Result<MieCoefficients, String> {
+        let eps_b = background.epsilon_at(frequency);
+        if eps_b.re <= 0.0 {
+            return Err("the background must have positive permittivity".into());
+        }
+        let n_b = eps_b.re.sqrt();
+        let k = 2.0 * PI * frequency * n_b;
+        let m = (config.material.epsilon_at(frequency) / eps_b.re).sqrt();
+        Ok(match config.shape {
+            ScattererShape::Cylinder => cylinder_coefficients(k, k * config.radius, m, config.polarization),
+            ScattererShape::Sphere => sphere_coefficients(k, k * config.radius, m),
+        })
+    };
+    if config.frequencies.iter().any(|&f| background.epsilon_at(f).im != 0.0) {
+        warnings.push("the background loss is ignored; cross-sections assume a lossless host".into());
+    }
+
+    let n = config.frequencies.len();
+    let mut result = MieResult {
+        frequencies: config.frequencies.clone(),
+        size_parameter: Vec::with_capacity(n),
+        scattering_cross_section: Vec::with_capacity(n),
+        extinction_cross_section: Vec::with_capacity(n),
+        absorption_cross_section: Vec::with_capacity(n),
+        scattering_efficiency: Vec::with_capacity(n),
+        extinction_efficiency: Vec::with_capacity(n),
+        absorption_efficiency: Vec::with_capacity(n),
+        scattered_flux: Vec::with_capacity(n),
+        absorbed_flux: Vec::with_capacity(n),
+        orders: Vec::with_capacity(n),
+        near_field: None,
+        warnings,
+    };
+    for &frequency in &config.frequencies {
+        let coefficients = coefficients_at(frequency)?;
+        let (k, x) = (coefficients.k, coefficients.k * config.radius);
+        let (scattering, extinction, geometric) = match config.shape {
+            // C = (4/k) [|b₀|² + 2 Σ |bₙ|²], Re likewise
+            ScattererShape::Cylinder => {
+                let weight = |n: usize| if n == 0 { 1.0 } else { 2.0 };
+                let terms = coefficients.scattered.iter().enumerate();
+                let sca: f64 = terms.clone().map(|(n, (b, _))| weight(n) * b.norm_sqr()).sum();
+                let ext: f64 = terms.map(|(n, (b, _))| weight(n) * b.re).sum();
+                (4.0 / k * sca, 4.0 / k * ext, 2.0 * config.radius)
+            }
+            // C = (2π/k²) Σ (2n+1)(|aₙ|² + |bₙ|²), Re(aₙ + bₙ)
+            ScattererShape::Sphere => {
+                let terms = coefficients.scattered.iter().enumerate().skip(1);
+                let sca: f64 = terms.clone().map(|(n, (a, b))| (2 * n + 1) as f64 * (a.norm_sqr() + b.norm_sqr())).sum();
+                let ext: f64 = terms.map(|(n, (a, b))| (2 * n + 1) as f64 * (a + b).re).sum();
+                let scale = 2.0 * PI / (k * k);
+                (scale * sca, scale * ext, PI * config.radius * config.radius)
+            }
+        };
+        let absorption = extinction - scattering;
+        result.size_parameter.push(x);
+        result.scattering_cross_section.push(scattering);
+        result.extinction_cross_section.push(extinction);
+        result.absorption_cross_section.push(absorption);
+        result.scattering_efficiency.push(scattering / geometric);
+        result.extinction_efficiency.push(extinction / geometric);
+        result.absorption_efficiency.push(absorption / geometric);
+        result.scattered_flux.push(scattering * config.incident_intensity);
+        result.absorbed_flux.push(absorption * config.incident_intensity);
+        result.orders.push(coefficients.scattered.len() - 1);
+    }
+    if let Some(spec) = &config.near_field {
+        if spec.frequency <= 0.0 {
+            return Err("near-field frequency must be positive".into());
+        }
+        let coefficients = coefficients_at(spec.frequency)?;
+        result.near_field = Some(near_field_map(config, &coefficients, spec)?);
+    }
+    Ok(result)
+}
+
+/// Analytic Mie cross-sections and near fields of a cylinder or sphere
+#[wasm_bindgen]
+pub fn compute_mie_scattering(config: &JsValue) -> Result<JsValue, JsValue> {
+    let config: MieConfig = serde_wasm_bindgen::from_value(config.clone())?;
+    let result = mie_scattering_internal(&config).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(shape: ScattererShape, radius: f64, material: LayerMedium, frequency: f64) -> MieConfig {
+        MieConfig {
+            shape,
+            radius,
+            material,
+            background: None,
+            polarization: FdfdPolarization::Ez,
+            frequencies: vec![frequency],
+            incident_intensity: 1.0,
+            near_field: None,
+        }
+    }
+
+    fn lossy(epsilon: f64, epsilon_imag: f64) -> LayerMedium {
+        LayerMedium { epsilon_imag, ..LayerMedium::constant(epsilon) }
+    }
+
+    #[test]
+    fn test_sphere_matches_bhmie_and_rayleigh() {
+        // Bohren & Huffman's BHMIE example: m = 1.55, r = 0.525 µm, λ = 0.6328 µm
+        let mut cfg = config(ScattererShape::Sphere, 0.525, LayerMedium::constant(1.55 * 1.55), 1.0 / 0.6328);
+        let result = mie_scattering_internal(&cfg).unwrap();
+        assert!((result.extinction_efficiency[0] - 3.10543).abs() < 1e-4, "{}", result.extinction_efficiency[0]);
+        assert!((result.scattering_efficiency[0] - 3.10543).abs() < 1e-4);
+        assert!(result.absorption_efficiency[0].abs() < 1e-10);
+
+        // small absorbing sphere: Q_abs ≈ 4x Im[(ε − 1)/(ε + 2)], interior field 3/(ε + 2)
+        let eps = Complex64::new(4.0, 1.0);
+        cfg = config(ScattererShape::Sphere, 0.002, lossy(4.0, 1.0), 1.0);
+        cfg.near_field = Some(MieNearFieldSpec { frequency: 1.0, size: 0.003, resolution: 1000.0 });
+        let result = mie_scattering_internal(&cfg).unwrap();
+        let x = 2.0 * PI * 0.002;
+        let rayleigh = 4.0 * x * ((eps - 1.0) / (eps + 2.0)).im;
+        assert!((result.absorption_efficiency[0] / rayleigh - 1.0).abs() < 1e-3);
+        let map = result.near_field.unwrap();
+        let centre = map.ny / 2 * map.nx + map.nx / 2;
+        let ex = Complex64::new(map.components[0].real[centre], map.components[0].imag[centre]);
+        assert!((ex - 3.0 / (eps + 2.0)).norm() < 1e-3);
+
+        // boundary conditions on a wavelength-sized sphere, to the truncation of
+        // the series against the analytic incident wave
+        let coefficients = sphere_coefficients(2.0 * PI, PI, Complex64::new(1.5, 0.0));
+        let (inner, outer) = (0.5 - 1e-9, 0.5 + 1e-9);
+        let tangential = [sphere_field(&coefficients, 0.5, [0.0, 0.0, inner]), sphere_field(&coefficients, 0.5, [0.0, 0.0, outer])];
+        assert!((tangential[0][0] - tangential[1][0]).norm() < 1e-4);
+        let normal = [sphere_field(&coefficients, 0.5, [inner, 0.0, 0.0]), sphere_field(&coefficients, 0.5, [outer, 0.0, 0.0])];
+        assert!((2.25 * normal[0][0] - normal[1][0]).norm() < 1e-4);
+    }
+
+    #[test]
+    fn test_cylinder_energy_balance_and_quasistatics() {
+        for polarization in [FdfdPolarization::Ez, FdfdPolarization::Hz] {
+            let mut cfg = config(ScattererShape::Cylinder, 0.4, LayerMedium::constant(6.0), 1.0);
+            cfg.polarization = polarization;
+            let result = mie_scattering_internal(&cfg).unwrap();
+            let (sca, ext) = (result.scattering_cross_section[0], result.extinction_cross_section[0]);
+            assert!(sca > 0.1 && ((ext - sca) / sca).abs() < 1e-9, "{:?}", polarization);
+
+            // thin lossy wire: C_abs ≈ k Im(ε) πr² |E_in / E₀|²
+            let eps = Complex64::new(3.0, 0.5);
+            cfg = config(ScattererShape::Cylinder, 0.0005, lossy(3.0, 0.5), 1.0);
+            cfg.polarization = polarization;
+            let result = mie_scattering_internal(&cfg).unwrap();
+            let field = match polarization {
+                FdfdPolarization::Ez => 1.0,
+                FdfdPolarization::Hz => (2.0 / (eps + 1.0)).norm_sqr(),
+            };
+            let expected = 2.0 * PI * 0.5 * PI * 0.0005 * 0.0005 * field;
+            assert!((result.absorption_cross_section[0] / expected - 1.0).abs() < 1e-3, "{:?}", polarization);
+            assert!((result.absorbed_flux[0] - result.absorption_cross_section[0]).abs() < 1e-15);
+
+            // the axial field is continuous across the surface
+            let coefficients = cylinder_coefficients(2.0 * PI, 2.0 * PI * 0.4, Complex64::new(2.0, 0.1), polarization);
+            let (a, b) = (cylinder_field(&coefficients, 0.4, 0.4 - 1e-9, 0.0), cylinder_field(&coefficients, 0.4, 0.4 + 1e-9, 0.0));
+            assert!((a - b).norm() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_large_metallic_cylinder_matches_reference() {
+        // r = 30 λ, ε = −20 + i: |Im mx| ≈ 843, past where J_n(mx) overflows;
+        // references from the same series evaluated with mpmath at 40 digits
+        let cases = [
+            (FdfdPolarization::Ez, 2.027_956_699_815_99, 2.011_438_859_835_35),
+            (FdfdPolarization::Hz, 2.125_530_246_730_87, 2.085_147_708_761_83),
+        ];
+        for (polarization, ext, sca) in cases {
+            let mut cfg = config(ScattererShape::Cylinder, 30.0, lossy(-20.0, 1.0), 1.0);
+            cfg.polarization = polarization;
+            let result = mie_scattering_internal(&cfg).unwrap();
+            assert!((result.extinction_efficiency[0] - ext).abs() < 1e-8, "{:?} {}", polarization, result.extinction_efficiency[0]);
+            assert!((result.scattering_efficiency[0] - sca).abs() < 1e-8, "{:?} {}", polarization, result.scattering_efficiency[0]);
+
+            // the interior field stays finite and meets the exterior one, to the
+            // truncation of the series against the analytic incident wave
+            let m = Complex64::new(-20.0, 1.0).sqrt();
+            let coefficients = cylinder_coefficients(2.0 * PI, 2.0 * PI * 30.0, m, polarization);
+            let (a, b) = (cylinder_field(&coefficients, 30.0, 30.0 - 1e-9, 0.0), cylinder_field(&coefficients, 30.0, 30.0 + 1e-9, 0.0));
+            assert!(a.is_finite() && (a - b).norm() < 1e-4, "{:?} {} {}", polarization, a, b);
+            assert!(cylinder_field(&coefficients, 30.0, 25.0, 0.0).norm() < 1e-50);
+        }
+    }
+}
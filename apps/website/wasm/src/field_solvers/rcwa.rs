@@ -0,0 +1,396 @@
+use num_complex::Complex64;
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+use crate::field_solvers::transfer_matrix::LayerMedium;
+use crate::lattice_calculations::voronoi_cells::Vector2D;
+use crate::numerical_calculations::dense_complex::ComplexMatrix;
+use crate::numerical_calculations::fourier_transform::fourier_series_coefficients;
+
+/// Grating periodicity taken from a lattice: the period is |basis1| scaled by
+/// `basis_size.x`, as in `MeepLattice`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GratingLattice {
+    pub basis1: Vector2D,
+    #[serde(default)]
+    pub basis_size: Option<Vector2D>,
+}
+
+impl GratingLattice {
+    pub fn period(&self) -> f64 {
+        let scale = self.basis_size.map_or(1.0, |s| s.x);
+        self.basis1.dot(&self.basis1).sqrt() * scale
+    }
+}
+
+/// Region `[start, start + width)` of one period filled with `medium`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GratingSegment {
+    pub start: f64,
+    pub width: f64,
+    #[serde(flatten)]
+    pub medium: LayerMedium,
+}
+
+/// Lamellar layer: a background medium with segments along the period.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GratingLayer {
+    pub thickness: f64,
+    #[serde(default = "vacuum")]
+    pub background: LayerMedium,
+    #[serde(default)]
+    pub segments: Vec<GratingSegment>,
+}
+
+fn vacuum() -> LayerMedium {
+    LayerMedium::constant(1.0)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GratingPolarization {
+    /// E along the grooves (Ey).
+    TE,
+    /// H along the grooves (Hy).
+    TM,
+}
+
+impl GratingLayer {
+    /// Permittivity sampled at the midpoints of `samples` equal intervals.
+    fn sample_epsilon(&self, period: f64, frequency: f64, samples: usize) -> Vec<Complex64> {
+        let background = self.background.epsilon_at(frequency);
+        (0..samples)
+            .map(|j| {
+                let x = (j as f64 + 0.5) * period / samples as f64;
+                self.segments
+                    .iter()
+                    .rev()
+                    .find(|s| (x - s.start).rem_euclid(period) < s.width)
+                    .map_or(background, |s| s.medium.epsilon_at(frequency))
+            })
+            .collect()
+    }
+}
+
+/// Toeplitz matrix [f]_{mn} = f̂_{m-n} of a sampled periodic function.
+fn toeplitz(samples: &[Complex64], orders: usize) -> ComplexMatrix {
+    let n_samples = samples.len();
+    let coefficients = fourier_series_coefficients(samples);
+    // samples sit at half-integer positions: shift the phase back to x = 0
+    let coefficient = |m: isize| {
+        let k = m.rem_euclid(n_samples as isize) as usize;
+        coefficients[k] * Complex64::from_polar(1.0, -PI * m as f64 / n_samples as f64)
+    };
+    ComplexMatrix::from_fn(orders, |i, j| coefficient(i as isize - j as isize))
+}
+
+/// Propagation constant q = sqrt(λ) on the decaying / forward branch.
+fn branch_sqrt(lambda: Complex64) -> Complex64 {
+    let q = lambda.sqrt();
+    if q.re <= 1e-12 * q.norm() && q.im > 0.0 { -q } else { q }
+}
+
+/// Tangential field matrix V = C W Q for a homogeneous region (W = I).
+fn homogeneous_v(q: &[Complex64], epsilon: Complex64, polarization: GratingPolarization) -> Vec<Complex64> {
+    let i = Complex64::new(0.0, 1.0);
+    q.iter()
+        .map(|&qm| match polarization {
+            GratingPolarization::TE => i * qm,
+            GratingPolarization::TM => -i * qm / epsilon,
+        })
+        .collect()
+}
+
+/// Diffraction amplitudes for a single frequency and angle.
+struct OrderAmplitudes {
+    reflected: Vec<Complex64>,
+    transmitted: Vec<Complex64>,
+    q_incident: Vec<Complex64>,
+    q_substrate: Vec<Complex64>,
+}
+
+struct RcwaProblem<'a> {
+    period: f64,
+    incident: &'a LayerMedium,
+    substrate: &'a LayerMedium,
+    layers: &'a [GratingLayer],
+    polarization: GratingPolarization,
+    num_orders: usize,
+    samples: usize,
+}
+
+impl RcwaProblem<'_> {
+    fn orders(&self) -> Vec<isize> {
+        let m = self.num_orders as isize;
+        (-m..=m).collect()
+    }
+
+    /// Enhanced-transmittance-matrix solution (Moharam et al.), working from
+    /// the substrate up so that only decaying exponentials appear.
+    fn solve(&self, frequency: f64, angle_deg: f64) -> Result<OrderAmplitudes, String> {
+        let orders = self.orders();
+        let n = orders.len();
+        let wavelength = 1.0 / frequency;
+        let k0 = 2.0 * PI * frequency;
+        let eps_in = self.incident.epsilon_at(frequency);
+        let eps_out = self.substrate.epsilon_at(frequency);
+        let n_in = eps_in.re.sqrt();
+
+        let kx: Vec<f64> = orders
+            .iter()
+            .map(|&m| n_in * angle_deg.to_radians().sin() + m as f64 * wavelength / self.period)
+            .collect();
+        let kx2: Vec<Complex64> = kx.iter().map(|&k| Complex64::new(k * k, 0.0)).collect();
+        let q_in: Vec<Complex64> = kx2.iter().map(|&k| branch_sqrt(k - eps_in)).collect();
+        let q_out: Vec<Complex64> = kx2.iter().map(|&k| branch_sqrt(k - eps_out)).collect();
+        let v_in = homogeneous_v(&q_in, eps_in, self.polarization);
+        let v_out = homogeneous_v(&q_out, eps_out, self.polarization);
+
+        let identity = ComplexMatrix::identity(n);
+        let mut f = identity.clone();
+        let mut g = ComplexMatrix::from_diagonal(&v_out).scale(Complex64::new(-1.0, 0.0));
+        // (a⁻¹, X) per layer, top layer first once reversed
+        let mut chain: Vec<(ComplexMatrix, Vec<Complex64>)> = Vec::with_capacity(self.layers.len());
+
+        for layer in self.layers.iter().rev() {
+            let samples = layer.sample_epsilon(self.period, frequency, self.samples);
+            let e = toeplitz(&samples, n);
+            let inv_samples: Vec<Complex64> = samples.iter().map(|&s| 1.0 / s).collect();
+            let p = toeplitz(&inv_samples, n);
+            let kx_mat = ComplexMatrix::from_diagonal(&kx.iter().map(|&k| Complex64::new(k, 0.0)).collect::<Vec<_>>());
+
+            let a_matrix = match self.polarization {
+                GratingPolarization::TE => ComplexMatrix::from_diagonal(&kx2).sub(&e),
+                // Li's factorisation rules: ∂²H = [1/ε]⁻¹ (Kx [ε]⁻¹ Kx - I) H
+                GratingPolarization::TM => {
+                    let inner = kx_mat.mul(&e.inverse()?).mul(&kx_mat).sub(&identity);
+                    p.solve(&inner)?
+                }
+            };
+            let (lambdas, w) = a_matrix.eigen()?;
+            let q: Vec<Complex64> = lambdas.iter().map(|&l| branch_sqrt(l)).collect();
+            let i = Complex64::new(0.0, 1.0);
+            let v = match self.polarization {
+                GratingPolarization::TE => w.mul_diagonal(&q).scale(i),
+                GratingPolarization::TM => p.mul(&w).mul_diagonal(&q).scale(-i),
+            };
+            let x: Vec<Complex64> = q.iter().map(|&qm| (-qm * k0 * layer.thickness).exp()).collect();
+
+            let w_inv_f = w.solve(&f)?;
+            let v_inv_g = v.solve(&g)?;
+            let a = w_inv_f.sub(&v_inv_g).scale(Complex64::new(0.5, 0.0));
+            let b = w_inv_f.add(&v_inv_g).scale(Complex64::new(0.5, 0.0));
+            let a_inv = a.inverse()?;
+            let xbax = ComplexMatrix::diagonal_mul(&x, &b.mul(&a_inv)).mul_diagonal(&x);
+
+            f = w.mul(&identity.add(&xbax));
+            g = v.mul(&xbax.sub(&identity));
+            chain.push((a_inv, x));
+        }
+
+        // incident field: unit amplitude in the zeroth order
+        let mut delta = vec![Complex64::new(0.0, 0.0); n];
+        delta[n / 2] = Complex64::new(1.0, 0.0);
+        let v_in_mat = ComplexMatrix::from_diagonal(&v_in);
+        let lhs = v_in_mat.mul(&f).sub(&g);
+        let rhs: Vec<Complex64> = v_in.iter().zip(&delta).map(|(v, d)| 2.0 * v * d).collect();
+        let mut rhs_matrix = ComplexMatrix::zeros(n);
+        for k in 0..n {
+            rhs_matrix[(k, 0)] = rhs[k];
+        }
+        let solution = lhs.solve(&rhs_matrix)?;
+        let mut amplitude: Vec<Complex64> = (0..n).map(|k| solution[(k, 0)]).collect();
+
+        let reflected: Vec<Complex64> = f.mul_vec(&amplitude).iter().zip(&delta).map(|(a, d)| a - d).collect();
+        for (a_inv, x) in chain.iter().rev() {
+            let scaled: Vec<Complex64> = amplitude.iter().zip(x).map(|(a, xm)| a * xm).collect();
+            amplitude = a_inv.mul_vec(&scaled);
+        }
+
+        Ok(OrderAmplitudes {
+            reflected,
+            transmitted: amplitude,
+            q_incident: q_in,
+            q_substrate: q_out,
+        })
+    }
+
+    /// Diffraction efficiencies (reflected, transmitted) per order.
+    fn efficiencies(&self, frequency: f64, angle_deg: f64) -> Result<(Vec<f64>, Vec<f64>), String> {
+        let amps = self.solve(frequency, angle_deg)?;
+        let i = Complex64::new(0.0, 1.0);
+        let eps_in = self.incident.epsilon_at(frequency);
+        let eps_out = self.substrate.epsilon_at(frequency);
+        // normal power flow ∝ Re(kz) (TE) or Re(kz/ε) (TM), with kz/k0 = i q
+        let flow = |q: Complex64, eps: Complex64| match self.polarization {
+            GratingPolarization::TE => (i * q).re,
+            GratingPolarization::TM => (i * q / eps).re,
+        };
+        let incident_flow = flow(amps.q_incident[amps.q_incident.len() / 2], eps_in);
+        if incident_flow <= 0.0 {
+            return Err("incident wave must propagate in the incident medium".into());
+        }
+        let reflection = amps
+            .reflected
+            .iter()
+            .zip(&amps.q_incident)
+            .map(|(r, &q)| r.norm_sqr() * flow(q, eps_in).max(0.0) / incident_flow)
+            .collect();
+        let transmission = amps
+            .transmitted
+            .iter()
+            .zip(&amps.q_substrate)
+            .map(|(t, &q)| t.norm_sqr() * flow(q, eps_out).max(0.0) / incident_flow)
+            .collect();
+        Ok((reflection, transmission))
+    }
+}
+
+fn default_num_orders() -> usize {
+    10
+}
+
+fn default_samples() -> usize {
+    512
+}
+
+fn default_angles() -> Vec<f64> {
+    vec![0.0]
+}
+
+#[derive(Deserialize)]
+pub struct RcwaConfig {
+    pub lattice: GratingLattice,
+    #[serde(default = "vacuum")]
+    pub incident_medium: LayerMedium,
+    #[serde(default = "vacuum")]
+    pub substrate: LayerMedium,
+    pub layers: Vec<GratingLayer>,
+    pub frequencies: Vec<f64>,
+    /// Angles of incidence in degrees.
+    #[serde(default = "default_angles")]
+    pub angles: Vec<f64>,
+    pub polarization: GratingPolarization,
+    /// Orders -M..=M are retained.
+    #[serde(default = "default_num_orders")]
+    pub num_orders: usize,
+    /// Samples per period used for the FFT of the permittivity profile.
+    #[serde(default = "default_samples")]
+    pub samples: usize,
+}
+
+#[derive(Serialize)]
+pub struct RcwaResult {
+    pub period: f64,
+    pub orders: Vec<i32>,
+    pub frequencies: Vec<f64>,
+    pub angles: Vec<f64>,
+    /// Efficiencies flattened as `[angle][frequency][order]`.
+    pub reflection_efficiency: Vec<f64>,
+    pub transmission_efficiency: Vec<f64>,
+    /// Totals flattened as `[angle][frequency]`.
+    pub total_reflection: Vec<f64>,
+    pub total_transmission: Vec<f64>,
+}
+
+pub fn rcwa_internal(config: &RcwaConfig) -> Result<RcwaResult, String> {
+    let period = config.lattice.period();
+    if period <= 0.0 {
+        return Err("lattice period must be positive".into());
+    }
+    if config.frequencies.iter().any(|&f| f <= 0.0) {
+        return Err("frequencies must be positive".into());
+    }
+    let n_orders = 2 * config.num_orders + 1;
+    // the Toeplitz matrices need harmonics up to ±2M
+    let samples = config.samples.max(4 * n_orders).next_power_of_two();
+
+    let problem = RcwaProblem {
+        period,
+        incident: &config.incident_medium,
+        substrate: &config.substrate,
+        layers: &config.layers,
+        polarization: config.polarization,
+        num_orders: config.num_orders,
+        samples,
+    };
+
+    let mut result = RcwaResult {
+        period,
+        orders: problem.orders().iter().map(|&m| m as i32).collect(),
+        frequencies: config.frequencies.clone(),
+        angles: config.angles.clone(),
+        reflection_efficiency: Vec::new(),
+        transmission_efficiency: Vec::new(),
+        total_reflection: Vec::new(),
+        total_transmission: Vec::new(),
+    };
+    for &angle in &config.angles {
+        for &f in &config.frequencies {
+            let (r, t) = problem.efficiencies(f, angle)?;
+            result.total_reflection.push(r.iter().sum());
+            result.total_transmission.push(t.iter().sum());
+            result.reflection_efficiency.extend(r);
+            result.transmission_efficiency.extend(t);
+        }
+    }
+    Ok(result)
+}
+
+/// Diffraction efficiencies of a lamellar 1D grating by rigorous coupled-wave analysis
+#[wasm_bindgen]
+pub fn compute_rcwa_1d(config: &JsValue) -> Result<JsValue, JsValue> {
+    let config: RcwaConfig = serde_wasm_bindgen::from_value(config.clone())?;
+    let result = rcwa_internal(&config).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field_solvers::transfer_matrix::{stack_response, StackLayer, StackPolarization};
+
+    fn glass_grating(polarization: GratingPolarization, angles: Vec<f64>) -> RcwaConfig {
+        RcwaConfig {
+            lattice: GratingLattice { basis1: Vector2D::new(1.0, 0.0), basis_size: None },
+            incident_medium: LayerMedium::constant(1.0),
+            substrate: LayerMedium::constant(2.25),
+            layers: vec![GratingLayer {
+                thickness: 0.4,
+                background: LayerMedium::constant(1.0),
+                segments: vec![GratingSegment { start: 0.0, width: 0.5, medium: LayerMedium::constant(2.25) }],
+            }],
+            frequencies: vec![1.0 / 0.8, 1.0 / 1.2],
+            angles,
+            polarization,
+            num_orders: 12,
+            samples: 512,
+        }
+    }
+
+    #[test]
+    fn test_lossless_grating_conserves_energy() {
+        for polarization in [GratingPolarization::TE, GratingPolarization::TM] {
+            let result = rcwa_internal(&glass_grating(polarization, vec![0.0, 20.0])).unwrap();
+            for (r, t) in result.total_reflection.iter().zip(&result.total_transmission) {
+                assert!((r + t - 1.0).abs() < 1e-6, "{:?}: R + T = {}", polarization, r + t);
+            }
+        }
+    }
+
+    #[test]
+    fn test_uniform_layer_matches_transfer_matrix() {
+        let mut config = glass_grating(GratingPolarization::TM, vec![30.0]);
+        config.layers[0].segments.clear();
+        config.layers[0].background = LayerMedium::constant(3.0);
+        config.num_orders = 3;
+        let result = rcwa_internal(&config).unwrap();
+
+        let f = config.frequencies[0];
+        let kx = 2.0 * PI * f * 30f64.to_radians().sin();
+        let stack = [StackLayer { thickness: 0.4, medium: LayerMedium::constant(3.0) }];
+        let tmm = stack_response(&config.incident_medium, &stack, &config.substrate, f, kx, StackPolarization::P);
+        assert!((result.total_reflection[0] - tmm.reflectance).abs() < 1e-9);
+        assert!((result.total_transmission[0] - tmm.transmittance).abs() < 1e-9);
+    }
+}
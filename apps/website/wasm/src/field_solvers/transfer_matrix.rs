@@ -0,0 +1,356 @@
+use num_complex::Complex64;
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+use crate::material_calculations::dispersion::{deserialize_susceptibilities, Susceptibility};
+
+/// Optical constants of a layer, following `Medium`: `index` takes precedence
+/// over `epsilon`, and `epsilon_imag` adds loss (positive = absorbing).
+/// Dispersive terms and `D_conductivity` are applied as in Meep.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LayerMedium {
+    #[serde(default = "unit_epsilon")]
+    pub epsilon: f64,
+    #[serde(default)]
+    pub epsilon_imag: f64,
+    #[serde(default)]
+    pub index: Option<f64>,
+    #[serde(default, rename = "E_susceptibilities", deserialize_with = "deserialize_susceptibilities")]
+    pub susceptibilities: Vec<Susceptibility>,
+    #[serde(default, rename = "D_conductivity")]
+    pub conductivity: f64,
+}
+
+fn unit_epsilon() -> f64 {
+    1.0
+}
+
+impl LayerMedium {
+    pub fn constant(epsilon: f64) -> Self {
+        Self { epsilon, epsilon_imag: 0.0, index: None, susceptibilities: Vec::new(), conductivity: 0.0 }
+    }
+
+    /// Relative permittivity at a frequency in Meep units:
+    /// ε(f) = (1 + iσ_D / 2πf) (ε∞ + Σ χₙ(f)).
+    pub fn epsilon_at(&self, frequency: f64) -> Complex64 {
+        let background = match self.index {
+            Some(n) => Complex64::new(n * n, self.epsilon_imag),
+            None => Complex64::new(self.epsilon, self.epsilon_imag),
+        };
+        let eps = background + self.susceptibilities.iter().map(|s| s.chi(frequency)).sum::<Complex64>();
+        if self.conductivity == 0.0 {
+            return eps;
+        }
+        eps * Complex64::new(1.0, self.conductivity / (2.0 * PI * frequency))
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StackLayer {
+    pub thickness: f64,
+    #[serde(flatten)]
+    pub medium: LayerMedium,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StackPolarization {
+    /// E perpendicular to the plane of incidence (TE).
+    S,
+    /// E in the plane of incidence (TM); amplitudes refer to the magnetic field.
+    P,
+}
+
+/// Complex reflection and transmission amplitudes of a stack.
+#[derive(Clone, Copy, Debug)]
+pub struct StackResponse {
+    pub r: Complex64,
+    pub t: Complex64,
+    pub reflectance: f64,
+    pub transmittance: f64,
+}
+
+type Matrix2 = [[Complex64; 2]; 2];
+
+fn mat_mul(a: &Matrix2, b: &Matrix2) -> Matrix2 {
+    [
+        [a[0][0] * b[0][0] + a[0][1] * b[1][0], a[0][0] * b[0][1] + a[0][1] * b[1][1]],
+        [a[1][0] * b[0][0] + a[1][1] * b[1][0], a[1][0] * b[0][1] + a[1][1] * b[1][1]],
+    ]
+}
+
+fn identity() -> Matrix2 {
+    let one = Complex64::new(1.0, 0.0);
+    let zero = Complex64::new(0.0, 0.0);
+    [[one, zero], [zero, one]]
+}
+
+/// Normal wavevector with Im ≥ 0 (decaying / outgoing branch).
+fn normal_wavevector(k0: f64, epsilon: Complex64, kx: f64) -> Complex64 {
+    let kz = (epsilon * k0 * k0 - kx * kx).sqrt();
+    if kz.im < 0.0 || (kz.im == 0.0 && kz.re < 0.0) { -kz } else { kz }
+}
+
+/// Tangential admittance kz (s) or kz/ε (p).
+fn admittance(kz: Complex64, epsilon: Complex64, polarization: StackPolarization) -> Complex64 {
+    match polarization {
+        StackPolarization::S => kz,
+        StackPolarization::P => kz / epsilon,
+    }
+}
+
+/// Characteristic (Abelès) matrix of a stack of finite layers.
+pub fn characteristic_matrix(
+    layers: &[StackLayer],
+    frequency: f64,
+    kx: f64,
+    polarization: StackPolarization,
+) -> Matrix2 {
+    let k0 = 2.0 * PI * frequency;
+    let i = Complex64::new(0.0, 1.0);
+    layers.iter().fold(identity(), |acc, layer| {
+        let eps = layer.medium.epsilon_at(frequency);
+        let kz = normal_wavevector(k0, eps, kx);
+        let q = admittance(kz, eps, polarization);
+        let delta = kz * layer.thickness;
+        let (c, s) = (delta.cos(), delta.sin());
+        let m = if q.norm() == 0.0 {
+            // grazing in this layer: the limit of sin(δ)/q is the thickness
+            [[c, -i * layer.thickness], [Complex64::new(0.0, 0.0), c]]
+        } else {
+            [[c, -i * s / q], [-i * q * s, c]]
+        };
+        mat_mul(&acc, &m)
+    })
+}
+
+/// Reflection and transmission of a stack between two semi-infinite media.
+/// `kx` is the conserved in-plane wavevector (angular, 2π × Meep units).
+pub fn stack_response(
+    incident: &LayerMedium,
+    layers: &[StackLayer],
+    substrate: &LayerMedium,
+    frequency: f64,
+    kx: f64,
+    polarization: StackPolarization,
+) -> StackResponse {
+    let k0 = 2.0 * PI * frequency;
+    let eps_in = incident.epsilon_at(frequency);
+    let eps_out = substrate.epsilon_at(frequency);
+    let q0 = admittance(normal_wavevector(k0, eps_in, kx), eps_in, polarization);
+    let qs = admittance(normal_wavevector(k0, eps_out, kx), eps_out, polarization);
+
+    let m = characteristic_matrix(layers, frequency, kx, polarization);
+    let a = q0 * m[0][0] + q0 * qs * m[0][1];
+    let b = m[1][0] + qs * m[1][1];
+    let r = (a - b) / (a + b);
+    let t = 2.0 * q0 / (a + b);
+
+    let transmittance = if q0.re > 0.0 { qs.re / q0.re * t.norm_sqr() } else { 0.0 };
+    StackResponse { r, t, reflectance: r.norm_sqr(), transmittance }
+}
+
+#[derive(Deserialize)]
+pub struct MultilayerConfig {
+    #[serde(default = "vacuum")]
+    pub incident_medium: LayerMedium,
+    pub layers: Vec<StackLayer>,
+    #[serde(default = "vacuum")]
+    pub substrate: LayerMedium,
+    pub frequencies: Vec<f64>,
+    /// Angles of incidence in degrees, measured in the incident medium.
+    #[serde(default = "normal_incidence")]
+    pub angles: Vec<f64>,
+}
+
+fn vacuum() -> LayerMedium {
+    LayerMedium::constant(1.0)
+}
+
+fn normal_incidence() -> Vec<f64> {
+    vec![0.0]
+}
+
+/// Spectra for one polarisation, flattened as `[angle][frequency]`.
+#[derive(Serialize, Default)]
+pub struct PolarizationSpectrum {
+    pub reflectance: Vec<f64>,
+    pub transmittance: Vec<f64>,
+    pub absorptance: Vec<f64>,
+    pub reflection_phase: Vec<f64>,
+    pub transmission_phase: Vec<f64>,
+}
+
+#[derive(Serialize)]
+pub struct MultilayerResult {
+    pub frequencies: Vec<f64>,
+    pub angles: Vec<f64>,
+    pub s: PolarizationSpectrum,
+    pub p: PolarizationSpectrum,
+}
+
+pub fn multilayer_spectrum_internal(config: &MultilayerConfig) -> Result<MultilayerResult, String> {
+    if config.layers.iter().any(|l| l.thickness < 0.0) {
+        return Err("layer thickness must not be negative".into());
+    }
+    if config.frequencies.iter().any(|&f| f <= 0.0) {
+        return Err("frequencies must be positive".into());
+    }
+    let mut s = PolarizationSpectrum::default();
+    let mut p = PolarizationSpectrum::default();
+
+    for &angle in &config.angles {
+        for &f in &config.frequencies {
+            let n_in = config.incident_medium.epsilon_at(f).re.sqrt();
+            let kx = 2.0 * PI * f * n_in * angle.to_radians().sin();
+            for (spectrum, polarization) in [(&mut s, StackPolarization::S), (&mut p, StackPolarization::P)] {
+                let res = stack_response(&config.incident_medium, &config.layers, &config.substrate, f, kx, polarization);
+                spectrum.reflectance.push(res.reflectance);
+                spectrum.transmittance.push(res.transmittance);
+                spectrum.absorptance.push(1.0 - res.reflectance - res.transmittance);
+                spectrum.reflection_phase.push(res.r.arg());
+                spectrum.transmission_phase.push(res.t.arg());
+            }
+        }
+    }
+
+    Ok(MultilayerResult {
+        frequencies: config.frequencies.clone(),
+        angles: config.angles.clone(),
+        s,
+        p,
+    })
+}
+
+/// Reflectance, transmittance, absorptance and phase of a 1D multilayer stack
+#[wasm_bindgen]
+pub fn compute_multilayer_spectrum(config: &JsValue) -> Result<JsValue, JsValue> {
+    let config: MultilayerConfig = serde_wasm_bindgen::from_value(config.clone())?;
+    let result = multilayer_spectrum_internal(&config).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+#[derive(Deserialize)]
+pub struct BlochBandConfig {
+    /// Layers of one period of the stack.
+    pub layers: Vec<StackLayer>,
+    pub frequencies: Vec<f64>,
+    /// In-plane wavevector in Meep units (cycles per unit length).
+    #[serde(default)]
+    pub kx: f64,
+    #[serde(default = "default_band_polarization")]
+    pub polarization: StackPolarization,
+}
+
+fn default_band_polarization() -> StackPolarization {
+    StackPolarization::S
+}
+
+#[derive(Serialize)]
+pub struct BlochBandResult {
+    pub period: f64,
+    pub frequencies: Vec<f64>,
+    /// Bloch wavevector along the stack in Meep units, folded into [0, 1/(2Λ)].
+    pub k_real: Vec<f64>,
+    /// Decay rate in Meep units; non-zero inside band gaps.
+    pub k_imag: Vec<f64>,
+    pub in_gap: Vec<bool>,
+}
+
+/// Bloch wavevector from cos(KΛ) = ½ Tr M for one period.
+pub fn bloch_wavevector(
+    layers: &[StackLayer],
+    frequency: f64,
+    kx: f64,
+    polarization: StackPolarization,
+) -> Complex64 {
+    let period: f64 = layers.iter().map(|l| l.thickness).sum();
+    let m = characteristic_matrix(layers, frequency, kx, polarization);
+    let half_trace = 0.5 * (m[0][0] + m[1][1]);
+    let mut k = half_trace.acos() / period;
+    // principal branch of acos has Re in [0, π]; report a decaying wave
+    if k.im < 0.0 {
+        k = -k;
+    }
+    if k.re < 0.0 {
+        k.re = -k.re;
+    }
+    k
+}
+
+pub fn bloch_bands_internal(config: &BlochBandConfig) -> Result<BlochBandResult, String> {
+    let period: f64 = config.layers.iter().map(|l| l.thickness).sum();
+    if period <= 0.0 {
+        return Err("unit cell must have a positive period".into());
+    }
+    let kx = 2.0 * PI * config.kx;
+    let mut k_real = Vec::with_capacity(config.frequencies.len());
+    let mut k_imag = Vec::with_capacity(config.frequencies.len());
+    let mut in_gap = Vec::with_capacity(config.frequencies.len());
+    for &f in &config.frequencies {
+        let k = bloch_wavevector(&config.layers, f, kx, config.polarization);
+        k_real.push(k.re / (2.0 * PI));
+        k_imag.push(k.im / (2.0 * PI));
+        in_gap.push(k.im * period > 1e-6);
+    }
+    Ok(BlochBandResult {
+        period,
+        frequencies: config.frequencies.clone(),
+        k_real,
+        k_imag,
+        in_gap,
+    })
+}
+
+/// Photonic band structure (Bloch k vs ω) of a periodic 1D stack
+#[wasm_bindgen]
+pub fn compute_bloch_bands_1d(config: &JsValue) -> Result<JsValue, JsValue> {
+    let config: BlochBandConfig = serde_wasm_bindgen::from_value(config.clone())?;
+    let result = bloch_bands_internal(&config).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layer(thickness: f64, index: f64) -> StackLayer {
+        StackLayer { thickness, medium: LayerMedium { index: Some(index), ..LayerMedium::constant(1.0) } }
+    }
+
+    #[test]
+    fn test_single_interface_fresnel() {
+        let air = LayerMedium::constant(1.0);
+        let glass = LayerMedium::constant(2.25);
+        let res = stack_response(&air, &[], &glass, 1.0, 0.0, StackPolarization::S);
+        assert!((res.reflectance - 0.04).abs() < 1e-12);
+        assert!((res.reflectance + res.transmittance - 1.0).abs() < 1e-12);
+
+        // p-polarised reflection vanishes at Brewster's angle
+        let theta = 1.5f64.atan();
+        let kx = 2.0 * PI * theta.sin();
+        let res = stack_response(&air, &[], &glass, 1.0, kx, StackPolarization::P);
+        assert!(res.reflectance < 1e-20);
+    }
+
+    #[test]
+    fn test_quarter_wave_stack_gap() {
+        let (n1, n2, f0) = (1.5, 2.5, 1.0);
+        let cell = vec![layer(0.25 / (n1 * f0), n1), layer(0.25 / (n2 * f0), n2)];
+
+        // the centre of the first gap is fully reflecting for a thick mirror
+        let mirror: Vec<StackLayer> = (0..20).flat_map(|_| cell.clone()).collect();
+        let res = stack_response(&LayerMedium::constant(1.0), &mirror, &LayerMedium::constant(1.0), f0, 0.0, StackPolarization::S);
+        assert!(res.reflectance > 0.9999);
+
+        // gap width Δf/f0 = (4/π) asin(|n1 - n2| / (n1 + n2)) around f0
+        let half_width = f0 * 2.0 / PI * ((n2 - n1) / (n1 + n2)).asin();
+        let inside = |f: f64| bloch_wavevector(&cell, f, 0.0, StackPolarization::S).im > 1e-9;
+        assert!(inside(f0));
+        assert!(inside(f0 + 0.95 * half_width));
+        assert!(inside(f0 - 0.95 * half_width));
+        assert!(!inside(f0 + 1.05 * half_width));
+        assert!(!inside(f0 - 1.05 * half_width));
+    }
+}
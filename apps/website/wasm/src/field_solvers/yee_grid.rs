@@ -0,0 +1,250 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::lattice_calculations::voronoi_cells::Vector2D;
+
+/// Uniform 2D Yee grid for a cell centred at the origin (Meep convention).
+///
+/// Pixel (i, j) is centred at `x = -size_x/2 + (i + 0.5)/resolution`, and
+/// flat arrays are stored row-major with x running fastest: `j * nx + i`.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct YeeGrid2D {
+    pub size_x: f64,
+    pub size_y: f64,
+    pub resolution: f64,
+}
+
+impl YeeGrid2D {
+    pub fn new(size_x: f64, size_y: f64, resolution: f64) -> Self {
+        Self { size_x, size_y, resolution }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.resolution <= 0.0 {
+            return Err("resolution must be positive".into());
+        }
+        if self.size_x <= 0.0 || self.size_y <= 0.0 {
+            return Err("cell size must be positive in x and y".into());
+        }
+        Ok(())
+    }
+
+    pub fn nx(&self) -> usize {
+        ((self.size_x * self.resolution).round() as usize).max(1)
+    }
+
+    pub fn ny(&self) -> usize {
+        ((self.size_y * self.resolution).round() as usize).max(1)
+    }
+
+    pub fn pixel_count(&self) -> usize {
+        self.nx() * self.ny()
+    }
+
+    /// Grid spacing Δ = 1/resolution.
+    pub fn dx(&self) -> f64 {
+        1.0 / self.resolution
+    }
+
+    /// x coordinate of a (possibly half-integer) pixel index.
+    pub fn x(&self, i: f64) -> f64 {
+        -0.5 * self.size_x + (i + 0.5) * self.dx()
+    }
+
+    /// y coordinate of a (possibly half-integer) pixel index.
+    pub fn y(&self, j: f64) -> f64 {
+        -0.5 * self.size_y + (j + 0.5) * self.dx()
+    }
+
+    pub fn index(&self, i: usize, j: usize) -> usize {
+        j * self.nx() + i
+    }
+
+    /// Nearest pixel to a point, clamped into the grid.
+    pub fn nearest_pixel(&self, p: Vector2D) -> (usize, usize) {
+        let fi = ((p.x + 0.5 * self.size_x) * self.resolution - 0.5).round();
+        let fj = ((p.y + 0.5 * self.size_y) * self.resolution - 0.5).round();
+        (
+            fi.clamp(0.0, (self.nx() - 1) as f64) as usize,
+            fj.clamp(0.0, (self.ny() - 1) as f64) as usize,
+        )
+    }
+}
+
+/// 2D geometric object with a constant relative permittivity.
+///
+/// Mirrors the in-plane part of Meep's `Block`, `Cylinder`, `Ellipsoid`
+/// and `Prism`; later objects take precedence over earlier ones.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum GeometryShape2D {
+    Block { center: Vector2D, size: Vector2D, epsilon: f64 },
+    Cylinder { center: Vector2D, radius: f64, epsilon: f64 },
+    Ellipsoid { center: Vector2D, size: Vector2D, epsilon: f64 },
+    Prism { vertices: Vec<Vector2D>, epsilon: f64 },
+}
+
+impl GeometryShape2D {
+    pub fn epsilon(&self) -> f64 {
+        match self {
+            GeometryShape2D::Block { epsilon, .. }
+            | GeometryShape2D::Cylinder { epsilon, .. }
+            | GeometryShape2D::Ellipsoid { epsilon, .. }
+            | GeometryShape2D::Prism { epsilon, .. } => *epsilon,
+        }
+    }
+
+    pub fn contains(&self, p: Vector2D) -> bool {
+        match self {
+            GeometryShape2D::Block { center, size, .. } => {
+                (p.x - center.x).abs() <= 0.5 * size.x && (p.y - center.y).abs() <= 0.5 * size.y
+            }
+            GeometryShape2D::Cylinder { center, radius, .. } => {
+                let dx = p.x - center.x;
+                let dy = p.y - center.y;
+                dx * dx + dy * dy <= radius * radius
+            }
+            GeometryShape2D::Ellipsoid { center, size, .. } => {
+                let u = 2.0 * (p.x - center.x) / size.x;
+                let v = 2.0 * (p.y - center.y) / size.y;
+                u * u + v * v <= 1.0
+            }
+            GeometryShape2D::Prism { vertices, .. } => point_in_polygon(p, vertices),
+        }
+    }
+}
+
+// Even-odd ray casting test
+fn point_in_polygon(p: Vector2D, vertices: &[Vector2D]) -> bool {
+    let n = vertices.len();
+    if n < 3 {
+        return false;
+    }
+    let mut inside = false;
+    let mut j = n - 1;
+    for i in 0..n {
+        let a = vertices[i];
+        let b = vertices[j];
+        if (a.y > p.y) != (b.y > p.y) {
+            let x_cross = a.x + (p.y - a.y) * (b.x - a.x) / (b.y - a.y);
+            if p.x < x_cross {
+                inside = !inside;
+            }
+        }
+        j = i;
+    }
+    inside
+}
+
+#[derive(Deserialize)]
+pub struct RasterizeConfig {
+    pub grid: YeeGrid2D,
+    #[serde(default = "default_background_epsilon")]
+    pub background_epsilon: f64,
+    #[serde(default)]
+    pub geometry: Vec<GeometryShape2D>,
+    #[serde(default = "default_subpixel_samples")]
+    pub subpixel_samples: usize,
+}
+
+fn default_background_epsilon() -> f64 {
+    1.0
+}
+
+fn default_subpixel_samples() -> usize {
+    4
+}
+
+#[derive(Serialize)]
+pub struct RasterizedEpsilon {
+    pub nx: usize,
+    pub ny: usize,
+    pub epsilon: Vec<f64>,
+}
+
+/// Rasterise geometry onto the grid, averaging ε over `samples × samples`
+/// sub-pixel points so that interfaces are smoothed.
+pub fn rasterize_epsilon_internal(
+    grid: &YeeGrid2D,
+    background_epsilon: f64,
+    geometry: &[GeometryShape2D],
+    samples: usize,
+) -> Result<Vec<f64>, String> {
+    grid.validate()?;
+    let samples = samples.max(1);
+    let (nx, ny) = (grid.nx(), grid.ny());
+    let mut epsilon = vec![background_epsilon; nx * ny];
+    let weight = 1.0 / (samples * samples) as f64;
+
+    for j in 0..ny {
+        for i in 0..nx {
+            let mut sum = 0.0;
+            for sj in 0..samples {
+                for si in 0..samples {
+                    let p = Vector2D::new(
+                        grid.x(i as f64 - 0.5 + (si as f64 + 0.5) / samples as f64),
+                        grid.y(j as f64 - 0.5 + (sj as f64 + 0.5) / samples as f64),
+                    );
+                    // last object containing the point wins
+                    sum += geometry
+                        .iter()
+                        .rev()
+                        .find(|g| g.contains(p))
+                        .map_or(background_epsilon, |g| g.epsilon());
+                }
+            }
+            epsilon[grid.index(i, j)] = sum * weight;
+        }
+    }
+    Ok(epsilon)
+}
+
+/// Rasterise 2D geometry into a flat permittivity array
+#[wasm_bindgen]
+pub fn rasterize_epsilon_2d(config: &JsValue) -> Result<JsValue, JsValue> {
+    let config: RasterizeConfig = serde_wasm_bindgen::from_value(config.clone())?;
+    let epsilon = rasterize_epsilon_internal(
+        &config.grid,
+        config.background_epsilon,
+        &config.geometry,
+        config.subpixel_samples,
+    )
+    .map_err(|e| JsValue::from_str(&e))?;
+
+    let result = RasterizedEpsilon {
+        nx: config.grid.nx(),
+        ny: config.grid.ny(),
+        epsilon,
+    };
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pixel_centres_staggering_and_index() {
+        let grid = YeeGrid2D::new(2.0, 1.0, 10.0);
+        assert_eq!((grid.nx(), grid.ny(), grid.pixel_count()), (20, 10, 200));
+        assert!((grid.x(0.0) + 0.95).abs() < 1e-12 && (grid.y(0.0) + 0.45).abs() < 1e-12);
+        // half-integer indices sit on the pixel edges, half a cell from the centres
+        assert!((grid.x(-0.5) + 1.0).abs() < 1e-12 && (grid.y(9.5) - 0.5).abs() < 1e-12);
+        assert!((grid.x(3.5) - grid.x(3.0) - 0.5 * grid.dx()).abs() < 1e-12);
+        assert_eq!(grid.index(3, 2), 43);
+        assert_eq!(grid.nearest_pixel(Vector2D::new(grid.x(7.0), grid.y(4.0))), (7, 4));
+        assert_eq!(grid.nearest_pixel(Vector2D::new(5.0, -5.0)), (19, 0));
+    }
+
+    #[test]
+    fn test_rasterize_averages_interface_pixels() {
+        let grid = YeeGrid2D::new(1.0, 1.0, 4.0);
+        // the block edge at x = 0.125 halves the pixel column centred on it
+        let block = GeometryShape2D::Block { center: Vector2D::new(-0.4375, 0.0), size: Vector2D::new(1.125, 2.0), epsilon: 3.0 };
+        let epsilon = rasterize_epsilon_internal(&grid, 1.0, &[block], 4).unwrap();
+        for j in 0..grid.ny() {
+            let row: Vec<f64> = (0..grid.nx()).map(|i| epsilon[grid.index(i, j)]).collect();
+            assert_eq!(row, vec![3.0, 3.0, 2.0, 1.0]);
+        }
+    }
+}
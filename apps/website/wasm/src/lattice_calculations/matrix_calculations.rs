@@ -6,11 +6,188 @@ pub struct Matrix2x2 {
     pub data: [[f64; 2]; 2],
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Matrix3x3 {
     pub data: [[f64; 3]; 3],
 }
 
+/// Cartesian vector, serialised like the TS `Vector3`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Vector3D {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Vector3D {
+    pub fn new(x: f64, y: f64, z: f64) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn to_array(&self) -> [f64; 3] {
+        [self.x, self.y, self.z]
+    }
+
+    pub fn from_array(v: [f64; 3]) -> Self {
+        Self { x: v[0], y: v[1], z: v[2] }
+    }
+
+    pub fn norm(&self) -> f64 {
+        (self.x * self.x + self.y * self.y + self.z * self.z).sqrt()
+    }
+}
+
+impl Matrix3x3 {
+    pub fn identity() -> Self {
+        Self::from_diagonal([1.0, 1.0, 1.0])
+    }
+
+    pub fn from_diagonal(d: [f64; 3]) -> Self {
+        Self { data: [[d[0], 0.0, 0.0], [0.0, d[1], 0.0], [0.0, 0.0, d[2]]] }
+    }
+
+    /// Symmetric matrix in Meep's diag/offdiag form: offdiag = (xy, xz, yz).
+    pub fn symmetric(diag: Vector3D, offdiag: Vector3D) -> Self {
+        Self {
+            data: [
+                [diag.x, offdiag.x, offdiag.y],
+                [offdiag.x, diag.y, offdiag.z],
+                [offdiag.y, offdiag.z, diag.z],
+            ],
+        }
+    }
+
+    pub fn diagonal(&self) -> Vector3D {
+        Vector3D::new(self.data[0][0], self.data[1][1], self.data[2][2])
+    }
+
+    pub fn offdiagonal(&self) -> Vector3D {
+        Vector3D::new(self.data[0][1], self.data[0][2], self.data[1][2])
+    }
+
+    /// Rotation by `angle` radians about `axis` (Rodrigues' formula).
+    pub fn rotation(axis: Vector3D, angle: f64) -> Result<Self, String> {
+        let norm = axis.norm();
+        if norm == 0.0 {
+            return Err("rotation axis must be non-zero".to_string());
+        }
+        let [x, y, z] = axis.to_array().map(|v| v / norm);
+        let (s, c) = angle.sin_cos();
+        let t = 1.0 - c;
+        Ok(Self {
+            data: [
+                [t * x * x + c, t * x * y - s * z, t * x * z + s * y],
+                [t * x * y + s * z, t * y * y + c, t * y * z - s * x],
+                [t * x * z - s * y, t * y * z + s * x, t * z * z + c],
+            ],
+        })
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut out = *self;
+        for i in 0..3 {
+            for j in 0..3 {
+                out.data[i][j] = self.data[j][i];
+            }
+        }
+        out
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        let mut out = Self { data: [[0.0; 3]; 3] };
+        for i in 0..3 {
+            for j in 0..3 {
+                out.data[i][j] = (0..3).map(|k| self.data[i][k] * other.data[k][j]).sum();
+            }
+        }
+        out
+    }
+
+    pub fn mul_vec(&self, v: Vector3D) -> Vector3D {
+        let v = v.to_array();
+        Vector3D::from_array(self.data.map(|row| row[0] * v[0] + row[1] * v[1] + row[2] * v[2]))
+    }
+
+    pub fn determinant(&self) -> f64 {
+        let [[a11, a12, a13], [a21, a22, a23], [a31, a32, a33]] = self.data;
+        a11 * (a22 * a33 - a23 * a32) - a12 * (a21 * a33 - a23 * a31) + a13 * (a21 * a32 - a22 * a31)
+    }
+
+    pub fn inverse(&self) -> Result<Self, String> {
+        let [[a11, a12, a13], [a21, a22, a23], [a31, a32, a33]] = self.data;
+        let det = self.determinant();
+
+        if det.abs() < 1e-10 {
+            return Err("Matrix is singular (determinant is zero)".to_string());
+        }
+
+        let inv_det = 1.0 / det;
+
+        // Calculate cofactor matrix and transpose
+        Ok(Self {
+            data: [
+                [
+                    (a22 * a33 - a23 * a32) * inv_det,
+                    (a13 * a32 - a12 * a33) * inv_det,
+                    (a12 * a23 - a13 * a22) * inv_det,
+                ],
+                [
+                    (a23 * a31 - a21 * a33) * inv_det,
+                    (a11 * a33 - a13 * a31) * inv_det,
+                    (a13 * a21 - a11 * a23) * inv_det,
+                ],
+                [
+                    (a21 * a32 - a22 * a31) * inv_det,
+                    (a12 * a31 - a11 * a32) * inv_det,
+                    (a11 * a22 - a12 * a21) * inv_det,
+                ],
+            ],
+        })
+    }
+
+    /// Eigenvalues (ascending) and unit eigenvectors (as columns) of a
+    /// symmetric matrix, by cyclic Jacobi rotations.
+    pub fn symmetric_eigen(&self) -> ([f64; 3], Self) {
+        let mut a = self.data;
+        let mut v = Self::identity().data;
+        for _ in 0..50 {
+            let off = a[0][1].powi(2) + a[0][2].powi(2) + a[1][2].powi(2);
+            let scale = a.iter().flatten().map(|x| x * x).sum::<f64>();
+            if off <= 1e-30 * scale.max(1e-300) {
+                break;
+            }
+            for (p, q) in [(0, 1), (0, 2), (1, 2)] {
+                if a[p][q] == 0.0 {
+                    continue;
+                }
+                let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let t = if theta == 0.0 { 1.0 } else { t };
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+                for row in a.iter_mut() {
+                    let (akp, akq) = (row[p], row[q]);
+                    row[p] = c * akp - s * akq;
+                    row[q] = s * akp + c * akq;
+                }
+                let (row_p, row_q) = (a[p], a[q]);
+                a[p] = std::array::from_fn(|k| c * row_p[k] - s * row_q[k]);
+                a[q] = std::array::from_fn(|k| s * row_p[k] + c * row_q[k]);
+                for row in v.iter_mut() {
+                    let (vp, vq) = (row[p], row[q]);
+                    row[p] = c * vp - s * vq;
+                    row[q] = s * vp + c * vq;
+                }
+            }
+        }
+        let mut order = [0, 1, 2];
+        order.sort_by(|&i, &j| a[i][i].total_cmp(&a[j][j]));
+        let values = order.map(|i| a[i][i]);
+        let vectors = Self { data: v.map(|row| order.map(|i| row[i])) };
+        (values, vectors)
+    }
+}
+
 /// Calculate the inverse of a 2x2 matrix
 #[wasm_bindgen]
 pub fn invert_matrix_2x2(a11: f64, a12: f64, a21: f64, a22: f64) -> Result<JsValue, String> {
@@ -39,37 +216,10 @@ pub fn invert_matrix_3x3(
     a21: f64, a22: f64, a23: f64,
     a31: f64, a32: f64, a33: f64,
 ) -> Result<JsValue, String> {
-    // Calculate determinant
-    let det = a11 * (a22 * a33 - a23 * a32)
-            - a12 * (a21 * a33 - a23 * a31)
-            + a13 * (a21 * a32 - a22 * a31);
-    
-    if det.abs() < 1e-10 {
-        return Err("Matrix is singular (determinant is zero)".to_string());
-    }
-    
-    let inv_det = 1.0 / det;
-    
-    // Calculate cofactor matrix and transpose
-    let result = Matrix3x3 {
-        data: [
-            [
-                (a22 * a33 - a23 * a32) * inv_det,
-                (a13 * a32 - a12 * a33) * inv_det,
-                (a12 * a23 - a13 * a22) * inv_det,
-            ],
-            [
-                (a23 * a31 - a21 * a33) * inv_det,
-                (a11 * a33 - a13 * a31) * inv_det,
-                (a13 * a21 - a11 * a23) * inv_det,
-            ],
-            [
-                (a21 * a32 - a22 * a31) * inv_det,
-                (a12 * a31 - a11 * a32) * inv_det,
-                (a11 * a22 - a12 * a21) * inv_det,
-            ],
-        ],
+    let matrix = Matrix3x3 {
+        data: [[a11, a12, a13], [a21, a22, a23], [a31, a32, a33]],
     };
+    let result = matrix.inverse()?;
     
     serde_wasm_bindgen::to_value(&result).map_err(|e| e.to_string())
 }
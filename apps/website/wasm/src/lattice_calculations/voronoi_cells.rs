@@ -143,8 +143,8 @@ fn calculate_brillouin_zones_internal(
         let mut attempt_end = zone_idx;
         let polygon = loop {
             let mut this_shell_set: Vec<Vector2D> = Vec::new();
-            for s in zone_idx..=attempt_end {
-                this_shell_set.extend(&shells[s]);
+            for shell in &shells[zone_idx..=attempt_end] {
+                this_shell_set.extend(shell);
             }
 
             match halfspace_intersection_with_neighbors(
@@ -7,11 +7,90 @@ mod lattice_calculations {
     pub mod matrix_calculations;
 }
 
+mod numerical_calculations {
+    pub mod banded_solver;
+    pub mod tridiagonal_eigen;
+    pub mod dense_complex;
+    pub mod least_squares;
+    pub mod fourier_transform;
+    pub mod spectrogram;
+    pub mod special_functions;
+}
+
+mod field_solvers {
+    pub mod yee_grid;
+    pub mod fdfd;
+    pub mod adjoint;
+    pub mod eigenmode_solver;
+    pub mod transfer_matrix;
+    pub mod rcwa;
+    pub mod fdtd_1d;
+    pub mod mie_scattering;
+    pub mod analytic_modes;
+}
+
+mod material_calculations {
+    pub mod dispersion;
+    pub mod dispersion_fitting;
+    pub mod material_library;
+    pub mod kramers_kronig;
+    pub mod tensor_permittivity;
+}
+
+mod simulation_planning {
+    pub mod pml_design;
+    pub mod resolution_advisor;
+    pub mod cost_estimator;
+    pub mod chunk_division;
+    pub mod field_decay;
+    pub mod dft_planner;
+}
+
+mod post_processing {
+    pub mod flux_spectra;
+    pub mod mode_coefficients;
+    pub mod near_to_far;
+    pub mod spectrum_csv;
+    pub mod touchstone;
+}
+
 // Re-export all items from latticePoints module
 pub use lattice_calculations::lattice_points::*;
 pub use lattice_calculations::voronoi_cells::*;
 pub use lattice_calculations::voronoi_separation::*;
 pub use lattice_calculations::matrix_calculations::*;
+pub use numerical_calculations::banded_solver::*;
+pub use numerical_calculations::tridiagonal_eigen::*;
+pub use numerical_calculations::dense_complex::*;
+pub use numerical_calculations::least_squares::*;
+pub use numerical_calculations::fourier_transform::*;
+pub use numerical_calculations::spectrogram::*;
+pub use numerical_calculations::special_functions::*;
+pub use field_solvers::yee_grid::*;
+pub use field_solvers::fdfd::*;
+pub use field_solvers::adjoint::*;
+pub use field_solvers::eigenmode_solver::*;
+pub use field_solvers::transfer_matrix::*;
+pub use field_solvers::rcwa::*;
+pub use field_solvers::fdtd_1d::*;
+pub use field_solvers::mie_scattering::*;
+pub use field_solvers::analytic_modes::*;
+pub use material_calculations::dispersion::*;
+pub use material_calculations::dispersion_fitting::*;
+pub use material_calculations::material_library::*;
+pub use material_calculations::kramers_kronig::*;
+pub use material_calculations::tensor_permittivity::*;
+pub use simulation_planning::pml_design::*;
+pub use simulation_planning::resolution_advisor::*;
+pub use simulation_planning::cost_estimator::*;
+pub use simulation_planning::chunk_division::*;
+pub use simulation_planning::field_decay::*;
+pub use simulation_planning::dft_planner::*;
+pub use post_processing::flux_spectra::*;
+pub use post_processing::mode_coefficients::*;
+pub use post_processing::near_to_far::*;
+pub use post_processing::spectrum_csv::*;
+pub use post_processing::touchstone::*;
 
 /// Adds two 32-bit integers.
 #[wasm_bindgen]
@@ -0,0 +1,368 @@
+use num_complex::Complex64;
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Dispersive susceptibility terms added to ε, mirroring Meep's classes.
+/// `frequency`, `gamma` and `bias` are in Meep frequency units (c/a); the
+/// common 2π cancels so they are used as given.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Susceptibility {
+    /// χ(f) = σ f₀² / (f₀² − f² − i f γ)
+    #[serde(rename = "LorentzianSusceptibility")]
+    Lorentzian {
+        #[serde(default)]
+        frequency: f64,
+        #[serde(default)]
+        gamma: f64,
+        #[serde(default)]
+        sigma: f64,
+    },
+    /// χ(f) = −σ f₀² / (f² + i f γ)
+    #[serde(rename = "DrudeSusceptibility")]
+    Drude {
+        #[serde(default)]
+        frequency: f64,
+        #[serde(default)]
+        gamma: f64,
+        #[serde(default)]
+        sigma: f64,
+    },
+    /// Lorentzian with a stochastic polarization source; the noise only drives
+    /// thermal emission, so the mean response is the plain Lorentzian.
+    #[serde(rename = "NoisyLorentzianSusceptibility")]
+    NoisyLorentzian {
+        #[serde(default)]
+        frequency: f64,
+        #[serde(default)]
+        gamma: f64,
+        #[serde(default)]
+        sigma: f64,
+        #[serde(default)]
+        noise_amp: f64,
+    },
+    #[serde(rename = "NoisyDrudeSusceptibility")]
+    NoisyDrude {
+        #[serde(default)]
+        frequency: f64,
+        #[serde(default)]
+        gamma: f64,
+        #[serde(default)]
+        sigma: f64,
+        #[serde(default)]
+        noise_amp: f64,
+    },
+    /// Lorentzian oscillator precessing about `bias`:
+    /// d²P/dt² + γ dP/dt + b × dP/dt + f₀² P = σ f₀² E.
+    #[serde(rename = "GyrotropicLorentzianSusceptibility")]
+    GyrotropicLorentzian {
+        #[serde(default)]
+        frequency: f64,
+        #[serde(default)]
+        gamma: f64,
+        #[serde(default)]
+        sigma: f64,
+        #[serde(default)]
+        bias: [f64; 3],
+    },
+    /// Drude counterpart of the gyrotropic Lorentzian (no restoring force).
+    #[serde(rename = "GyrotropicDrudeSusceptibility")]
+    GyrotropicDrude {
+        #[serde(default)]
+        frequency: f64,
+        #[serde(default)]
+        gamma: f64,
+        #[serde(default)]
+        sigma: f64,
+        #[serde(default)]
+        bias: [f64; 3],
+    },
+}
+
+pub type Tensor3 = [[Complex64; 3]; 3];
+
+impl Susceptibility {
+    /// Resonance denominator Ω(f) and strength σ f₀² of the term.
+    fn oscillator(&self, f: f64) -> (Complex64, f64) {
+        use Susceptibility::*;
+        match *self {
+            Lorentzian { frequency, gamma, sigma }
+            | NoisyLorentzian { frequency, gamma, sigma, .. }
+            | GyrotropicLorentzian { frequency, gamma, sigma, .. } => {
+                (Complex64::new(frequency * frequency - f * f, -f * gamma), sigma * frequency * frequency)
+            }
+            Drude { frequency, gamma, sigma }
+            | NoisyDrude { frequency, gamma, sigma, .. }
+            | GyrotropicDrude { frequency, gamma, sigma, .. } => {
+                (Complex64::new(-f * f, -f * gamma), sigma * frequency * frequency)
+            }
+        }
+    }
+
+    fn bias(&self) -> Option<[f64; 3]> {
+        match *self {
+            Susceptibility::GyrotropicLorentzian { bias, .. } | Susceptibility::GyrotropicDrude { bias, .. } => Some(bias),
+            _ => None,
+        }
+    }
+
+    /// Full susceptibility tensor at frequency `f`.
+    pub fn chi_tensor(&self, f: f64) -> Tensor3 {
+        let zero = Complex64::new(0.0, 0.0);
+        let (omega, strength) = self.oscillator(f);
+        let mut chi = [[zero; 3]; 3];
+        match self.bias() {
+            None => {
+                for (i, row) in chi.iter_mut().enumerate() {
+                    row[i] = strength / omega;
+                }
+            }
+            Some(b) => {
+                // (Ω I − i f [b]×)⁻¹ = (Ω² I + i f Ω [b]× − f² b bᵀ) / (Ω (Ω² − f² |b|²))
+                let b2 = b.iter().map(|v| v * v).sum::<f64>();
+                let scale = strength / (omega * (omega * omega - f * f * b2));
+                let cross = [[0.0, -b[2], b[1]], [b[2], 0.0, -b[0]], [-b[1], b[0], 0.0]];
+                for i in 0..3 {
+                    for j in 0..3 {
+                        let identity = if i == j { omega * omega } else { zero };
+                        chi[i][j] = scale
+                            * (identity + Complex64::new(0.0, f) * omega * cross[i][j] - f * f * b[i] * b[j]);
+                    }
+                }
+            }
+        }
+        chi
+    }
+
+    /// Scalar susceptibility; for gyrotropic terms the isotropic part (trace / 3).
+    pub fn chi(&self, f: f64) -> Complex64 {
+        if self.bias().is_none() {
+            let (omega, strength) = self.oscillator(f);
+            return strength / omega;
+        }
+        let t = self.chi_tensor(f);
+        (t[0][0] + t[1][1] + t[2][2]) / 3.0
+    }
+
+    /// Parse Meep's repr, e.g. `DrudeSusceptibility(frequency=0.5, gamma=0.01, sigma=2)`
+    /// or `GyrotropicLorentzianSusceptibility(bias=Vector3(0, 0, 1), ...)`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        let open = text.find('(').ok_or_else(|| format!("malformed susceptibility '{}'", text))?;
+        if !text.ends_with(')') {
+            return Err(format!("malformed susceptibility '{}'", text));
+        }
+        let name = text[..open].trim();
+        let args = &text[open + 1..text.len() - 1];
+
+        let mut params = serde_json::Map::new();
+        params.insert("type".into(), name.into());
+        for arg in split_top_level(args).into_iter().filter(|a| !a.trim().is_empty()) {
+            let (key, value) = arg
+                .split_once('=')
+                .ok_or_else(|| format!("expected key=value in '{}'", arg.trim()))?;
+            params.insert(key.trim().into(), parse_value(value.trim())?);
+        }
+        serde_json::from_value(serde_json::Value::Object(params)).map_err(|e| format!("{}: {}", name, e))
+    }
+
+    /// Inverse of [`Susceptibility::parse`], the form stored in `Medium.E_susceptibilities`.
+    pub fn meep_repr(&self) -> String {
+        let value = serde_json::to_value(self).unwrap_or_default();
+        let Some(fields) = value.as_object() else {
+            return String::new();
+        };
+        let name = fields.get("type").and_then(|t| t.as_str()).unwrap_or_default();
+        let args: Vec<String> = fields
+            .iter()
+            .filter(|(key, _)| key.as_str() != "type")
+            .map(|(key, value)| match value.as_array() {
+                Some(v) => format!(
+                    "{}=Vector3({})",
+                    key,
+                    v.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(", ")
+                ),
+                None => format!("{}={}", key, value),
+            })
+            .collect();
+        format!("{}({})", name, args.join(", "))
+    }
+}
+
+/// Split on commas that are not nested inside parentheses.
+fn split_top_level(args: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in args.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&args[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&args[start..]);
+    parts
+}
+
+fn parse_value(value: &str) -> Result<serde_json::Value, String> {
+    let number = |s: &str| {
+        s.trim().parse::<f64>().map_err(|_| format!("invalid number '{}'", s.trim()))
+    };
+    if let Some(inner) = value.strip_prefix("Vector3(").and_then(|v| v.strip_suffix(')')) {
+        let mut components = [0.0; 3];
+        for (c, s) in components.iter_mut().zip(inner.split(',')) {
+            *c = number(s)?;
+        }
+        return Ok(serde_json::json!(components));
+    }
+    Ok(serde_json::json!(number(value)?))
+}
+
+/// Accepts each entry either as a typed object or as a Meep repr string.
+pub fn deserialize_susceptibilities<'de, D>(deserializer: D) -> Result<Vec<Susceptibility>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Entry {
+        Meep(String),
+        Typed(Susceptibility),
+    }
+    Vec::<Entry>::deserialize(deserializer)?
+        .into_iter()
+        .map(|entry| match entry {
+            Entry::Meep(text) => Susceptibility::parse(&text).map_err(serde::de::Error::custom),
+            Entry::Typed(s) => Ok(s),
+        })
+        .collect()
+}
+
+/// n + ik with k ≥ 0 for a passive medium.
+pub fn complex_refractive_index(epsilon: Complex64) -> Complex64 {
+    let n = epsilon.sqrt();
+    if n.im < 0.0 { -n } else { n }
+}
+
+/// Frequency range matching `FreqRange` on the TS side.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct FrequencyRange {
+    pub min: f64,
+    pub max: f64,
+}
+
+fn default_points() -> usize {
+    200
+}
+
+#[derive(Deserialize)]
+pub struct DispersionConfig {
+    pub medium: crate::field_solvers::transfer_matrix::LayerMedium,
+    pub frequency_range: FrequencyRange,
+    #[serde(default = "default_points")]
+    pub points: usize,
+}
+
+#[derive(Serialize)]
+pub struct DispersionResult {
+    pub frequencies: Vec<f64>,
+    pub epsilon_real: Vec<f64>,
+    pub epsilon_imag: Vec<f64>,
+    pub n: Vec<f64>,
+    pub k: Vec<f64>,
+}
+
+pub fn evaluate_dispersion_internal(config: &DispersionConfig) -> Result<DispersionResult, String> {
+    let FrequencyRange { min, max } = config.frequency_range;
+    if min <= 0.0 || max < min {
+        return Err("frequency range must satisfy 0 < min <= max".into());
+    }
+    let points = config.points.max(1);
+    let frequencies: Vec<f64> = (0..points)
+        .map(|i| if points == 1 { min } else { min + (max - min) * i as f64 / (points - 1) as f64 })
+        .collect();
+
+    let mut result = DispersionResult {
+        frequencies: frequencies.clone(),
+        epsilon_real: Vec::with_capacity(points),
+        epsilon_imag: Vec::with_capacity(points),
+        n: Vec::with_capacity(points),
+        k: Vec::with_capacity(points),
+    };
+    for f in frequencies {
+        let eps = config.medium.epsilon_at(f);
+        let index = complex_refractive_index(eps);
+        result.epsilon_real.push(eps.re);
+        result.epsilon_imag.push(eps.im);
+        result.n.push(index.re);
+        result.k.push(index.im);
+    }
+    Ok(result)
+}
+
+/// Complex ε(f) and n + ik of a dispersive medium sampled over a frequency range
+#[wasm_bindgen]
+pub fn evaluate_dispersion(config: &JsValue) -> Result<JsValue, JsValue> {
+    let config: DispersionConfig = serde_wasm_bindgen::from_value(config.clone())?;
+    let result = evaluate_dispersion_internal(&config).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field_solvers::transfer_matrix::LayerMedium;
+
+    #[test]
+    fn test_parse_meep_repr() {
+        let drude = Susceptibility::parse("DrudeSusceptibility(frequency=1e-10, gamma=0.042747, sigma=4.5e21)").unwrap();
+        assert_eq!(drude, Susceptibility::Drude { frequency: 1e-10, gamma: 0.042747, sigma: 4.5e21 });
+
+        let gyro = Susceptibility::parse("GyrotropicLorentzianSusceptibility(bias=Vector3(0, 0, 0.2), frequency=1, gamma=0, sigma=1)").unwrap();
+        assert_eq!(gyro.bias(), Some([0.0, 0.0, 0.2]));
+        assert!(Susceptibility::parse("Susceptibility(frequency=1)").is_err());
+
+        let medium: LayerMedium = serde_json::from_str(
+            r#"{"epsilon": 2.0, "E_susceptibilities": ["LorentzianSusceptibility(frequency=1, gamma=0.1, sigma=0.5)",
+                {"type": "DrudeSusceptibility", "frequency": 2.0, "sigma": 1.0}]}"#,
+        )
+        .unwrap();
+        assert_eq!(medium.susceptibilities.len(), 2);
+    }
+
+    #[test]
+    fn test_lorentzian_is_causal_and_lossy() {
+        let term = Susceptibility::Lorentzian { frequency: 1.0, gamma: 0.1, sigma: 2.0 };
+        assert!((term.chi(0.0) - Complex64::new(2.0, 0.0)).norm() < 1e-12);
+        // absorption peak at resonance, Im χ = σ f₀ / γ
+        assert!((term.chi(1.0) - Complex64::new(0.0, 20.0)).norm() < 1e-12);
+        assert!(term.chi(0.5).im > 0.0 && term.chi(2.0).re < 0.0);
+    }
+
+    #[test]
+    fn test_drude_metal_index() {
+        // plasma frequency 1: ε = 1 − 1/f² below f_p is negative, k dominates
+        let mut medium = LayerMedium::constant(1.0);
+        medium.susceptibilities = vec![Susceptibility::Drude { frequency: 1.0, gamma: 0.0, sigma: 1.0 }];
+        let eps = medium.epsilon_at(0.5);
+        assert!((eps.re + 3.0).abs() < 1e-12);
+        let index = complex_refractive_index(eps);
+        assert!(index.re.abs() < 1e-12 && (index.im - 3f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_gyrotropic_tensor_is_antisymmetric_off_diagonal() {
+        let term = Susceptibility::GyrotropicLorentzian { frequency: 1.0, gamma: 0.05, sigma: 1.0, bias: [0.0, 0.0, 0.3] };
+        let t = term.chi_tensor(0.8);
+        assert!((t[0][1] + t[1][0]).norm() < 1e-12);
+        assert!(t[0][1].norm() > 1e-3);
+        // along the bias the response is the plain Lorentzian
+        let plain = Susceptibility::Lorentzian { frequency: 1.0, gamma: 0.05, sigma: 1.0 };
+        assert!((t[2][2] - plain.chi(0.8)).norm() < 1e-12);
+    }
+}
@@ -0,0 +1,360 @@
+use num_complex::Complex64;
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::material_calculations::dispersion::{complex_refractive_index, FrequencyRange, Susceptibility};
+use crate::numerical_calculations::least_squares::bounded_least_squares;
+
+/// One row of tabulated optical constants; `wavelength` is in units of `a`.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct IndexSample {
+    pub wavelength: f64,
+    pub n: f64,
+    #[serde(default)]
+    pub k: f64,
+}
+
+/// Parse `wavelength, n[, k]` rows, skipping headers and comments.
+/// Commas, semicolons, tabs and spaces are all accepted as separators.
+pub fn parse_index_csv(text: &str) -> Result<Vec<IndexSample>, String> {
+    let mut samples = Vec::new();
+    for (line_no, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line
+            .split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+            .filter(|s| !s.is_empty())
+            .collect();
+        let values: Result<Vec<f64>, _> = fields.iter().map(|s| s.parse::<f64>()).collect();
+        match values {
+            Ok(v) if v.len() >= 2 => samples.push(IndexSample { wavelength: v[0], n: v[1], k: v.get(2).copied().unwrap_or(0.0) }),
+            // a non-numeric first row is a header
+            Err(_) if samples.is_empty() => continue,
+            _ => return Err(format!("line {}: expected 'wavelength, n[, k]'", line_no + 1)),
+        }
+    }
+    Ok(samples)
+}
+
+/// Which terms a parameter vector describes:
+/// `[ε∞, (σ_D, γ_D)?, (f₀, γ, σ) × lorentzians]`.
+#[derive(Clone, Copy, Debug)]
+struct ModelLayout {
+    drude: bool,
+    lorentzians: usize,
+}
+
+impl ModelLayout {
+    fn terms(&self) -> usize {
+        self.lorentzians + self.drude as usize
+    }
+
+    /// Drude terms use Meep's convention frequency = 1, sigma = f_p².
+    fn susceptibilities(&self, p: &[f64]) -> Vec<Susceptibility> {
+        let mut terms = Vec::with_capacity(self.terms());
+        let mut offset = 1;
+        if self.drude {
+            terms.push(Susceptibility::Drude { frequency: 1.0, gamma: p[2], sigma: p[1] });
+            offset = 3;
+        }
+        for l in 0..self.lorentzians {
+            let q = &p[offset + 3 * l..offset + 3 * l + 3];
+            terms.push(Susceptibility::Lorentzian { frequency: q[0], gamma: q[1], sigma: q[2] });
+        }
+        terms
+    }
+
+    fn epsilon(&self, p: &[f64], f: f64) -> Complex64 {
+        Complex64::new(p[0], 0.0) + self.susceptibilities(p).iter().map(|s| s.chi(f)).sum::<Complex64>()
+    }
+
+    /// Box constraints: ε∞ ≥ 1 and non-negative strengths and damping keep
+    /// Im ε ≥ 0 at every frequency and the time-domain update stable.
+    fn bounds(&self, f_min: f64, f_max: f64) -> (Vec<f64>, Vec<f64>) {
+        let mut lower = vec![1.0];
+        let mut upper = vec![f64::INFINITY];
+        if self.drude {
+            lower.extend([0.0, 0.0]);
+            upper.extend([f64::INFINITY, 10.0 * f_max]);
+        }
+        for _ in 0..self.lorentzians {
+            lower.extend([0.01 * f_min, 0.0, 0.0]);
+            upper.extend([100.0 * f_max, 10.0 * f_max, f64::INFINITY]);
+        }
+        (lower, upper)
+    }
+}
+
+struct FitData {
+    frequencies: Vec<f64>,
+    epsilon: Vec<Complex64>,
+    weights: Vec<f64>,
+}
+
+impl FitData {
+    fn residual(&self, layout: ModelLayout, p: &[f64]) -> Vec<f64> {
+        let mut r = Vec::with_capacity(2 * self.frequencies.len());
+        for ((&f, &eps), &w) in self.frequencies.iter().zip(&self.epsilon).zip(&self.weights) {
+            let d = (layout.epsilon(p, f) - eps) / w;
+            r.push(d.re);
+            r.push(d.im);
+        }
+        r
+    }
+
+    fn errors(&self, layout: ModelLayout, p: &[f64]) -> (f64, f64) {
+        let e: Vec<f64> = self
+            .frequencies
+            .iter()
+            .zip(&self.epsilon)
+            .zip(&self.weights)
+            .map(|((&f, &eps), &w)| (layout.epsilon(p, f) - eps).norm() / w)
+            .collect();
+        let rms = (e.iter().map(|v| v * v).sum::<f64>() / e.len() as f64).sqrt();
+        (rms, e.iter().cloned().fold(0.0, f64::max))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Candidate {
+    layout: ModelLayout,
+    params: Vec<f64>,
+    rms: f64,
+}
+
+fn fit_candidate(data: &FitData, layout: ModelLayout, x0: &[f64], f_min: f64, f_max: f64) -> Option<Candidate> {
+    let (lower, upper) = layout.bounds(f_min, f_max);
+    let fit = bounded_least_squares(|p| data.residual(layout, p), x0, &lower, &upper, 300).ok()?;
+    let (rms, _) = data.errors(layout, &fit.params);
+    rms.is_finite().then_some(Candidate { layout, params: fit.params, rms })
+}
+
+/// Starting points for one more Lorentzian: at the worst absorption misfit,
+/// and as far-UV / far-IR poles that account for background dispersion.
+fn lorentzian_seeds(data: &FitData, current: &Candidate, f_min: f64, f_max: f64) -> Vec<[f64; 3]> {
+    let misfit: Vec<Complex64> = data
+        .frequencies
+        .iter()
+        .zip(&data.epsilon)
+        .map(|(&f, &eps)| eps - current.layout.epsilon(&current.params, f))
+        .collect();
+    let (peak, peak_im) = misfit
+        .iter()
+        .enumerate()
+        .map(|(i, d)| (i, d.im))
+        .fold((0, f64::NEG_INFINITY), |best, c| if c.1 > best.1 { c } else { best });
+    let mean_re = misfit.iter().map(|d| d.re).sum::<f64>() / misfit.len() as f64;
+
+    let f_peak = data.frequencies[peak];
+    let mut seeds = vec![[2.0 * f_max, 0.1 * f_max, mean_re.abs().max(0.1)], [0.5 * f_min, 0.1 * f_min, mean_re.abs().max(0.1)]];
+    if peak_im > 0.0 {
+        // Im χ(f₀) = σ f₀ / γ
+        let gamma = 0.1 * f_peak;
+        seeds.insert(0, [f_peak, gamma, peak_im * gamma / f_peak]);
+    }
+    seeds
+}
+
+fn extend_params(current: &Candidate, drude: Option<[f64; 2]>, lorentzian: Option<[f64; 3]>) -> (ModelLayout, Vec<f64>) {
+    let mut layout = current.layout;
+    let mut params = vec![current.params[0]];
+    let rest_start = if layout.drude { 3 } else { 1 };
+    if layout.drude {
+        params.extend_from_slice(&current.params[1..3]);
+    } else if let Some(d) = drude {
+        params.extend(d);
+        layout.drude = true;
+    }
+    params.extend_from_slice(&current.params[rest_start..]);
+    if let Some(l) = lorentzian {
+        params.extend(l);
+        layout.lorentzians += 1;
+    }
+    (layout, params)
+}
+
+fn default_max_terms() -> usize {
+    4
+}
+
+fn default_tolerance() -> f64 {
+    0.01
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+pub struct DispersionFitConfig {
+    #[serde(default)]
+    pub samples: Vec<IndexSample>,
+    /// Alternative to `samples`: raw `wavelength, n, k` text.
+    #[serde(default)]
+    pub csv: Option<String>,
+    /// Fitting window in Meep frequency units; defaults to the data span.
+    #[serde(default)]
+    pub valid_freq_range: Option<FrequencyRange>,
+    #[serde(default = "default_max_terms")]
+    pub max_terms: usize,
+    /// Target RMS of |Δε| / max(|ε|, 1); fitting stops once it is reached.
+    #[serde(default = "default_tolerance")]
+    pub tolerance: f64,
+    #[serde(default = "default_true")]
+    pub allow_drude: bool,
+}
+
+#[derive(Serialize)]
+pub struct DispersionFitResult {
+    pub epsilon: f64,
+    pub susceptibilities: Vec<Susceptibility>,
+    /// Terms in Meep repr form, ready for `Medium.E_susceptibilities`.
+    #[serde(rename = "E_susceptibilities")]
+    pub meep_susceptibilities: Vec<String>,
+    pub valid_freq_range: FrequencyRange,
+    pub rms_error: f64,
+    pub max_error: f64,
+    /// Im ε ≥ 0 over the window (guaranteed by the bounds, checked on a grid).
+    pub passive: bool,
+    pub frequencies: Vec<f64>,
+    pub fitted_n: Vec<f64>,
+    pub fitted_k: Vec<f64>,
+}
+
+pub fn fit_dispersion_internal(config: &DispersionFitConfig) -> Result<DispersionFitResult, String> {
+    let mut samples = config.samples.clone();
+    if let Some(csv) = &config.csv {
+        samples.extend(parse_index_csv(csv)?);
+    }
+    if samples.iter().any(|s| s.wavelength <= 0.0) {
+        return Err("wavelengths must be positive".into());
+    }
+    let mut points: Vec<(f64, Complex64)> = samples
+        .iter()
+        .map(|s| (1.0 / s.wavelength, Complex64::new(s.n, s.k).powi(2)))
+        .filter(|(f, _)| config.valid_freq_range.is_none_or(|r| *f >= r.min && *f <= r.max))
+        .collect();
+    if points.len() < 2 {
+        return Err("need at least two samples inside the fitting window".into());
+    }
+    points.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let data = FitData {
+        frequencies: points.iter().map(|p| p.0).collect(),
+        epsilon: points.iter().map(|p| p.1).collect(),
+        weights: points.iter().map(|p| p.1.norm().max(1.0)).collect(),
+    };
+    let f_min = data.frequencies[0];
+    let f_max = *data.frequencies.last().unwrap();
+    let window = config.valid_freq_range.unwrap_or(FrequencyRange { min: f_min, max: f_max });
+
+    let eps_high = data.epsilon.last().unwrap().re.max(1.0);
+    let base = ModelLayout { drude: false, lorentzians: 0 };
+    let mut best = fit_candidate(&data, base, &[eps_high], f_min, f_max)
+        .ok_or("failed to fit a constant permittivity")?;
+
+    while best.rms > config.tolerance && best.layout.terms() < config.max_terms {
+        let mut trials = Vec::new();
+        for seed in lorentzian_seeds(&data, &best, f_min, f_max) {
+            trials.push(extend_params(&best, None, Some(seed)));
+        }
+        if config.allow_drude && !best.layout.drude {
+            let low = data.epsilon[0] - best.layout.epsilon(&best.params, f_min);
+            // χ_D ≈ −σ / f² well above the damping rate
+            let sigma = (-low.re * f_min * f_min).max(1e-3 * f_min * f_min);
+            trials.push(extend_params(&best, Some([sigma, 0.1 * f_min]), None));
+        }
+        let next = trials
+            .into_iter()
+            .filter_map(|(layout, x0)| fit_candidate(&data, layout, &x0, f_min, f_max))
+            .min_by(|a, b| a.rms.total_cmp(&b.rms));
+        match next {
+            Some(c) if c.rms < 0.99 * best.rms => best = c,
+            _ => break,
+        }
+    }
+
+    let susceptibilities = best.layout.susceptibilities(&best.params);
+    let (rms_error, max_error) = data.errors(best.layout, &best.params);
+    let grid: Vec<f64> = (0..200).map(|i| window.min + (window.max - window.min) * i as f64 / 199.0).collect();
+    let passive = grid.iter().all(|&f| f <= 0.0 || best.layout.epsilon(&best.params, f).im >= -1e-12);
+    let fitted: Vec<Complex64> = data
+        .frequencies
+        .iter()
+        .map(|&f| complex_refractive_index(best.layout.epsilon(&best.params, f)))
+        .collect();
+
+    Ok(DispersionFitResult {
+        epsilon: best.params[0],
+        meep_susceptibilities: susceptibilities.iter().map(|s| s.meep_repr()).collect(),
+        susceptibilities,
+        valid_freq_range: window,
+        rms_error,
+        max_error,
+        passive,
+        frequencies: data.frequencies.clone(),
+        fitted_n: fitted.iter().map(|n| n.re).collect(),
+        fitted_k: fitted.iter().map(|n| n.im).collect(),
+    })
+}
+
+/// Fit a passive Lorentz–Drude model to tabulated (wavelength, n, k) data
+#[wasm_bindgen]
+pub fn fit_dispersion(config: &JsValue) -> Result<JsValue, JsValue> {
+    let config: DispersionFitConfig = serde_wasm_bindgen::from_value(config.clone())?;
+    let result = fit_dispersion_internal(&config).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tabulate(epsilon: impl Fn(f64) -> Complex64, wavelengths: impl Iterator<Item = f64>) -> Vec<IndexSample> {
+        wavelengths
+            .map(|wl| {
+                let n = complex_refractive_index(epsilon(1.0 / wl));
+                IndexSample { wavelength: wl, n: n.re, k: n.im }
+            })
+            .collect()
+    }
+
+    fn config(samples: Vec<IndexSample>) -> DispersionFitConfig {
+        DispersionFitConfig {
+            samples,
+            csv: None,
+            valid_freq_range: None,
+            max_terms: 3,
+            tolerance: 1e-3,
+            allow_drude: true,
+        }
+    }
+
+    #[test]
+    fn test_recovers_single_lorentzian() {
+        let truth = Susceptibility::Lorentzian { frequency: 1.5, gamma: 0.2, sigma: 0.8 };
+        let samples = tabulate(|f| 2.0 + truth.chi(f), (0..60).map(|i| 0.4 + 0.02 * i as f64));
+        let fit = fit_dispersion_internal(&config(samples)).unwrap();
+        assert!(fit.rms_error < 1e-3, "rms {}", fit.rms_error);
+        assert!(fit.passive);
+        assert_eq!(fit.susceptibilities.len(), 1);
+    }
+
+    #[test]
+    fn test_fits_drude_metal_from_csv() {
+        let truth = Susceptibility::Drude { frequency: 1.0, gamma: 0.05, sigma: 9.0 };
+        let rows: Vec<String> = tabulate(|f| 1.0 + truth.chi(f), (0..40).map(|i| 0.5 + 0.025 * i as f64))
+            .iter()
+            .map(|s| format!("{},{},{}", s.wavelength, s.n, s.k))
+            .collect();
+        let mut cfg = config(Vec::new());
+        cfg.csv = Some(format!("wl,n,k\n{}", rows.join("\n")));
+        let fit = fit_dispersion_internal(&cfg).unwrap();
+        assert!(fit.rms_error < 1e-3, "rms {}", fit.rms_error);
+        assert!(fit.passive);
+        assert!(fit.meep_susceptibilities[0].starts_with("DrudeSusceptibility("));
+        let parsed = Susceptibility::parse(&fit.meep_susceptibilities[0]).unwrap();
+        assert_eq!(parsed, fit.susceptibilities[0]);
+    }
+}
@@ -0,0 +1,223 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::numerical_calculations::fourier_transform::hilbert_transform;
+
+/// Linear interpolation on sorted abscissae, clamped at both ends.
+fn interpolate(xs: &[f64], ys: &[f64], x: f64) -> f64 {
+    if x <= xs[0] {
+        return ys[0];
+    }
+    let last = xs.len() - 1;
+    if x >= xs[last] {
+        return ys[last];
+    }
+    let i = xs.partition_point(|&v| v <= x) - 1;
+    let t = (x - xs[i]) / (xs[i + 1] - xs[i]);
+    ys[i] + t * (ys[i + 1] - ys[i])
+}
+
+fn default_padding() -> usize {
+    8
+}
+
+fn default_grid_points() -> usize {
+    4096
+}
+
+fn default_tolerance() -> f64 {
+    0.05
+}
+
+#[derive(Deserialize)]
+pub struct KramersKronigConfig {
+    pub frequencies: Vec<f64>,
+    pub epsilon_real: Vec<f64>,
+    pub epsilon_imag: Vec<f64>,
+    /// The spectrum is zero-padded to `padding` × the highest frequency.
+    #[serde(default = "default_padding")]
+    pub padding: usize,
+    /// Uniform samples between 0 and the highest frequency.
+    #[serde(default = "default_grid_points")]
+    pub grid_points: usize,
+    /// Relative deviation above which a frequency is flagged.
+    #[serde(default = "default_tolerance")]
+    pub tolerance: f64,
+}
+
+#[derive(Serialize)]
+pub struct KramersKronigResult {
+    pub frequencies: Vec<f64>,
+    /// Re ε reconstructed from Im ε, plus the fitted ε∞.
+    pub epsilon_real_kk: Vec<f64>,
+    /// Im ε reconstructed from Re ε − ε∞.
+    pub epsilon_imag_kk: Vec<f64>,
+    pub real_deviation: Vec<f64>,
+    pub imag_deviation: Vec<f64>,
+    /// max(|Δ Re|, |Δ Im|) / max(|ε|, 1) per frequency.
+    pub relative_deviation: Vec<f64>,
+    pub epsilon_infinity: f64,
+    pub max_relative_deviation: f64,
+    pub causal: bool,
+    pub warnings: Vec<String>,
+}
+
+/// Kramers–Kronig consistency of tabulated ε(f). With fields ∝ exp(−iωt),
+/// χ = ε − ε∞ is analytic in the upper half plane and Hermitian, so on the
+/// real axis Im χ = H[Re χ] and Re χ = −H[Im χ]. Both are evaluated by FFT
+/// on a uniform, zero-padded grid over (−F, F) using the even/odd symmetry.
+pub fn kramers_kronig_internal(config: &KramersKronigConfig) -> Result<KramersKronigResult, String> {
+    let n_data = config.frequencies.len();
+    if n_data < 3 || config.epsilon_real.len() != n_data || config.epsilon_imag.len() != n_data {
+        return Err("need at least three frequencies with matching epsilon_real and epsilon_imag".into());
+    }
+    if config.frequencies.iter().any(|&f| f <= 0.0) {
+        return Err("frequencies must be positive".into());
+    }
+    let mut order: Vec<usize> = (0..n_data).collect();
+    order.sort_by(|&a, &b| config.frequencies[a].total_cmp(&config.frequencies[b]));
+    let freqs: Vec<f64> = order.iter().map(|&i| config.frequencies[i]).collect();
+    let eps_re: Vec<f64> = order.iter().map(|&i| config.epsilon_real[i]).collect();
+    let eps_im: Vec<f64> = order.iter().map(|&i| config.epsilon_imag[i]).collect();
+
+    let f_max = freqs[n_data - 1];
+    let m = config.grid_points.max(16);
+    let df = f_max / m as f64;
+    let n = (2 * config.padding.max(1) * m).next_power_of_two();
+    // index j ↔ frequency jΔ for j < n/2, (j − n)Δ otherwise
+    let grid_frequency = |j: usize| if j < n / 2 { j as f64 * df } else { (j as f64 - n as f64) * df };
+    let (last_re, last_im) = (eps_re[n_data - 1], eps_im[n_data - 1]);
+
+    // Im ε is odd; below the first sample it falls linearly to 0 and above
+    // the last it continues with the Lorentzian tail ∝ f⁻³
+    let imag_at = |f: f64| {
+        if f < freqs[0] {
+            eps_im[0] * f / freqs[0]
+        } else if f > f_max {
+            last_im * (f_max / f).powi(3)
+        } else {
+            interpolate(&freqs, &eps_im, f)
+        }
+    };
+    let odd_imag: Vec<f64> = (0..n)
+        .map(|j| {
+            let f = grid_frequency(j);
+            f.signum() * imag_at(f.abs())
+        })
+        .collect();
+    let real_from_imag: Vec<f64> = hilbert_transform(&odd_imag).iter().map(|v| -v).collect();
+    let kk_re_at = |f: f64| interpolate_grid(&real_from_imag, df, f);
+
+    // ε∞ as the constant that best aligns the reconstruction with the data
+    let epsilon_infinity = freqs.iter().zip(&eps_re).map(|(&f, &e)| e - kk_re_at(f)).sum::<f64>() / n_data as f64;
+
+    // Re χ is even and decays as f⁻² above the data
+    let real_at = |f: f64| {
+        if f > f_max {
+            (last_re - epsilon_infinity) * (f_max / f).powi(2)
+        } else {
+            interpolate(&freqs, &eps_re, f) - epsilon_infinity
+        }
+    };
+    let even_real: Vec<f64> = (0..n).map(|j| real_at(grid_frequency(j).abs())).collect();
+    let imag_from_real = hilbert_transform(&even_real);
+
+    let mut result = KramersKronigResult {
+        frequencies: freqs.clone(),
+        epsilon_real_kk: Vec::with_capacity(n_data),
+        epsilon_imag_kk: Vec::with_capacity(n_data),
+        real_deviation: Vec::with_capacity(n_data),
+        imag_deviation: Vec::with_capacity(n_data),
+        relative_deviation: Vec::with_capacity(n_data),
+        epsilon_infinity,
+        max_relative_deviation: 0.0,
+        causal: true,
+        warnings: Vec::new(),
+    };
+    for i in 0..n_data {
+        let re = epsilon_infinity + kk_re_at(freqs[i]);
+        let im = interpolate_grid(&imag_from_real, df, freqs[i]);
+        let d_re = re - eps_re[i];
+        let d_im = im - eps_im[i];
+        let scale = eps_re[i].hypot(eps_im[i]).max(1.0);
+        let relative = d_re.abs().max(d_im.abs()) / scale;
+        result.epsilon_real_kk.push(re);
+        result.epsilon_imag_kk.push(im);
+        result.real_deviation.push(d_re);
+        result.imag_deviation.push(d_im);
+        result.relative_deviation.push(relative);
+        result.max_relative_deviation = result.max_relative_deviation.max(relative);
+    }
+
+    let flagged = result.relative_deviation.iter().filter(|&&d| d > config.tolerance).count();
+    if flagged > 0 {
+        result.causal = false;
+        result.warnings.push(format!(
+            "{} of {} frequencies deviate from Kramers–Kronig by more than {:.0}% (max {:.1}%)",
+            flagged,
+            n_data,
+            100.0 * config.tolerance,
+            100.0 * result.max_relative_deviation
+        ));
+    }
+    if eps_im.iter().any(|&v| v < 0.0) {
+        result.warnings.push("Im ε < 0 indicates gain; the data is not passive".into());
+    }
+    if eps_im[0] * freqs[0] > 0.1 * eps_im.iter().cloned().fold(0.0, f64::max) * freqs[n_data - 1] {
+        result.warnings.push(
+            "Im ε grows towards zero frequency; a DC conductivity term is not captured by the ε∞ + χ relation".into(),
+        );
+    }
+    Ok(result)
+}
+
+/// Value at frequency `f ≥ 0` of a uniform grid starting at 0 with step `df`.
+fn interpolate_grid(values: &[f64], df: f64, f: f64) -> f64 {
+    let x = f / df;
+    let i = x.floor() as usize;
+    let t = x - i as f64;
+    values[i] * (1.0 - t) + values[i + 1] * t
+}
+
+/// Kramers–Kronig consistency check of tabulated complex permittivity
+#[wasm_bindgen]
+pub fn check_kramers_kronig(config: &JsValue) -> Result<JsValue, JsValue> {
+    let config: KramersKronigConfig = serde_wasm_bindgen::from_value(config.clone())?;
+    let result = kramers_kronig_internal(&config).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material_calculations::dispersion::Susceptibility;
+
+    fn lorentzian_data(sign: f64) -> KramersKronigConfig {
+        let term = Susceptibility::Lorentzian { frequency: 1.0, gamma: 0.1, sigma: 1.0 };
+        let frequencies: Vec<f64> = (1..=400).map(|i| i as f64 * 0.01).collect();
+        let eps: Vec<_> = frequencies.iter().map(|&f| 2.0 + term.chi(f)).collect();
+        KramersKronigConfig {
+            epsilon_real: eps.iter().map(|e| e.re).collect(),
+            epsilon_imag: eps.iter().map(|e| sign * e.im).collect(),
+            frequencies,
+            padding: default_padding(),
+            grid_points: default_grid_points(),
+            tolerance: default_tolerance(),
+        }
+    }
+
+    #[test]
+    fn test_lorentzian_is_consistent() {
+        let result = kramers_kronig_internal(&lorentzian_data(1.0)).unwrap();
+        assert!(result.causal, "max deviation {}", result.max_relative_deviation);
+        assert!((result.epsilon_infinity - 2.0).abs() < 0.01);
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_flags_time_reversed_response() {
+        let result = kramers_kronig_internal(&lorentzian_data(-1.0)).unwrap();
+        assert!(!result.causal);
+        assert!(result.max_relative_deviation > 0.5);
+    }
+}
@@ -0,0 +1,577 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::field_solvers::transfer_matrix::LayerMedium;
+use crate::material_calculations::dispersion::{
+    complex_refractive_index, DispersionResult, FrequencyRange, Susceptibility,
+};
+
+/// Speed of light in m/s, as in `physicalUnitsHelper`.
+pub const SPEED_OF_LIGHT: f64 = 299_792_458.0;
+
+/// hc in eV·µm, for converting Lorentz–Drude tables given in eV.
+pub const EV_UM: f64 = 1.239_841_93;
+
+/// Project length unit, serialised like the TS `LengthUnit` enum.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LengthUnit {
+    #[serde(rename = "am")]
+    Am,
+    #[serde(rename = "fm")]
+    Fm,
+    #[serde(rename = "pm")]
+    Pm,
+    #[serde(rename = "nm")]
+    Nm,
+    #[serde(rename = "\u{3bc}m", alias = "\u{b5}m", alias = "um")]
+    Um,
+    #[serde(rename = "mm")]
+    Mm,
+    #[serde(rename = "cm")]
+    Cm,
+    #[serde(rename = "m")]
+    M,
+    #[serde(rename = "km")]
+    Km,
+}
+
+impl LengthUnit {
+    pub fn meters(&self) -> f64 {
+        match self {
+            LengthUnit::Am => 1e-18,
+            LengthUnit::Fm => 1e-15,
+            LengthUnit::Pm => 1e-12,
+            LengthUnit::Nm => 1e-9,
+            LengthUnit::Um => 1e-6,
+            LengthUnit::Mm => 1e-3,
+            LengthUnit::Cm => 1e-2,
+            LengthUnit::M => 1.0,
+            LengthUnit::Km => 1e3,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            LengthUnit::Am => "am",
+            LengthUnit::Fm => "fm",
+            LengthUnit::Pm => "pm",
+            LengthUnit::Nm => "nm",
+            LengthUnit::Um => "\u{3bc}m",
+            LengthUnit::Mm => "mm",
+            LengthUnit::Cm => "cm",
+            LengthUnit::M => "m",
+            LengthUnit::Km => "km",
+        }
+    }
+}
+
+/// The project's characteristic length `a` and its unit.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct UnitSystem {
+    pub a: f64,
+    pub unit: LengthUnit,
+}
+
+impl UnitSystem {
+    pub fn a_in_meters(&self) -> f64 {
+        self.a * self.unit.meters()
+    }
+
+    /// Multiply a frequency in c/µm by this to get Meep units (c/a).
+    pub fn frequency_scale(&self) -> f64 {
+        self.a_in_meters() / 1e-6
+    }
+
+    pub fn frequency_to_hz(&self, frequency: f64) -> f64 {
+        frequency * SPEED_OF_LIGHT / self.a_in_meters()
+    }
+
+    pub fn hz_to_frequency(&self, hertz: f64) -> f64 {
+        hertz * self.a_in_meters() / SPEED_OF_LIGHT
+    }
+
+    /// Meep frequency of a vacuum wavelength given in µm.
+    pub fn wavelength_um_to_frequency(&self, wavelength_um: f64) -> f64 {
+        self.frequency_scale() / wavelength_um
+    }
+}
+
+/// A library material. Dispersion parameters are stored in c/µm and
+/// rescaled to the project's `a` on evaluation.
+#[derive(Clone, Debug, Serialize)]
+pub struct MaterialEntry {
+    pub name: &'static str,
+    pub abbreviation: &'static str,
+    pub category: &'static str,
+    pub epsilon: f64,
+    pub susceptibilities: Vec<Susceptibility>,
+    /// D_conductivity in c/µm.
+    pub conductivity: f64,
+    pub chi2: f64,
+    pub chi3: f64,
+    /// Validity window as vacuum wavelengths in µm (shortest, longest).
+    pub valid_wavelength_um: (f64, f64),
+    pub reference: &'static str,
+}
+
+/// Sellmeier terms B λ² / (λ² − C) with C in µm² are lossless Lorentzians
+/// with f₀ = 1/√C and σ = B.
+fn sellmeier(terms: &[(f64, f64)]) -> Vec<Susceptibility> {
+    terms
+        .iter()
+        .map(|&(b, c)| Susceptibility::Lorentzian { frequency: 1.0 / c.sqrt(), gamma: 0.0, sigma: b })
+        .collect()
+}
+
+/// Literature Sellmeier terms with every strength scaled by one factor so
+/// that n(`anchor_um`) equals the preset index, keeping the fitted dispersion.
+fn rescaled(index: f64, anchor_um: f64, terms: &[(f64, f64)]) -> Vec<Susceptibility> {
+    let l2 = anchor_um * anchor_um;
+    let chi: f64 = terms.iter().map(|&(b, c)| b * l2 / (l2 - c)).sum();
+    let factor = (index * index - 1.0) / chi;
+    sellmeier(&terms.iter().map(|&(b, c)| (b * factor, c)).collect::<Vec<_>>())
+}
+
+/// Single-pole Sellmeier with its resonance at `resonance_um`, scaled so
+/// that n(`anchor_um`) equals the preset index.
+fn anchored(index: f64, anchor_um: f64, resonance_um: f64) -> Vec<Susceptibility> {
+    let f0 = 1.0 / resonance_um;
+    let f = 1.0 / anchor_um;
+    let sigma = (index * index - 1.0) * (f0 * f0 - f * f) / (f0 * f0);
+    vec![Susceptibility::Lorentzian { frequency: f0, gamma: 0.0, sigma }]
+}
+
+/// Rakić et al. (1998) Lorentz–Drude model: plasma frequency and
+/// (strength, resonance, damping) rows in eV, the first row being Drude.
+fn lorentz_drude(plasma_ev: f64, terms: &[(f64, f64, f64)]) -> Vec<Susceptibility> {
+    let plasma = plasma_ev / EV_UM;
+    terms
+        .iter()
+        .map(|&(strength, resonance_ev, damping_ev)| {
+            let gamma = damping_ev / EV_UM;
+            if resonance_ev == 0.0 {
+                Susceptibility::Drude { frequency: 1.0, gamma, sigma: strength * plasma * plasma }
+            } else {
+                let frequency = resonance_ev / EV_UM;
+                Susceptibility::Lorentzian { frequency, gamma, sigma: strength * plasma * plasma / (frequency * frequency) }
+            }
+        })
+        .collect()
+}
+
+fn entry(
+    name: &'static str,
+    abbreviation: &'static str,
+    category: &'static str,
+    susceptibilities: Vec<Susceptibility>,
+    valid_wavelength_um: (f64, f64),
+    reference: &'static str,
+) -> MaterialEntry {
+    MaterialEntry {
+        name,
+        abbreviation,
+        category,
+        epsilon: 1.0,
+        susceptibilities,
+        conductivity: 0.0,
+        chi2: 0.0,
+        chi3: 0.0,
+        valid_wavelength_um,
+        reference,
+    }
+}
+
+/// Library counterparts of the presets in `meepMaterialPresets.ts`.
+pub fn material_library() -> Vec<MaterialEntry> {
+    const BASIC: &str = "Basic";
+    const SEMI: &str = "Semiconductors";
+    const NONLINEAR: &str = "Non-Linear Materials";
+    const METALS: &str = "Plasmonic Metals";
+    const ALL: (f64, f64) = (0.0, f64::INFINITY);
+
+    let mut air = entry("Air", "Air", BASIC, Vec::new(), ALL, "n = 1.000293 at 589 nm");
+    air.epsilon = 1.000293f64.powi(2);
+
+    // The preset's n = 1.9 with a flat D_conductivity, which is quoted in
+    // c/a and so equals the stored c/µm value at a = 1 µm.
+    let mut ito = entry("Indium Tin Oxide", "ITO", SEMI, Vec::new(), (0.4, 2.5), "ITO preset: n = 1.9, D_conductivity 1e5 at a = 1 µm");
+    ito.epsilon = 1.9f64.powi(2);
+    ito.conductivity = 1.0e5;
+
+    let mut lithium_niobate = entry(
+        "Lithium Niobate",
+        "LN",
+        NONLINEAR,
+        rescaled(2.14, 1.55, &[(2.9804, 0.02047), (0.5981, 0.0666), (8.9543, 416.08)]),
+        (0.4, 5.0),
+        "Zelmon et al. 1997, extraordinary axis, scaled to n = 2.14 at 1.55 µm",
+    );
+    lithium_niobate.chi2 = 4.5e-12;
+
+    let mut bcb = entry("Benzocyclobutene", "BCB", NONLINEAR, anchored(1.535, 1.55, 0.15), (0.4, 1.7), "single pole anchored at 1.55 µm");
+    bcb.chi3 = 1.0e-20;
+
+    vec![
+        air,
+        entry("Vacuum", "Vacuum", BASIC, Vec::new(), ALL, "n = 1"),
+        entry(
+            "Silicon",
+            "Si",
+            SEMI,
+            rescaled(3.48, 1.55, &[(10.668_429_3, 0.301_516_485f64.powi(2)), (0.003_043_474_8, 1.134_751_15f64.powi(2)), (1.541_334_08, 1104.0f64.powi(2))]),
+            (1.36, 11.0),
+            "Li 1980 at room temperature, scaled to n = 3.48 at 1.55 µm",
+        ),
+        entry("Germanium", "Ge", SEMI, anchored(4.00, 1.55, 0.46), (1.55, 16.0), "single pole anchored at 1.55 µm"),
+        entry("Gallium Arsenide", "GaAs", SEMI, anchored(3.40, 1.55, 0.344), (0.9, 17.0), "single pole anchored at 1.55 µm"),
+        entry("Indium Phosphide", "InP", SEMI, anchored(3.17, 1.55, 0.365), (0.95, 10.0), "single pole anchored at 1.55 µm"),
+        entry("Gallium Nitride", "GaN", SEMI, anchored(2.31, 1.55, 0.25), (0.37, 10.0), "single pole anchored at 1.55 µm"),
+        entry("Silicon Carbide", "SiC", SEMI, anchored(2.55, 1.55, 0.2), (0.4, 5.0), "single pole anchored at 1.55 µm"),
+        entry(
+            "Silica",
+            "SiO₂",
+            SEMI,
+            rescaled(1.444, 1.55, &[(0.696_166_3, 0.068_404_3f64.powi(2)), (0.407_942_6, 0.116_241_4f64.powi(2)), (0.897_479_4, 9.896_161f64.powi(2))]),
+            (0.21, 6.7),
+            "Malitson 1965, scaled to n = 1.444 at 1.55 µm",
+        ),
+        entry(
+            "Alumina",
+            "Al₂O₃",
+            SEMI,
+            rescaled(1.76, 1.55, &[(1.431_349_3, 0.072_663_1f64.powi(2)), (0.650_547_13, 0.119_324_2f64.powi(2)), (5.341_402_1, 18.028_251f64.powi(2))]),
+            (0.2, 5.0),
+            "Malitson 1962 ordinary ray, scaled to n = 1.76 at 1.55 µm",
+        ),
+        ito,
+        lithium_niobate,
+        entry(
+            "Silicon Nitride",
+            "Si₃N₄",
+            NONLINEAR,
+            rescaled(2.05, 1.55, &[(3.0249, 0.135_340_6f64.powi(2)), (40314.0, 1239.842f64.powi(2))]),
+            (0.31, 5.5),
+            "Luke et al. 2015, scaled to n = 2.05 at 1.55 µm",
+        ),
+        entry("Titanium Dioxide", "TiO₂", NONLINEAR, anchored(2.40, 1.55, 0.25), (0.43, 5.0), "single pole anchored at 1.55 µm"),
+        bcb,
+        entry(
+            "Gold",
+            "Au",
+            METALS,
+            lorentz_drude(9.03, &[(0.760, 0.0, 0.053), (0.024, 0.415, 0.241), (0.010, 0.830, 0.345), (0.071, 2.969, 0.870), (0.601, 4.304, 2.494), (4.384, 13.32, 2.214)]),
+            (0.25, 12.4),
+            "Rakić et al. 1998, Lorentz–Drude",
+        ),
+        entry(
+            "Silver",
+            "Ag",
+            METALS,
+            lorentz_drude(9.01, &[(0.845, 0.0, 0.048), (0.065, 0.816, 3.886), (0.124, 4.481, 0.452), (0.011, 8.185, 0.065), (0.840, 9.083, 0.916), (5.646, 20.29, 2.419)]),
+            (0.25, 12.4),
+            "Rakić et al. 1998, Lorentz–Drude",
+        ),
+        entry(
+            "Aluminium",
+            "Al",
+            METALS,
+            lorentz_drude(14.98, &[(0.523, 0.0, 0.047), (0.227, 0.162, 0.333), (0.050, 1.544, 0.312), (0.166, 1.808, 1.351), (0.030, 3.473, 3.382)]),
+            (0.25, 12.4),
+            "Rakić et al. 1998, Lorentz–Drude",
+        ),
+    ]
+}
+
+/// Look a material up by name or abbreviation, ignoring case.
+pub fn find_material(key: &str) -> Result<MaterialEntry, String> {
+    material_library()
+        .into_iter()
+        .find(|m| m.name.eq_ignore_ascii_case(key) || m.abbreviation.eq_ignore_ascii_case(key))
+        .ok_or_else(|| format!("unknown material '{}'", key))
+}
+
+fn scale_susceptibility(s: &Susceptibility, k: f64) -> Susceptibility {
+    use Susceptibility::*;
+    let scale3 = |b: [f64; 3]| b.map(|v| v * k);
+    match s.clone() {
+        Lorentzian { frequency, gamma, sigma } => Lorentzian { frequency: frequency * k, gamma: gamma * k, sigma },
+        // Drude strength is σ f₀², so keep σ and scale f₀
+        Drude { frequency, gamma, sigma } => Drude { frequency: frequency * k, gamma: gamma * k, sigma },
+        NoisyLorentzian { frequency, gamma, sigma, noise_amp } => {
+            NoisyLorentzian { frequency: frequency * k, gamma: gamma * k, sigma, noise_amp }
+        }
+        NoisyDrude { frequency, gamma, sigma, noise_amp } => NoisyDrude { frequency: frequency * k, gamma: gamma * k, sigma, noise_amp },
+        GyrotropicLorentzian { frequency, gamma, sigma, bias } => {
+            GyrotropicLorentzian { frequency: frequency * k, gamma: gamma * k, sigma, bias: scale3(bias) }
+        }
+        GyrotropicDrude { frequency, gamma, sigma, bias } => {
+            GyrotropicDrude { frequency: frequency * k, gamma: gamma * k, sigma, bias: scale3(bias) }
+        }
+    }
+}
+
+impl MaterialEntry {
+    /// The material in Meep units for the given project length scale.
+    pub fn medium(&self, units: &UnitSystem) -> LayerMedium {
+        let k = units.frequency_scale();
+        let mut medium = LayerMedium::constant(self.epsilon);
+        medium.susceptibilities = self.susceptibilities.iter().map(|s| scale_susceptibility(s, k)).collect();
+        medium.conductivity = self.conductivity * k;
+        medium
+    }
+
+    pub fn valid_freq_range(&self, units: &UnitSystem) -> FrequencyRange {
+        let (shortest, longest) = self.valid_wavelength_um;
+        FrequencyRange {
+            min: units.wavelength_um_to_frequency(longest),
+            max: if shortest > 0.0 { units.wavelength_um_to_frequency(shortest) } else { f64::INFINITY },
+        }
+    }
+
+    /// Warning if any part of `band` lies outside the validity window.
+    pub fn frequency_warning(&self, units: &UnitSystem, band: FrequencyRange) -> Option<String> {
+        let valid = self.valid_freq_range(units);
+        (band.min < valid.min || band.max > valid.max).then(|| {
+            format!(
+                "{}: source band {:.4}–{:.4} lies outside the material's valid range {:.4}–{:.4} (λ = {}–{} µm)",
+                self.name, band.min, band.max, valid.min, valid.max, self.valid_wavelength_um.0, self.valid_wavelength_um.1
+            )
+        })
+    }
+}
+
+/// A library entry in Meep units, shaped like the TS `Medium`.
+#[derive(Serialize)]
+pub struct LibraryMedium {
+    pub name: String,
+    pub abbreviation: String,
+    pub category: String,
+    pub epsilon: f64,
+    #[serde(rename = "E_susceptibilities")]
+    pub susceptibilities: Vec<String>,
+    #[serde(rename = "D_conductivity")]
+    pub conductivity: f64,
+    pub chi2: f64,
+    pub chi3: f64,
+    pub valid_freq_range: FrequencyRange,
+    pub reference: String,
+}
+
+pub fn material_library_internal(units: &UnitSystem) -> Result<Vec<LibraryMedium>, String> {
+    if units.a <= 0.0 {
+        return Err("length scale a must be positive".into());
+    }
+    Ok(material_library()
+        .iter()
+        .map(|m| {
+            let medium = m.medium(units);
+            LibraryMedium {
+                name: m.name.into(),
+                abbreviation: m.abbreviation.into(),
+                category: m.category.into(),
+                epsilon: medium.epsilon,
+                susceptibilities: medium.susceptibilities.iter().map(|s| s.meep_repr()).collect(),
+                conductivity: medium.conductivity,
+                chi2: m.chi2,
+                chi3: m.chi3,
+                valid_freq_range: m.valid_freq_range(units),
+                reference: m.reference.into(),
+            }
+        })
+        .collect())
+}
+
+/// Gaussian source band, checked at frequency ± fwidth / 2 as Meep does.
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct SourceBand {
+    pub frequency: f64,
+    #[serde(default)]
+    pub fwidth: f64,
+}
+
+impl SourceBand {
+    pub fn range(&self) -> FrequencyRange {
+        FrequencyRange { min: self.frequency - 0.5 * self.fwidth, max: self.frequency + 0.5 * self.fwidth }
+    }
+}
+
+fn default_points() -> usize {
+    200
+}
+
+#[derive(Deserialize)]
+pub struct MaterialEvaluationConfig {
+    pub material: String,
+    #[serde(flatten)]
+    pub units: UnitSystem,
+    /// Defaults to the material's validity window, clipped to finite values.
+    #[serde(default)]
+    pub frequency_range: Option<FrequencyRange>,
+    #[serde(default = "default_points")]
+    pub points: usize,
+    #[serde(default)]
+    pub sources: Vec<SourceBand>,
+}
+
+#[derive(Serialize)]
+pub struct MaterialEvaluationResult {
+    pub dispersion: DispersionResult,
+    pub frequencies_hz: Vec<f64>,
+    pub valid_freq_range: FrequencyRange,
+    pub warnings: Vec<String>,
+}
+
+pub fn evaluate_material_internal(config: &MaterialEvaluationConfig) -> Result<MaterialEvaluationResult, String> {
+    if config.units.a <= 0.0 {
+        return Err("length scale a must be positive".into());
+    }
+    let material = find_material(&config.material)?;
+    let medium = material.medium(&config.units);
+    let valid = material.valid_freq_range(&config.units);
+    let range = config.frequency_range.unwrap_or(FrequencyRange {
+        min: if valid.min > 0.0 { valid.min } else { config.units.wavelength_um_to_frequency(10.0) },
+        max: if valid.max.is_finite() { valid.max } else { config.units.wavelength_um_to_frequency(0.2) },
+    });
+    if range.min <= 0.0 || range.max < range.min {
+        return Err("frequency range must satisfy 0 < min <= max".into());
+    }
+
+    let points = config.points.max(1);
+    let mut dispersion = DispersionResult {
+        frequencies: Vec::with_capacity(points),
+        epsilon_real: Vec::with_capacity(points),
+        epsilon_imag: Vec::with_capacity(points),
+        n: Vec::with_capacity(points),
+        k: Vec::with_capacity(points),
+    };
+    for i in 0..points {
+        let f = if points == 1 { range.min } else { range.min + (range.max - range.min) * i as f64 / (points - 1) as f64 };
+        let eps = medium.epsilon_at(f);
+        let index = complex_refractive_index(eps);
+        dispersion.frequencies.push(f);
+        dispersion.epsilon_real.push(eps.re);
+        dispersion.epsilon_imag.push(eps.im);
+        dispersion.n.push(index.re);
+        dispersion.k.push(index.im);
+    }
+
+    let warnings = config
+        .sources
+        .iter()
+        .filter_map(|s| material.frequency_warning(&config.units, s.range()))
+        .collect();
+    Ok(MaterialEvaluationResult {
+        frequencies_hz: dispersion.frequencies.iter().map(|&f| config.units.frequency_to_hz(f)).collect(),
+        dispersion,
+        valid_freq_range: valid,
+        warnings,
+    })
+}
+
+#[derive(Deserialize)]
+pub struct MaterialCheckConfig {
+    pub materials: Vec<String>,
+    #[serde(flatten)]
+    pub units: UnitSystem,
+    pub sources: Vec<SourceBand>,
+}
+
+pub fn check_material_frequencies_internal(config: &MaterialCheckConfig) -> Result<Vec<String>, String> {
+    let mut warnings = Vec::new();
+    for key in &config.materials {
+        let material = find_material(key)?;
+        for source in &config.sources {
+            warnings.extend(material.frequency_warning(&config.units, source.range()));
+        }
+    }
+    Ok(warnings)
+}
+
+/// Full material library in Meep units for the project's length scale `a`
+#[wasm_bindgen]
+pub fn get_material_library(config: &JsValue) -> Result<JsValue, JsValue> {
+    let units: UnitSystem = serde_wasm_bindgen::from_value(config.clone())?;
+    let result = material_library_internal(&units).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+/// Dispersion of a library material over a frequency range, with validity warnings
+#[wasm_bindgen]
+pub fn evaluate_material(config: &JsValue) -> Result<JsValue, JsValue> {
+    let config: MaterialEvaluationConfig = serde_wasm_bindgen::from_value(config.clone())?;
+    let result = evaluate_material_internal(&config).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+/// Warnings for sources whose bandwidth leaves a material's valid_freq_range
+#[wasm_bindgen]
+pub fn check_material_frequencies(config: &JsValue) -> Result<JsValue, JsValue> {
+    let config: MaterialCheckConfig = serde_wasm_bindgen::from_value(config.clone())?;
+    let result = check_material_frequencies_internal(&config).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MICRON: UnitSystem = UnitSystem { a: 1.0, unit: LengthUnit::Um };
+
+    fn index_at(key: &str, units: &UnitSystem, wavelength_um: f64) -> num_complex::Complex64 {
+        let medium = find_material(key).unwrap().medium(units);
+        complex_refractive_index(medium.epsilon_at(units.wavelength_um_to_frequency(wavelength_um)))
+    }
+
+    #[test]
+    fn test_matches_preset_indices_at_telecom() {
+        // the `index` of every dielectric preset in meepMaterialPresets.ts
+        let presets = [
+            ("Si", 3.48),
+            ("Ge", 4.00),
+            ("GaAs", 3.40),
+            ("InP", 3.17),
+            ("GaN", 2.31),
+            ("SiC", 2.55),
+            ("SiO₂", 1.444),
+            ("Al₂O₃", 1.76),
+            ("LN", 2.14),
+            ("Si₃N₄", 2.05),
+            ("TiO₂", 2.40),
+            ("BCB", 1.535),
+        ];
+        for (key, n) in presets {
+            let got = index_at(key, &MICRON, 1.55);
+            assert!((got.re - n).abs() < 1e-12 && got.im == 0.0, "{}: n = {}", key, got);
+        }
+        let ito = find_material("ITO").unwrap().medium(&MICRON);
+        assert_eq!((ito.epsilon, ito.conductivity), (1.9 * 1.9, 1.0e5));
+    }
+
+    #[test]
+    fn test_evaluation_is_independent_of_length_unit() {
+        let nm = UnitSystem { a: 500.0, unit: LengthUnit::Nm };
+        for key in ["Au", "Si", "Ag"] {
+            let a = index_at(key, &MICRON, 0.8);
+            let b = index_at(key, &nm, 0.8);
+            assert!((a - b).norm() < 1e-9 * a.norm(), "{}", key);
+        }
+        // gold is metallic in the near infrared
+        let gold = find_material("gold").unwrap().medium(&nm);
+        assert!(gold.epsilon_at(nm.wavelength_um_to_frequency(1.0)).re < -20.0);
+    }
+
+    #[test]
+    fn test_warns_outside_valid_range() {
+        let config = MaterialCheckConfig {
+            materials: vec!["Si".into()],
+            units: MICRON,
+            sources: vec![SourceBand { frequency: 1.0 / 1.55, fwidth: 0.1 }],
+        };
+        assert!(check_material_frequencies_internal(&config).unwrap().is_empty());
+
+        let wide = MaterialCheckConfig { sources: vec![SourceBand { frequency: 1.0 / 1.55, fwidth: 0.8 }], ..config };
+        let warnings = check_material_frequencies_internal(&wide).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("Silicon"));
+    }
+}
@@ -0,0 +1,258 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::lattice_calculations::matrix_calculations::{Matrix3x3, Vector3D};
+
+/// Real symmetric 3×3 material tensor (ε or μ) in Meep's diag/offdiag form.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MaterialTensor {
+    pub matrix: Matrix3x3,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OpticalClass {
+    Isotropic,
+    Uniaxial,
+    Biaxial,
+}
+
+/// Principal values, axes (unit vectors, ascending values) and indices.
+#[derive(Clone, Debug, Serialize)]
+pub struct PrincipalAxes {
+    pub values: [f64; 3],
+    pub axes: [Vector3D; 3],
+    pub indices: [f64; 3],
+    pub class: OpticalClass,
+    /// For uniaxial media, the direction of the distinct principal value.
+    pub optic_axis: Option<Vector3D>,
+    /// Ordinary and extraordinary indices of a uniaxial medium.
+    pub ordinary_index: Option<f64>,
+    pub extraordinary_index: Option<f64>,
+    /// n_max − n_min.
+    pub birefringence: f64,
+}
+
+impl MaterialTensor {
+    pub fn isotropic(value: f64) -> Self {
+        Self { matrix: Matrix3x3::from_diagonal([value; 3]) }
+    }
+
+    pub fn from_meep(diag: Vector3D, offdiag: Vector3D) -> Self {
+        Self { matrix: Matrix3x3::symmetric(diag, offdiag) }
+    }
+
+    /// Principal values along the given (orthonormal) crystal axes.
+    pub fn from_principal(values: [f64; 3], axes: &Matrix3x3) -> Self {
+        Self { matrix: axes.mul(&Matrix3x3::from_diagonal(values)).mul(&axes.transpose()) }
+    }
+
+    pub fn diag(&self) -> Vector3D {
+        self.matrix.diagonal()
+    }
+
+    pub fn offdiag(&self) -> Vector3D {
+        self.matrix.offdiagonal()
+    }
+
+    /// The tensor of the same medium rotated by R: ε' = R ε Rᵀ.
+    pub fn rotated(&self, rotation: &Matrix3x3) -> Self {
+        Self { matrix: rotation.mul(&self.matrix).mul(&rotation.transpose()) }
+    }
+
+    /// ε⁻¹, as used by the E = ε⁻¹ D update.
+    pub fn inverse(&self) -> Result<Self, String> {
+        Ok(Self { matrix: self.matrix.inverse()? })
+    }
+
+    /// Scalar permittivity seen by a field whose D is along `axis`,
+    /// 1 / (ε⁻¹)_aa; exact for diagonal tensors and the usual choice when a
+    /// scalar solver meets off-diagonal terms.
+    pub fn effective_scalar(&self, axis: usize) -> Result<f64, String> {
+        Ok(1.0 / self.matrix.inverse()?.data[axis][axis])
+    }
+
+    pub fn is_positive_definite(&self) -> bool {
+        self.matrix.symmetric_eigen().0[0] > 0.0
+    }
+
+    pub fn principal_axes(&self) -> PrincipalAxes {
+        let (values, vectors) = self.matrix.symmetric_eigen();
+        let column = |c: usize| Vector3D::new(vectors.data[0][c], vectors.data[1][c], vectors.data[2][c]);
+        let axes = [column(0), column(1), column(2)];
+        let indices = values.map(|v| v.max(0.0).sqrt());
+
+        let scale = values.iter().fold(0.0f64, |m, v| m.max(v.abs())).max(1e-300);
+        let equal = |a: f64, b: f64| (a - b).abs() <= 1e-9 * scale;
+        let (class, distinct, repeated) = if equal(values[0], values[2]) {
+            (OpticalClass::Isotropic, None, None)
+        } else if equal(values[0], values[1]) {
+            (OpticalClass::Uniaxial, Some(2), Some(0))
+        } else if equal(values[1], values[2]) {
+            (OpticalClass::Uniaxial, Some(0), Some(2))
+        } else {
+            (OpticalClass::Biaxial, None, None)
+        };
+
+        PrincipalAxes {
+            values,
+            axes,
+            indices,
+            class,
+            optic_axis: distinct.map(|i| axes[i]),
+            ordinary_index: repeated.map(|i| indices[i]),
+            extraordinary_index: distinct.map(|i| indices[i]),
+            birefringence: indices[2] - indices[0],
+        }
+    }
+}
+
+/// Orientation of the crystal frame: an axis–angle rotation (degrees).
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct TensorRotation {
+    pub axis: Vector3D,
+    pub angle: f64,
+}
+
+fn unit_diag() -> Vector3D {
+    Vector3D::new(1.0, 1.0, 1.0)
+}
+
+fn zero_offdiag() -> Vector3D {
+    Vector3D::new(0.0, 0.0, 0.0)
+}
+
+#[derive(Deserialize)]
+pub struct TensorMediumConfig {
+    #[serde(default = "unit_diag")]
+    pub epsilon_diag: Vector3D,
+    #[serde(default = "zero_offdiag")]
+    pub epsilon_offdiag: Vector3D,
+    #[serde(default = "unit_diag")]
+    pub mu_diag: Vector3D,
+    #[serde(default = "zero_offdiag")]
+    pub mu_offdiag: Vector3D,
+    /// Rotations applied in order, taking the crystal frame to the lab frame.
+    #[serde(default)]
+    pub rotations: Vec<TensorRotation>,
+}
+
+#[derive(Serialize)]
+pub struct TensorAnalysis {
+    pub diag: Vector3D,
+    pub offdiag: Vector3D,
+    pub matrix: Matrix3x3,
+    pub inverse: Matrix3x3,
+    pub principal: PrincipalAxes,
+    pub positive_definite: bool,
+}
+
+#[derive(Serialize)]
+pub struct TensorMediumResult {
+    pub epsilon: TensorAnalysis,
+    pub mu: TensorAnalysis,
+    pub warnings: Vec<String>,
+}
+
+fn analyse(name: &str, tensor: MaterialTensor, warnings: &mut Vec<String>) -> Result<TensorAnalysis, String> {
+    let positive_definite = tensor.is_positive_definite();
+    if !positive_definite {
+        warnings.push(format!("{} is not positive definite; the time-domain update will be unstable", name));
+    }
+    let inverse = tensor.inverse().map_err(|e| format!("{}: {}", name, e))?;
+    Ok(TensorAnalysis {
+        diag: tensor.diag(),
+        offdiag: tensor.offdiag(),
+        matrix: tensor.matrix,
+        inverse: inverse.matrix,
+        principal: tensor.principal_axes(),
+        positive_definite,
+    })
+}
+
+pub fn analyze_tensor_medium_internal(config: &TensorMediumConfig) -> Result<TensorMediumResult, String> {
+    let mut rotation = Matrix3x3::identity();
+    for r in &config.rotations {
+        rotation = Matrix3x3::rotation(r.axis, r.angle.to_radians())?.mul(&rotation);
+    }
+    let epsilon = MaterialTensor::from_meep(config.epsilon_diag, config.epsilon_offdiag).rotated(&rotation);
+    let mu = MaterialTensor::from_meep(config.mu_diag, config.mu_offdiag).rotated(&rotation);
+
+    let mut warnings = Vec::new();
+    Ok(TensorMediumResult {
+        epsilon: analyse("epsilon", epsilon, &mut warnings)?,
+        mu: analyse("mu", mu, &mut warnings)?,
+        warnings,
+    })
+}
+
+/// Rotate an anisotropic medium and report its inverse and principal axes
+#[wasm_bindgen]
+pub fn analyze_tensor_medium(config: &JsValue) -> Result<JsValue, JsValue> {
+    let config: TensorMediumConfig = serde_wasm_bindgen::from_value(config.clone())?;
+    let result = analyze_tensor_medium_internal(&config).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// LiNbO₃ at 1.55 µm: n_o = 2.211, n_e = 2.138 along the crystal c axis.
+    fn lithium_niobate() -> MaterialTensor {
+        MaterialTensor::from_principal([2.211f64.powi(2), 2.211f64.powi(2), 2.138f64.powi(2)], &Matrix3x3::identity())
+    }
+
+    #[test]
+    fn test_rotated_uniaxial_recovers_indices_and_optic_axis() {
+        let rotation = Matrix3x3::rotation(Vector3D::new(1.0, 0.0, 0.0), 30f64.to_radians()).unwrap();
+        let tensor = lithium_niobate().rotated(&rotation);
+        assert!(tensor.offdiag().z.abs() > 0.01);
+
+        let principal = tensor.principal_axes();
+        assert_eq!(principal.class, OpticalClass::Uniaxial);
+        assert!((principal.ordinary_index.unwrap() - 2.211).abs() < 1e-12);
+        assert!((principal.extraordinary_index.unwrap() - 2.138).abs() < 1e-12);
+        assert!((principal.birefringence - 0.073).abs() < 1e-12);
+
+        // the optic axis is the rotated c axis, up to sign
+        let c_axis = rotation.mul_vec(Vector3D::new(0.0, 0.0, 1.0));
+        let axis = principal.optic_axis.unwrap();
+        let overlap = axis.x * c_axis.x + axis.y * c_axis.y + axis.z * c_axis.z;
+        assert!((overlap.abs() - 1.0).abs() < 1e-12);
+
+        // ε⁻¹ is R diag(1/ε) Rᵀ, and a y-polarised scalar solver sees
+        // 1 / (cos²θ / ε_o + sin²θ / ε_e)
+        let (eps_o, eps_e) = (2.211f64.powi(2), 2.138f64.powi(2));
+        let exact = MaterialTensor::from_principal([1.0 / eps_o, 1.0 / eps_o, 1.0 / eps_e], &rotation);
+        let inverse = tensor.inverse().unwrap();
+        for (row, exact_row) in inverse.matrix.data.iter().zip(&exact.matrix.data) {
+            for (value, expected) in row.iter().zip(exact_row) {
+                assert!((value - expected).abs() < 1e-14);
+            }
+        }
+        let (sin, cos) = 30f64.to_radians().sin_cos();
+        let expected = 1.0 / (cos * cos / eps_o + sin * sin / eps_e);
+        assert!((tensor.effective_scalar(1).unwrap() - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_inverse_and_eigenvectors_of_general_tensor() {
+        let tensor = MaterialTensor::from_meep(Vector3D::new(4.0, 3.0, 2.5), Vector3D::new(0.3, -0.2, 0.5));
+        let product = tensor.matrix.mul(&tensor.inverse().unwrap().matrix);
+        for i in 0..3 {
+            for j in 0..3 {
+                assert!((product.data[i][j] - if i == j { 1.0 } else { 0.0 }).abs() < 1e-12);
+            }
+        }
+        let principal = tensor.principal_axes();
+        assert_eq!(principal.class, OpticalClass::Biaxial);
+        for (value, axis) in principal.values.iter().zip(&principal.axes) {
+            let image = tensor.matrix.mul_vec(*axis);
+            assert!((image.x - value * axis.x).abs() < 1e-12);
+            assert!((image.y - value * axis.y).abs() < 1e-12);
+            assert!((image.z - value * axis.z).abs() < 1e-12);
+        }
+        assert!(tensor.effective_scalar(2).unwrap() < 2.5);
+    }
+}
@@ -0,0 +1,224 @@
+use num_complex::Complex64;
+
+/// Complex banded matrix with `kl` sub- and `ku` super-diagonals.
+///
+/// Storage is row-oriented and reserves `kl` extra super-diagonals for the
+/// fill-in produced by partial pivoting (same layout idea as LAPACK `gbtrf`).
+#[derive(Clone, Debug)]
+pub struct BandedMatrix {
+    n: usize,
+    kl: usize,
+    ku: usize,
+    width: usize,
+    data: Vec<Complex64>,
+}
+
+/// LU factorisation of a [`BandedMatrix`] with row pivots.
+#[derive(Clone, Debug)]
+pub struct BandedLu {
+    lu: BandedMatrix,
+    pivots: Vec<usize>,
+}
+
+impl BandedMatrix {
+    pub fn new(n: usize, kl: usize, ku: usize) -> Self {
+        let width = 2 * kl + ku + 1;
+        Self {
+            n,
+            kl,
+            ku,
+            width,
+            data: vec![Complex64::new(0.0, 0.0); n * width],
+        }
+    }
+
+    #[inline]
+    fn offset(&self, i: usize, j: usize) -> usize {
+        debug_assert!(j + self.kl >= i && j <= i + self.ku + self.kl);
+        i * self.width + (j + self.kl - i)
+    }
+
+    #[inline]
+    fn get(&self, i: usize, j: usize) -> Complex64 {
+        self.data[self.offset(i, j)]
+    }
+
+    #[inline]
+    fn get_mut(&mut self, i: usize, j: usize) -> &mut Complex64 {
+        let k = self.offset(i, j);
+        &mut self.data[k]
+    }
+
+    /// Accumulate `value` into entry (i, j). The entry must lie inside the band.
+    pub fn add(&mut self, i: usize, j: usize, value: Complex64) -> Result<(), String> {
+        if i >= self.n || j >= self.n || j + self.kl < i || j > i + self.ku {
+            return Err(format!("Entry ({}, {}) lies outside the matrix band", i, j));
+        }
+        *self.get_mut(i, j) += value;
+        Ok(())
+    }
+
+    /// Matrix-vector product `A x`.
+    pub fn multiply(&self, x: &[Complex64]) -> Vec<Complex64> {
+        (0..self.n)
+            .map(|i| {
+                let lo = i.saturating_sub(self.kl);
+                let hi = (i + self.ku).min(self.n - 1);
+                (lo..=hi).map(|j| self.get(i, j) * x[j]).sum()
+            })
+            .collect()
+    }
+
+    /// Gaussian elimination with partial pivoting, consuming the matrix.
+    pub fn factor(mut self) -> Result<BandedLu, String> {
+        let n = self.n;
+        let kl = self.kl;
+        let reach = self.ku + self.kl;
+        let mut pivots = vec![0usize; n];
+
+        for (k, pivot_row) in pivots.iter_mut().enumerate() {
+            // ---- 1. pick the largest entry in column k ------------------------
+            let last_row = (k + kl).min(n - 1);
+            let mut p = k;
+            let mut best = self.get(k, k).norm();
+            for i in (k + 1)..=last_row {
+                let v = self.get(i, k).norm();
+                if v > best {
+                    best = v;
+                    p = i;
+                }
+            }
+            if best == 0.0 {
+                return Err(format!("Matrix is singular (zero pivot in column {})", k));
+            }
+            *pivot_row = p;
+
+            // ---- 2. swap the trailing parts of rows k and p -------------------
+            let last_col = (k + reach).min(n - 1);
+            if p != k {
+                for j in k..=last_col {
+                    let a = self.offset(k, j);
+                    let b = self.offset(p, j);
+                    self.data.swap(a, b);
+                }
+            }
+
+            // ---- 3. eliminate below the pivot ---------------------------------
+            let pivot = self.get(k, k);
+            for i in (k + 1)..=last_row {
+                let l = self.get(i, k) / pivot;
+                *self.get_mut(i, k) = l;
+                if l == Complex64::new(0.0, 0.0) {
+                    continue;
+                }
+                for j in (k + 1)..=last_col {
+                    let u = self.get(k, j);
+                    *self.get_mut(i, j) -= l * u;
+                }
+            }
+        }
+
+        Ok(BandedLu { lu: self, pivots })
+    }
+}
+
+impl BandedLu {
+    /// Solve `A x = b`.
+    pub fn solve(&self, b: &[Complex64]) -> Vec<Complex64> {
+        let n = self.lu.n;
+        let kl = self.lu.kl;
+        let reach = self.lu.ku + self.lu.kl;
+        let mut x = b.to_vec();
+
+        // forward substitution with the unit lower factor (pivots applied on the fly)
+        for k in 0..n {
+            x.swap(k, self.pivots[k]);
+            let xk = x[k];
+            let last = (k + kl).min(n - 1);
+            for (i, xi) in (k + 1..=last).zip(&mut x[k + 1..=last]) {
+                *xi -= self.lu.get(i, k) * xk;
+            }
+        }
+
+        // back substitution with the upper factor
+        for i in (0..n).rev() {
+            let last = (i + reach).min(n - 1);
+            let s: Complex64 = (i + 1..=last).zip(&x[i + 1..=last]).map(|(j, xj)| self.lu.get(i, j) * xj).sum();
+            x[i] = (x[i] - s) / self.lu.get(i, i);
+        }
+        x
+    }
+
+    /// Solve `Aᵀ x = b` (plain transpose, no conjugation) with the same factors.
+    pub fn solve_transpose(&self, b: &[Complex64]) -> Vec<Complex64> {
+        let n = self.lu.n;
+        let kl = self.lu.kl;
+        let reach = self.lu.ku + self.lu.kl;
+        let mut x = b.to_vec();
+
+        // Uᵀ y = b
+        for i in 0..n {
+            let first = i.saturating_sub(reach);
+            let s: Complex64 = (first..i).zip(&x[first..i]).map(|(j, xj)| self.lu.get(j, i) * xj).sum();
+            x[i] = (x[i] - s) / self.lu.get(i, i);
+        }
+
+        // Lᵀ with the pivots undone in reverse order
+        for k in (0..n).rev() {
+            let last = (k + kl).min(n - 1);
+            let s: Complex64 = (k + 1..=last).zip(&x[k + 1..=last]).map(|(i, xi)| self.lu.get(i, k) * xi).sum();
+            x[k] -= s;
+            x.swap(k, self.pivots[k]);
+        }
+        x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_matrix(n: usize, kl: usize, ku: usize) -> BandedMatrix {
+        let mut a = BandedMatrix::new(n, kl, ku);
+        for i in 0..n {
+            for j in i.saturating_sub(kl)..=(i + ku).min(n - 1) {
+                // small diagonal forces pivoting in some columns
+                let re = ((3 * i + 7 * j) % 11) as f64 - 5.0;
+                let im = ((5 * i + 2 * j) % 7) as f64 - 3.0;
+                let diag = if i == j { 0.1 } else { 1.0 };
+                a.add(i, j, Complex64::new(re * diag, im)).unwrap();
+            }
+        }
+        a
+    }
+
+    #[test]
+    fn test_solve_matches_multiply() {
+        let a = test_matrix(40, 3, 2);
+        let x: Vec<Complex64> = (0..40)
+            .map(|k| Complex64::new(k as f64 * 0.1, 1.0 - k as f64 * 0.05))
+            .collect();
+        let b = a.multiply(&x);
+        let lu = a.factor().unwrap();
+        let solved = lu.solve(&b);
+        for (s, e) in solved.iter().zip(&x) {
+            assert!((s - e).norm() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_solve_transpose() {
+        let n = 25;
+        let a = test_matrix(n, 2, 4);
+        let x: Vec<Complex64> = (0..n).map(|k| Complex64::new(1.0, k as f64)).collect();
+        // b = Aᵀ x computed entry by entry; column j holds rows j − 4 … j + 2
+        let b: Vec<Complex64> = (0..n)
+            .map(|j| (j.saturating_sub(4)..=(j + 2).min(n - 1)).map(|i| a.get(i, j) * x[i]).sum())
+            .collect();
+        let lu = a.factor().unwrap();
+        let solved = lu.solve_transpose(&b);
+        for (s, e) in solved.iter().zip(&x) {
+            assert!((s - e).norm() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,398 @@
+use num_complex::Complex64;
+
+/// Dense complex square matrix stored row-major.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComplexMatrix {
+    n: usize,
+    data: Vec<Complex64>,
+}
+
+const ZERO: Complex64 = Complex64 { re: 0.0, im: 0.0 };
+const ONE: Complex64 = Complex64 { re: 1.0, im: 0.0 };
+
+impl ComplexMatrix {
+    pub fn zeros(n: usize) -> Self {
+        Self { n, data: vec![ZERO; n * n] }
+    }
+
+    pub fn identity(n: usize) -> Self {
+        Self::from_diagonal(&vec![ONE; n])
+    }
+
+    pub fn from_diagonal(diagonal: &[Complex64]) -> Self {
+        let mut m = Self::zeros(diagonal.len());
+        for (i, &d) in diagonal.iter().enumerate() {
+            m[(i, i)] = d;
+        }
+        m
+    }
+
+    pub fn from_fn(n: usize, f: impl Fn(usize, usize) -> Complex64) -> Self {
+        let mut m = Self::zeros(n);
+        for i in 0..n {
+            for j in 0..n {
+                m[(i, j)] = f(i, j);
+            }
+        }
+        m
+    }
+
+    pub fn size(&self) -> usize {
+        self.n
+    }
+
+    pub fn mul(&self, other: &ComplexMatrix) -> ComplexMatrix {
+        let n = self.n;
+        let mut out = Self::zeros(n);
+        for i in 0..n {
+            for k in 0..n {
+                let a = self[(i, k)];
+                if a == ZERO {
+                    continue;
+                }
+                for j in 0..n {
+                    out.data[i * n + j] += a * other.data[k * n + j];
+                }
+            }
+        }
+        out
+    }
+
+    pub fn mul_vec(&self, v: &[Complex64]) -> Vec<Complex64> {
+        (0..self.n)
+            .map(|i| (0..self.n).map(|j| self[(i, j)] * v[j]).sum())
+            .collect()
+    }
+
+    /// Multiply from the right by a diagonal matrix (scale columns).
+    pub fn mul_diagonal(&self, diagonal: &[Complex64]) -> ComplexMatrix {
+        let mut out = self.clone();
+        for i in 0..self.n {
+            for j in 0..self.n {
+                out[(i, j)] *= diagonal[j];
+            }
+        }
+        out
+    }
+
+    /// Multiply from the left by a diagonal matrix (scale rows).
+    pub fn diagonal_mul(diagonal: &[Complex64], m: &ComplexMatrix) -> ComplexMatrix {
+        let mut out = m.clone();
+        for i in 0..m.n {
+            for j in 0..m.n {
+                out[(i, j)] *= diagonal[i];
+            }
+        }
+        out
+    }
+
+    pub fn add(&self, other: &ComplexMatrix) -> ComplexMatrix {
+        let mut out = self.clone();
+        out.data.iter_mut().zip(&other.data).for_each(|(a, b)| *a += b);
+        out
+    }
+
+    pub fn sub(&self, other: &ComplexMatrix) -> ComplexMatrix {
+        let mut out = self.clone();
+        out.data.iter_mut().zip(&other.data).for_each(|(a, b)| *a -= b);
+        out
+    }
+
+    pub fn scale(&self, factor: Complex64) -> ComplexMatrix {
+        let mut out = self.clone();
+        out.data.iter_mut().for_each(|a| *a *= factor);
+        out
+    }
+
+    /// Solve `A X = B` by LU decomposition with partial pivoting.
+    pub fn solve(&self, b: &ComplexMatrix) -> Result<ComplexMatrix, String> {
+        let n = self.n;
+        let mut a = self.clone();
+        let mut x = b.clone();
+        for k in 0..n {
+            let p = (k..n)
+                .max_by(|&i, &j| a[(i, k)].norm().total_cmp(&a[(j, k)].norm()))
+                .unwrap_or(k);
+            if a[(p, k)].norm() == 0.0 {
+                return Err("Matrix is singular".to_string());
+            }
+            if p != k {
+                for j in 0..n {
+                    a.data.swap(k * n + j, p * n + j);
+                    x.data.swap(k * n + j, p * n + j);
+                }
+            }
+            let pivot = a[(k, k)];
+            for i in (k + 1)..n {
+                let l = a[(i, k)] / pivot;
+                if l == ZERO {
+                    continue;
+                }
+                for j in k..n {
+                    let u = a[(k, j)];
+                    a[(i, j)] -= l * u;
+                }
+                for j in 0..n {
+                    let u = x[(k, j)];
+                    x[(i, j)] -= l * u;
+                }
+            }
+        }
+        for i in (0..n).rev() {
+            for j in 0..n {
+                let mut s = x[(i, j)];
+                for k in (i + 1)..n {
+                    s -= a[(i, k)] * x[(k, j)];
+                }
+                x[(i, j)] = s / a[(i, i)];
+            }
+        }
+        Ok(x)
+    }
+
+    /// Solve `A x = b` for a single right-hand side.
+    pub fn solve_vec(&self, b: &[Complex64]) -> Result<Vec<Complex64>, String> {
+        let n = self.n;
+        let mut a = self.clone();
+        let mut x = b.to_vec();
+        for k in 0..n {
+            let p = (k..n)
+                .max_by(|&i, &j| a[(i, k)].norm().total_cmp(&a[(j, k)].norm()))
+                .unwrap_or(k);
+            if a[(p, k)].norm() == 0.0 {
+                return Err("Matrix is singular".to_string());
+            }
+            if p != k {
+                for j in 0..n {
+                    a.data.swap(k * n + j, p * n + j);
+                }
+                x.swap(k, p);
+            }
+            let pivot = a[(k, k)];
+            for i in (k + 1)..n {
+                let l = a[(i, k)] / pivot;
+                if l == ZERO {
+                    continue;
+                }
+                for j in k..n {
+                    let u = a[(k, j)];
+                    a[(i, j)] -= l * u;
+                }
+                let u = x[k];
+                x[i] -= l * u;
+            }
+        }
+        for i in (0..n).rev() {
+            let mut s = x[i];
+            for k in (i + 1)..n {
+                s -= a[(i, k)] * x[k];
+            }
+            x[i] = s / a[(i, i)];
+        }
+        Ok(x)
+    }
+
+    pub fn inverse(&self) -> Result<ComplexMatrix, String> {
+        self.solve(&Self::identity(self.n))
+    }
+
+    /// Eigenvalues and right eigenvectors (as columns) of a general complex
+    /// matrix, via Hessenberg reduction and shifted QR to complex Schur form.
+    pub fn eigen(&self) -> Result<(Vec<Complex64>, ComplexMatrix), String> {
+        let n = self.n;
+        let mut h = self.clone();
+        let mut z = Self::identity(n);
+
+        // ---- 1. Householder reduction to upper Hessenberg form -------------
+        for k in 0..n.saturating_sub(2) {
+            let alpha_norm = ((k + 1)..n).map(|i| h[(i, k)].norm_sqr()).sum::<f64>().sqrt();
+            if alpha_norm == 0.0 {
+                continue;
+            }
+            let x0 = h[(k + 1, k)];
+            let phase = if x0.norm() > 0.0 { x0 / x0.norm() } else { ONE };
+            let mut v: Vec<Complex64> = ((k + 1)..n).map(|i| h[(i, k)]).collect();
+            v[0] += phase * alpha_norm;
+            let v_norm = v.iter().map(|c| c.norm_sqr()).sum::<f64>().sqrt();
+            v.iter_mut().for_each(|c| *c /= v_norm);
+
+            // H ← (I - 2vvᴴ) H
+            for j in 0..n {
+                let dot: Complex64 = v.iter().enumerate().map(|(a, vi)| vi.conj() * h[(k + 1 + a, j)]).sum();
+                for (a, vi) in v.iter().enumerate() {
+                    h[(k + 1 + a, j)] -= 2.0 * vi * dot;
+                }
+            }
+            // H ← H (I - 2vvᴴ), Z ← Z (I - 2vvᴴ)
+            for m in [&mut h, &mut z] {
+                for i in 0..n {
+                    let dot: Complex64 = v.iter().enumerate().map(|(a, vi)| m[(i, k + 1 + a)] * vi).sum();
+                    for (a, vi) in v.iter().enumerate() {
+                        m[(i, k + 1 + a)] -= 2.0 * dot * vi.conj();
+                    }
+                }
+            }
+            for i in (k + 2)..n {
+                h[(i, k)] = ZERO;
+            }
+        }
+
+        // ---- 2. shifted QR iterations with Givens rotations ----------------
+        let scale = h.data.iter().fold(0.0f64, |m, c| m.max(c.norm())).max(f64::MIN_POSITIVE);
+        let mut hi = n;
+        let mut iterations = 0;
+        while hi > 1 {
+            let last = hi - 1;
+            // find the start of the unreduced block ending at `last`
+            let mut lo = last;
+            while lo > 0 {
+                let sub = h[(lo, lo - 1)].norm();
+                let diag = h[(lo, lo)].norm() + h[(lo - 1, lo - 1)].norm();
+                if sub <= f64::EPSILON * diag.max(scale * 1e-3) {
+                    h[(lo, lo - 1)] = ZERO;
+                    break;
+                }
+                lo -= 1;
+            }
+            if lo == last {
+                hi -= 1;
+                iterations = 0;
+                continue;
+            }
+            iterations += 1;
+            if iterations > 60 * n.max(10) {
+                return Err("eigenvalue iteration did not converge".into());
+            }
+
+            // Wilkinson shift from the trailing 2x2 block, with occasional exceptional shifts
+            let (a, b, c, d) = (h[(last - 1, last - 1)], h[(last - 1, last)], h[(last, last - 1)], h[(last, last)]);
+            let mut shift = {
+                let tr = a + d;
+                let det = a * d - b * c;
+                let disc = (tr * tr * 0.25 - det).sqrt();
+                let l1 = tr * 0.5 + disc;
+                let l2 = tr * 0.5 - disc;
+                if (l1 - d).norm() < (l2 - d).norm() { l1 } else { l2 }
+            };
+            if iterations % 11 == 0 {
+                shift = d + h[(last, last - 1)].norm() * Complex64::new(0.75, 0.5);
+            }
+
+            for i in lo..=last {
+                h[(i, i)] -= shift;
+            }
+            let mut rotations = Vec::with_capacity(last - lo);
+            for k in lo..last {
+                let (x, y) = (h[(k, k)], h[(k + 1, k)]);
+                let r = (x.norm_sqr() + y.norm_sqr()).sqrt();
+                let (cs, sn) = if r == 0.0 {
+                    (1.0, ZERO)
+                } else if x.norm() == 0.0 {
+                    (0.0, ONE)
+                } else {
+                    (x.norm() / r, (x / x.norm()) * y.conj() / r)
+                };
+                for j in k..n {
+                    let (p, q) = (h[(k, j)], h[(k + 1, j)]);
+                    h[(k, j)] = cs * p + sn * q;
+                    h[(k + 1, j)] = -sn.conj() * p + cs * q;
+                }
+                rotations.push((cs, sn));
+            }
+            for (offset, &(cs, sn)) in rotations.iter().enumerate() {
+                let k = lo + offset;
+                let rows = (k + 2).min(last) + 1;
+                for i in 0..rows {
+                    let (p, q) = (h[(i, k)], h[(i, k + 1)]);
+                    h[(i, k)] = p * cs + q * sn.conj();
+                    h[(i, k + 1)] = -p * sn + q * cs;
+                }
+                for i in 0..n {
+                    let (p, q) = (z[(i, k)], z[(i, k + 1)]);
+                    z[(i, k)] = p * cs + q * sn.conj();
+                    z[(i, k + 1)] = -p * sn + q * cs;
+                }
+            }
+            for i in lo..=last {
+                h[(i, i)] += shift;
+            }
+        }
+
+        // ---- 3. eigenvectors of the triangular Schur factor ----------------
+        let eigenvalues: Vec<Complex64> = (0..n).map(|i| h[(i, i)]).collect();
+        let small = f64::EPSILON * scale;
+        let mut vectors = Self::zeros(n);
+        for k in 0..n {
+            let lambda = eigenvalues[k];
+            let mut x = vec![ZERO; n];
+            x[k] = ONE;
+            for j in (0..k).rev() {
+                let s: Complex64 = ((j + 1)..=k).map(|l| h[(j, l)] * x[l]).sum();
+                let mut denom = h[(j, j)] - lambda;
+                if denom.norm() < small {
+                    denom = Complex64::new(small, 0.0);
+                }
+                x[j] = -s / denom;
+            }
+            let v = z.mul_vec(&x);
+            let norm = v.iter().map(|c| c.norm_sqr()).sum::<f64>().sqrt();
+            for i in 0..n {
+                vectors[(i, k)] = v[i] / norm;
+            }
+        }
+        Ok((eigenvalues, vectors))
+    }
+}
+
+impl std::ops::Index<(usize, usize)> for ComplexMatrix {
+    type Output = Complex64;
+
+    fn index(&self, (i, j): (usize, usize)) -> &Complex64 {
+        &self.data[i * self.n + j]
+    }
+}
+
+impl std::ops::IndexMut<(usize, usize)> for ComplexMatrix {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut Complex64 {
+        &mut self.data[i * self.n + j]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_matrix(n: usize) -> ComplexMatrix {
+        ComplexMatrix::from_fn(n, |i, j| {
+            Complex64::new(((i * 5 + j * 3) % 7) as f64 - 3.0, ((i * 2 + j * 7) % 5) as f64 - 2.0)
+        })
+    }
+
+    #[test]
+    fn test_solve_and_inverse() {
+        let a = sample_matrix(6);
+        let inv = a.inverse().unwrap();
+        let eye = a.mul(&inv);
+        for i in 0..6 {
+            for j in 0..6 {
+                let expected = if i == j { ONE } else { ZERO };
+                assert!((eye[(i, j)] - expected).norm() < 1e-10);
+            }
+        }
+    }
+
+    #[test]
+    fn test_eigen_decomposition() {
+        for n in [1, 2, 5, 12] {
+            let a = sample_matrix(n);
+            let (values, vectors) = a.eigen().unwrap();
+            for k in 0..n {
+                let v: Vec<Complex64> = (0..n).map(|i| vectors[(i, k)]).collect();
+                let av = a.mul_vec(&v);
+                for i in 0..n {
+                    assert!((av[i] - values[k] * v[i]).norm() < 1e-8, "n = {}, k = {}", n, k);
+                }
+            }
+        }
+    }
+}
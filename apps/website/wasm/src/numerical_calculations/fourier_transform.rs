@@ -1,8 +1,9 @@
-use ndarray::{Array1, ArrayView1};
-use num_complex::Complex;
 use rustfft::{FftPlanner, num_complex::Complex64};
 use wasm_bindgen::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+use crate::numerical_calculations::dense_complex::ComplexMatrix;
 
 #[derive(Serialize, Deserialize)]
 pub struct FftResult {
@@ -62,6 +63,231 @@ pub fn compute_fft(signal: &[f64], sample_rate: f64) -> Result<JsValue, JsValue>
     Ok(serde_wasm_bindgen::to_value(&result)?)
 }
 
+/// Fourier-series coefficients c_m of a periodic signal sampled at N points,
+/// c_m = (1/N) Σ x_j exp(-2πi m j / N), returned in FFT order (m = 0, 1, …, -1)
+pub fn fourier_series_coefficients(samples: &[Complex64]) -> Vec<Complex64> {
+    let n = samples.len();
+    let mut buffer = samples.to_vec();
+
+    let mut planner = FftPlanner::new();
+    let fft = planner.plan_fft_forward(n);
+    fft.process(&mut buffer);
+
+    buffer.iter().map(|c| c / n as f64).collect()
+}
+
+/// Discrete Hilbert transform of a periodic real sequence,
+/// H[x](t) = (1/π) P∫ x(s) / (t − s) ds, so that H[cos] = sin.
+/// Applied spectrally: X_k → −i sgn(k) X_k.
+pub fn hilbert_transform(samples: &[f64]) -> Vec<f64> {
+    let n = samples.len();
+    let mut buffer: Vec<Complex64> = samples.iter().map(|&x| Complex64::new(x, 0.0)).collect();
+
+    let mut planner = FftPlanner::new();
+    planner.plan_fft_forward(n).process(&mut buffer);
+    for (k, c) in buffer.iter_mut().enumerate() {
+        *c *= if k == 0 || 2 * k == n {
+            Complex64::new(0.0, 0.0)
+        } else if 2 * k < n {
+            Complex64::new(0.0, -1.0)
+        } else {
+            Complex64::new(0.0, 1.0)
+        };
+    }
+    planner.plan_fft_inverse(n).process(&mut buffer);
+
+    buffer.iter().map(|c| c.re / n as f64).collect()
+}
+
+/// Largest denominator order `pade_spectrum` accepts; its dense M × M solve
+/// already takes seconds here.
+pub const MAX_PADE_ORDER: usize = 2000;
+
+/// Padé approximant [L/M] of the power series X(z) = Σ c_n zⁿ formed by the
+/// samples, with L + M + 1 = N, evaluated at z = exp(−2πi f Δt). Unlike the
+/// truncated sum, the rational form continues decaying resonances past the
+/// end of the record, so peaks narrower than 1/T are resolved.
+pub fn pade_spectrum(
+    signal: &[f64],
+    sample_rate: f64,
+    frequencies: &[f64],
+    denominator_order: Option<usize>,
+) -> Result<Vec<Complex64>, String> {
+    let n = signal.len();
+    if n < 3 {
+        return Err("need at least three samples".into());
+    }
+    let m = denominator_order.unwrap_or(n / 2).clamp(1, n - 1);
+    if m > MAX_PADE_ORDER {
+        return Err(format!("Padé denominator order {} exceeds {}", m, MAX_PADE_ORDER));
+    }
+    let l = n - 1 - m;
+    let c = |k: isize| if k >= 0 { Complex64::new(signal[k as usize], 0.0) } else { Complex64::new(0.0, 0.0) };
+
+    // denominator b₀ = 1: Σ_{k=1}^{M} b_k c_{j−k} = −c_j for j = L+1 … L+M
+    let system = ComplexMatrix::from_fn(m, |row, col| c((l + 1 + row) as isize - (col + 1) as isize));
+    let rhs: Vec<Complex64> = (0..m).map(|row| -c((l + 1 + row) as isize)).collect();
+    let mut denominator = vec![Complex64::new(1.0, 0.0)];
+    denominator.extend(system.solve_vec(&rhs)?);
+    let numerator: Vec<Complex64> =
+        (0..=l).map(|j| (0..=j.min(m)).map(|k| denominator[k] * c((j - k) as isize)).sum()).collect();
+
+    let horner = |coefficients: &[Complex64], z: Complex64| {
+        coefficients.iter().rev().fold(Complex64::new(0.0, 0.0), |acc, &a| acc * z + a)
+    };
+    Ok(frequencies
+        .iter()
+        .map(|&f| {
+            let z = Complex64::from_polar(1.0, -2.0 * PI * f / sample_rate);
+            horner(&numerator, z) / horner(&denominator, z)
+        })
+        .collect())
+}
+
+/// Low-pass filter a record and keep every `step`-th sample, returning the
+/// kept samples and the delay (in input samples) of the first one.
+///
+/// The Blackman-windowed sinc cuts off at the new Nyquist frequency with a
+/// transition ~0.35 of it wide, so only the lower 80% of the new band is
+/// alias-free. Outputs are formed only where the filter fully overlaps the
+/// record; a linear filter keeps the poles of a sum of damped sinusoids,
+/// which is what the Padé fit relies on.
+pub fn decimate(signal: &[f64], step: usize) -> Result<(Vec<f64>, usize), String> {
+    if step <= 1 {
+        return Ok((signal.to_vec(), 0));
+    }
+    let half = 16 * step;
+    if signal.len() < 2 * half + 3 {
+        return Err(format!("need more than {} samples to decimate by {}", 2 * half + 2, step));
+    }
+    let cutoff = 0.5 / step as f64;
+    let length = (2 * half) as f64;
+    let mut taps: Vec<f64> = (0..=2 * half)
+        .map(|k| {
+            let t = k as f64 - half as f64;
+            let sinc = if t == 0.0 { 2.0 * cutoff } else { (2.0 * PI * cutoff * t).sin() / (PI * t) };
+            let phase = 2.0 * PI * k as f64 / length;
+            sinc * (0.42 - 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos())
+        })
+        .collect();
+    let gain: f64 = taps.iter().sum();
+    taps.iter_mut().for_each(|t| *t /= gain);
+    let kept = (2 * half..signal.len())
+        .step_by(step)
+        .map(|end| taps.iter().zip(signal[end - 2 * half..=end].iter().rev()).map(|(h, x)| h * x).sum())
+        .collect();
+    Ok((kept, half))
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(tag = "method", rename_all = "lowercase")]
+pub enum SpectrumMethod {
+    /// FFT of the signal zero-padded to `zero_padding` times its length.
+    Fft {
+        #[serde(default = "unit_padding")]
+        zero_padding: usize,
+    },
+    Pade {
+        #[serde(default)]
+        denominator_order: Option<usize>,
+        #[serde(default = "default_pade_points")]
+        points: usize,
+        /// Longer records are low-pass filtered and decimated so that the
+        /// default M = N/2 stays within this order.
+        #[serde(default = "default_pade_max_order", alias = "maxOrder")]
+        max_order: usize,
+    },
+}
+
+fn unit_padding() -> usize {
+    1
+}
+
+fn default_pade_points() -> usize {
+    2000
+}
+
+fn default_pade_max_order() -> usize {
+    500
+}
+
+#[derive(Deserialize)]
+pub struct SpectrumConfig {
+    pub sample_rate: f64,
+    #[serde(flatten)]
+    pub method: SpectrumMethod,
+    /// Band evaluated by the Padé method; defaults to 0 … Nyquist, or to the
+    /// alias-free 80% of it when the record is decimated.
+    #[serde(default)]
+    pub min_frequency: Option<f64>,
+    #[serde(default)]
+    pub max_frequency: Option<f64>,
+}
+
+/// One-sided spectrum with the same amplitude scaling as `compute_fft`.
+pub fn compute_spectrum_internal(signal: &[f64], config: &SpectrumConfig) -> Result<FftResult, String> {
+    let n = signal.len();
+    if n == 0 || config.sample_rate <= 0.0 {
+        return Err("signal must be non-empty and the sample rate positive".into());
+    }
+    let (frequencies, spectrum, samples) = match config.method {
+        SpectrumMethod::Fft { zero_padding } => {
+            let padded = n * zero_padding.max(1);
+            let mut buffer: Vec<Complex64> = (0..padded)
+                .map(|i| Complex64::new(if i < n { signal[i] } else { 0.0 }, 0.0))
+                .collect();
+            FftPlanner::new().plan_fft_forward(padded).process(&mut buffer);
+            buffer.truncate(padded / 2 + 1);
+            ((0..=padded / 2).map(|i| i as f64 * config.sample_rate / padded as f64).collect(), buffer, n)
+        }
+        SpectrumMethod::Pade { denominator_order, points, max_order } => {
+            if max_order == 0 || max_order > MAX_PADE_ORDER {
+                return Err(format!("max_order must be between 1 and {}", MAX_PADE_ORDER));
+            }
+            if denominator_order.is_some_and(|m| m > max_order) {
+                return Err(format!("denominator_order exceeds max_order {}", max_order));
+            }
+            let step = if denominator_order.is_some() { 1 } else { n.div_ceil(2 * max_order + 1) };
+            let (decimated, delay) = decimate(signal, step)?;
+            let sample_rate = config.sample_rate / step as f64;
+            let alias_free = if step > 1 { 0.4 * sample_rate } else { 0.5 * sample_rate };
+            let low = config.min_frequency.unwrap_or(0.0);
+            let high = config.max_frequency.unwrap_or(alias_free);
+            if step > 1 && low.abs().max(high.abs()) > alias_free {
+                return Err(format!(
+                    "the band reaches past {} (80% of the Nyquist frequency after decimating by {} to keep the \
+                     Padé order within {}); narrow the band or raise max_order",
+                    alias_free, step, max_order
+                ));
+            }
+            let points = points.max(2);
+            let frequencies: Vec<f64> =
+                (0..points).map(|i| low + (high - low) * i as f64 / (points - 1) as f64).collect();
+            let mut spectrum = pade_spectrum(&decimated, sample_rate, &frequencies, denominator_order)?;
+            // the kept record starts `delay` samples in; restore the phase reference
+            for (x, &f) in spectrum.iter_mut().zip(&frequencies) {
+                *x *= Complex64::from_polar(1.0, -2.0 * PI * f * delay as f64 / config.sample_rate);
+            }
+            (frequencies, spectrum, decimated.len())
+        }
+    };
+    Ok(FftResult {
+        real: spectrum.iter().map(|c| c.re).collect(),
+        imag: spectrum.iter().map(|c| c.im).collect(),
+        magnitude: spectrum.iter().map(|c| 2.0 * c.norm() / samples as f64).collect(),
+        phase: spectrum.iter().map(|c| c.arg()).collect(),
+        frequencies,
+    })
+}
+
+/// Spectrum of a real signal by zero-padded FFT or Padé extrapolation
+#[wasm_bindgen]
+pub fn compute_spectrum(signal: &[f64], config: &JsValue) -> Result<JsValue, JsValue> {
+    let config: SpectrumConfig = serde_wasm_bindgen::from_value(config.clone())?;
+    let result = compute_spectrum_internal(signal, &config).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
 /// Compute the inverse FFT
 #[wasm_bindgen]
 pub fn compute_ifft(real: &[f64], imag: &[f64]) -> Result<Vec<f64>, JsValue> {
@@ -88,6 +314,14 @@ pub fn compute_ifft(real: &[f64], imag: &[f64]) -> Result<Vec<f64>, JsValue> {
     Ok(signal)
 }
 
+/// Spectral envelope of a Gaussian pulse of temporal width `width` centred
+/// at `frequency`, normalised to 1 at the centre: exp(−2π² w² (f − f₀)²).
+pub fn gaussian_envelope_spectrum(frequency: f64, width: f64, f: f64) -> f64 {
+    let spectral_width = 1.0 / (2.0 * std::f64::consts::PI * width);
+    let delta_f = f - frequency;
+    (-delta_f * delta_f / (2.0 * spectral_width * spectral_width)).exp()
+}
+
 /// Compute the Fourier transform of a Gaussian pulse
 #[wasm_bindgen]
 pub fn gaussian_pulse_spectrum(
@@ -115,8 +349,7 @@ pub fn gaussian_pulse_spectrum(
     let magnitude: Vec<f64> = frequencies
         .iter()
         .map(|&f| {
-            let delta_f = f - frequency;
-            let gaussian = (-delta_f * delta_f / (2.0 * spectral_width * spectral_width)).exp();
+            let gaussian = gaussian_envelope_spectrum(frequency, width, f);
             amplitude.norm() * gaussian * width * (2.0 * std::f64::consts::PI).sqrt()
         })
         .collect();
@@ -130,3 +363,118 @@ pub fn gaussian_pulse_spectrum(
     
     Ok(serde_wasm_bindgen::to_value(&result)?)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two Lorentzian lines 0.03 apart, recorded for T = 20 (FFT bins 0.05).
+    fn ring_down() -> Vec<f64> {
+        (0..200)
+            .map(|i| {
+                let t = i as f64 / 10.0;
+                (-0.01 * t).exp() * ((2.0 * PI * 1.0 * t).cos() + (2.0 * PI * 1.03 * t).cos())
+            })
+            .collect()
+    }
+
+    fn local_maxima(result: &FftResult, low: f64, high: f64) -> Vec<f64> {
+        let m = &result.magnitude;
+        (1..m.len() - 1)
+            .filter(|&i| m[i] > m[i - 1] && m[i] >= m[i + 1])
+            .map(|i| result.frequencies[i])
+            .filter(|&f| f > low && f < high)
+            .collect()
+    }
+
+    #[test]
+    fn test_pade_resolves_lines_closer_than_fft_bins() {
+        let signal = ring_down();
+        let fft = compute_spectrum_internal(&signal, &SpectrumConfig {
+            sample_rate: 10.0,
+            method: SpectrumMethod::Fft { zero_padding: 16 },
+            min_frequency: None,
+            max_frequency: None,
+        })
+        .unwrap();
+        assert_eq!(local_maxima(&fft, 0.95, 1.08).len(), 1);
+
+        let pade = compute_spectrum_internal(&signal, &SpectrumConfig {
+            sample_rate: 10.0,
+            method: SpectrumMethod::Pade { denominator_order: None, points: 4001, max_order: default_pade_max_order() },
+            min_frequency: Some(0.9),
+            max_frequency: Some(1.1),
+        })
+        .unwrap();
+        let peaks = local_maxima(&pade, 0.95, 1.08);
+        assert_eq!(peaks.len(), 2, "{:?}", peaks);
+        assert!((peaks[0] - 1.0).abs() < 1e-3 && (peaks[1] - 1.03).abs() < 1e-3, "{:?}", peaks);
+
+        // capping M at 40 decimates the record by 3, which still separates the lines
+        let capped = |max_frequency| {
+            compute_spectrum_internal(&signal, &SpectrumConfig {
+                sample_rate: 10.0,
+                method: SpectrumMethod::Pade { denominator_order: None, points: 4001, max_order: 40 },
+                min_frequency: Some(0.9),
+                max_frequency: Some(max_frequency),
+            })
+        };
+        let peaks = local_maxima(&capped(1.1).unwrap(), 0.95, 1.08);
+        assert_eq!(peaks.len(), 2, "{:?}", peaks);
+        assert!((peaks[0] - 1.0).abs() < 1e-3 && (peaks[1] - 1.03).abs() < 1e-3, "{:?}", peaks);
+        assert!(capped(1.5).err().is_some_and(|e| e.contains("Nyquist")));
+        assert!(pade_spectrum(&vec![1.0; 2 * MAX_PADE_ORDER + 4], 1.0, &[0.1], None).is_err());
+    }
+
+    #[test]
+    fn test_decimation_removes_tones_above_the_new_nyquist() {
+        // decimating 10 Hz sampling by 3 leaves a Nyquist frequency of 5/3 Hz,
+        // where a 2.283 Hz tone would alias onto 1.05 Hz
+        let signal: Vec<f64> = ring_down()
+            .iter()
+            .enumerate()
+            .map(|(i, x)| {
+                let t = i as f64 / 10.0;
+                x + 2.0 * (-0.01 * t).exp() * (2.0 * PI * (10.0 / 3.0 - 1.05) * t).cos()
+            })
+            .collect();
+        let spectrum = |signal: &[f64]| {
+            compute_spectrum_internal(signal, &SpectrumConfig {
+                sample_rate: 10.0,
+                method: SpectrumMethod::Pade { denominator_order: None, points: 2001, max_order: 40 },
+                min_frequency: Some(0.9),
+                max_frequency: Some(1.1),
+            })
+            .unwrap()
+        };
+        let (result, clean) = (spectrum(&signal), spectrum(&ring_down()));
+        let peaks = local_maxima(&result, 0.95, 1.08);
+        assert_eq!(peaks.len(), 2, "{:?}", peaks);
+        assert!((peaks[0] - 1.0).abs() < 1e-3 && (peaks[1] - 1.03).abs() < 1e-3, "{:?}", peaks);
+        // the tone is twice as strong as either line, yet leaves the band untouched
+        let peak = clean.magnitude.iter().fold(0.0f64, |m, &v| m.max(v));
+        let leak = result.magnitude.iter().zip(&clean.magnitude).fold(0.0f64, |m, (a, b)| m.max((a - b).abs()));
+        assert!(leak < 1e-2 * peak, "{} vs {}", leak, peak);
+    }
+
+    #[test]
+    fn test_pade_recovers_lorentzian_linewidth() {
+        // |X|² of exp(−γt) cos(2πf₀t) has a half-maximum half-width of γ / 2π
+        let gamma = 0.005;
+        let signal: Vec<f64> = (0..300)
+            .map(|i| {
+                let t = i as f64 / 10.0;
+                (-gamma * t).exp() * (2.0 * PI * 2.0 * t).cos()
+            })
+            .collect();
+        let frequencies: Vec<f64> = (0..2001).map(|i| 1.99 + 0.02 * i as f64 / 2000.0).collect();
+        let spectrum = pade_spectrum(&signal, 10.0, &frequencies, None).unwrap();
+        let power: Vec<f64> = spectrum.iter().map(|c| c.norm_sqr()).collect();
+        let peak = (0..power.len()).max_by(|&a, &b| power[a].total_cmp(&power[b])).unwrap();
+        assert!((frequencies[peak] - 2.0).abs() < 2e-5);
+        let above: Vec<f64> =
+            frequencies.iter().zip(&power).filter(|(_, &p)| p >= 0.5 * power[peak]).map(|(&f, _)| f).collect();
+        let half_width = 0.5 * (above[above.len() - 1] - above[0]);
+        assert!((half_width / (gamma / (2.0 * PI)) - 1.0).abs() < 0.05, "{}", half_width);
+    }
+}
@@ -0,0 +1,147 @@
+use num_complex::Complex64;
+
+use crate::numerical_calculations::dense_complex::ComplexMatrix;
+
+/// Outcome of a bounded least-squares fit.
+#[derive(Clone, Debug)]
+pub struct LeastSquaresFit {
+    pub params: Vec<f64>,
+    /// Sum of squared residuals at `params`.
+    pub cost: f64,
+    pub iterations: usize,
+}
+
+fn sum_squares(r: &[f64]) -> f64 {
+    r.iter().map(|v| v * v).sum()
+}
+
+fn clamp_to(x: &mut [f64], lower: &[f64], upper: &[f64]) {
+    for ((v, &lo), &hi) in x.iter_mut().zip(lower).zip(upper) {
+        *v = v.clamp(lo, hi);
+    }
+}
+
+/// Forward-difference Jacobian, stepping inward at an upper bound.
+fn jacobian(
+    residual: &impl Fn(&[f64]) -> Vec<f64>,
+    x: &[f64],
+    r0: &[f64],
+    upper: &[f64],
+) -> Vec<Vec<f64>> {
+    let mut columns = Vec::with_capacity(x.len());
+    let mut probe = x.to_vec();
+    for k in 0..x.len() {
+        let mut h = 1e-7 * x[k].abs().max(1e-6);
+        if x[k] + h > upper[k] {
+            h = -h;
+        }
+        probe[k] = x[k] + h;
+        let r = residual(&probe);
+        probe[k] = x[k];
+        columns.push(r.iter().zip(r0).map(|(a, b)| (a - b) / h).collect());
+    }
+    columns
+}
+
+/// Minimise ‖r(x)‖² subject to `lower ≤ x ≤ upper` with a projected
+/// Levenberg–Marquardt iteration. Parameters sitting on a bound with the
+/// gradient pointing outward are frozen for that step.
+pub fn bounded_least_squares(
+    residual: impl Fn(&[f64]) -> Vec<f64>,
+    x0: &[f64],
+    lower: &[f64],
+    upper: &[f64],
+    max_iterations: usize,
+) -> Result<LeastSquaresFit, String> {
+    let n = x0.len();
+    if lower.len() != n || upper.len() != n {
+        return Err("bounds must match the number of parameters".into());
+    }
+    if lower.iter().zip(upper).any(|(lo, hi)| lo > hi) {
+        return Err("lower bound exceeds upper bound".into());
+    }
+    let mut x = x0.to_vec();
+    clamp_to(&mut x, lower, upper);
+    let mut r = residual(&x);
+    let mut cost = sum_squares(&r);
+    if !cost.is_finite() {
+        return Err("residual is not finite at the initial guess".into());
+    }
+
+    let mut lambda = 1e-3;
+    let mut iterations = 0;
+    while iterations < max_iterations {
+        iterations += 1;
+        let jac = jacobian(&residual, &x, &r, upper);
+        let gradient: Vec<f64> = jac.iter().map(|c| c.iter().zip(&r).map(|(a, b)| a * b).sum()).collect();
+        let free: Vec<bool> = (0..n)
+            .map(|k| !((x[k] <= lower[k] && gradient[k] > 0.0) || (x[k] >= upper[k] && gradient[k] < 0.0)))
+            .collect();
+        if gradient.iter().zip(&free).all(|(g, &f)| !f || g.abs() <= 1e-15 * cost.max(1e-300)) {
+            break;
+        }
+
+        let mut improved = false;
+        while lambda < 1e12 {
+            let mut normal = ComplexMatrix::zeros(n);
+            let mut rhs = ComplexMatrix::zeros(n);
+            for i in 0..n {
+                if !free[i] {
+                    normal[(i, i)] = Complex64::new(1.0, 0.0);
+                    continue;
+                }
+                for j in 0..n {
+                    if free[j] {
+                        let jtj: f64 = jac[i].iter().zip(&jac[j]).map(|(a, b)| a * b).sum();
+                        normal[(i, j)] = Complex64::new(jtj, 0.0);
+                    }
+                }
+                let d = normal[(i, i)].re;
+                normal[(i, i)] = Complex64::new(d + lambda * d.max(1e-12), 0.0);
+                rhs[(i, 0)] = Complex64::new(-gradient[i], 0.0);
+            }
+            let step = normal.solve(&rhs)?;
+            let mut trial: Vec<f64> = (0..n).map(|k| x[k] + step[(k, 0)].re).collect();
+            clamp_to(&mut trial, lower, upper);
+            let r_trial = residual(&trial);
+            let trial_cost = sum_squares(&r_trial);
+            if trial_cost.is_finite() && trial_cost < cost {
+                let relative_gain = (cost - trial_cost) / cost.max(1e-300);
+                x = trial;
+                r = r_trial;
+                cost = trial_cost;
+                lambda = (lambda * 0.3).max(1e-12);
+                improved = relative_gain > 1e-12;
+                break;
+            }
+            lambda *= 10.0;
+        }
+        if !improved {
+            break;
+        }
+    }
+    Ok(LeastSquaresFit { params: x, cost, iterations })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recovers_exponential_decay() {
+        let t: Vec<f64> = (0..30).map(|i| i as f64 * 0.1).collect();
+        let data: Vec<f64> = t.iter().map(|&t| 2.5 * (-1.3 * t).exp()).collect();
+        let residual = |p: &[f64]| t.iter().zip(&data).map(|(&t, &y)| p[0] * (-p[1] * t).exp() - y).collect();
+        let fit = bounded_least_squares(residual, &[1.0, 0.5], &[0.0, 0.0], &[10.0, 10.0], 200).unwrap();
+        assert!((fit.params[0] - 2.5).abs() < 1e-6 && (fit.params[1] - 1.3).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_respects_bounds() {
+        // unconstrained minimum at x = -2
+        let residual = |p: &[f64]| vec![p[0] + 2.0];
+        let fit = bounded_least_squares(residual, &[1.0], &[0.0], &[5.0], 50).unwrap();
+        assert_eq!(fit.params[0], 0.0);
+        assert!((fit.cost - 4.0).abs() < 1e-12);
+    }
+}
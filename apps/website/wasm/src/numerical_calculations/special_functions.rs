@@ -0,0 +1,224 @@
+use num_complex::Complex64;
+use std::f64::consts::PI;
+
+const EULER_GAMMA: f64 = 0.577_215_664_901_532_9;
+
+/// Exponentially scaled Bessel functions e^{−|Im z|} Jₙ(z), n = 0…N, by
+/// Miller's downward recurrence Jₙ₋₁ = (2n/z) Jₙ − Jₙ₊₁ from well above N,
+/// which keeps the relative accuracy of small high orders. The sequence is
+/// normalised with e^{∓iz} = J₀ + 2 Σ (∓i)ⁿ Jₙ, taking the sign for which
+/// |e^{∓iz}| = e^{|Im z|} so that the terms add without cancellation.
+pub fn bessel_j_scaled_orders(z: Complex64, max_order: usize) -> Vec<Complex64> {
+    let mut j = vec![Complex64::new(0.0, 0.0); max_order + 1];
+    if z.norm() == 0.0 {
+        j[0] = Complex64::new(1.0, 0.0);
+        return j;
+    }
+    let size = z.norm();
+    let start = max_order.max(size.ceil() as usize) + (10.0 * size.cbrt()) as usize + 20;
+    let mut f = vec![Complex64::new(0.0, 0.0); start + 2];
+    f[start] = Complex64::new(1e-300, 0.0);
+    for n in (1..=start).rev() {
+        f[n - 1] = 2.0 * n as f64 / z * f[n] - f[n + 1];
+        if f[n - 1].norm() > 1e250 {
+            f[n - 1..].iter_mut().for_each(|v| *v *= 1e-250);
+        }
+    }
+    // bring the largest term to unit size so the complex division below
+    // (which squares the modulus) neither underflows nor overflows
+    let largest = f.iter().map(|v| v.norm()).fold(0.0, f64::max);
+    f.iter_mut().for_each(|v| *v /= largest);
+    let rotation = if z.im >= 0.0 { Complex64::new(0.0, -1.0) } else { Complex64::new(0.0, 1.0) };
+    let mut phase = Complex64::new(1.0, 0.0);
+    let mut sum = f[0];
+    for value in &f[1..] {
+        phase *= rotation;
+        sum += 2.0 * phase * value;
+    }
+    // e^{∓iz} e^{−|Im z|} has unit modulus
+    let target = Complex64::from_polar(1.0, if z.im >= 0.0 { -z.re } else { z.re });
+    let scale = target / sum;
+    for (out, value) in j.iter_mut().zip(&f) {
+        *out = value * scale;
+    }
+    j
+}
+
+/// Bessel functions J₀(z)…J_N(z) of complex argument; overflows once
+/// |Im z| ≳ 700, where `bessel_j_scaled_orders` should be used instead.
+pub fn bessel_j_orders(z: Complex64, max_order: usize) -> Vec<Complex64> {
+    let growth = z.im.abs().exp();
+    bessel_j_scaled_orders(z, max_order).iter().map(|j| j * growth).collect()
+}
+
+/// Logarithmic derivatives Gₙ(z) = Jₙ′(z)/Jₙ(z) by the downward recurrence
+/// Gₙ₋₁ = (n−1)/z − 1/(Gₙ + n/z), the cylindrical counterpart of
+/// `riccati_log_derivatives`; it neither overflows nor underflows.
+pub fn cylinder_log_derivatives(z: Complex64, max_order: usize) -> Vec<Complex64> {
+    let start = max_order.max(z.norm().ceil() as usize) + 16;
+    let mut g = vec![Complex64::new(0.0, 0.0); start + 1];
+    g[start] = start as f64 / z;
+    for n in (1..=start).rev() {
+        g[n - 1] = (n - 1) as f64 / z - 1.0 / (g[n] + n as f64 / z);
+    }
+    g.truncate(max_order + 1);
+    g
+}
+
+/// Bessel functions Y₀(x)…Y_N(x) for x > 0. Y₀ and Y₁ follow from the
+/// Neumann series
+///   Y₀ = (2/π)(ln(x/2) + γ) J₀ − (4/π) Σ (−1)ᵏ J₂ₖ / k,
+///   Y₁ = −Y₀′ = (2/π)(ln(x/2) + γ) J₁ − 2J₀/(πx) + (2/π) Σ (−1)ᵏ (J₂ₖ₋₁ − J₂ₖ₊₁) / k,
+/// and higher orders from the (stable) upward recurrence.
+pub fn bessel_y_orders(x: f64, max_order: usize) -> Vec<f64> {
+    let terms = (x + 10.0 * x.cbrt() + 30.0).ceil() as usize;
+    let j: Vec<f64> = bessel_j_orders(Complex64::new(x, 0.0), 2 * terms + 1).iter().map(|c| c.re).collect();
+    let log = (0.5 * x).ln() + EULER_GAMMA;
+    let mut y0 = 2.0 / PI * log * j[0];
+    let mut y1 = 2.0 / PI * log * j[1] - 2.0 * j[0] / (PI * x);
+    for k in 1..=terms {
+        let sign = if k % 2 == 0 { 1.0 } else { -1.0 };
+        y0 -= 4.0 / PI * sign * j[2 * k] / k as f64;
+        y1 += 2.0 / PI * sign * (j[2 * k - 1] - j[2 * k + 1]) / k as f64;
+    }
+    let mut y = vec![y0, y1];
+    for n in 1..max_order {
+        y.push(2.0 * n as f64 / x * y[n] - y[n - 1]);
+    }
+    y.truncate(max_order + 1);
+    y
+}
+
+/// Hankel functions H⁽¹⁾ₙ(x) = Jₙ(x) + i Yₙ(x) for x > 0, n = 0…N.
+pub fn hankel_orders(x: f64, max_order: usize) -> Vec<Complex64> {
+    let j = bessel_j_orders(Complex64::new(x, 0.0), max_order);
+    let y = bessel_y_orders(x, max_order);
+    j.iter().zip(&y).map(|(j, y)| Complex64::new(j.re, *y)).collect()
+}
+
+/// Exponentially scaled modified Bessel functions eˣ Kₙ(x), n = 0…N, for
+/// x > 0. K₀ and K₁ come from eˣ Kₙ = ∫₀^∞ exp(−x(cosh t − 1)) cosh(nt) dt,
+/// whose trapezoidal rule converges exponentially for a step well inside the
+/// integrand's width 1/√x; higher orders follow the stable upward recurrence.
+pub fn bessel_k_scaled_orders(x: f64, max_order: usize) -> Vec<f64> {
+    let step = 0.1f64.min(0.5 / x.sqrt());
+    let (mut k0, mut k1) = (0.5, 0.5);
+    for i in 1.. {
+        let t = i as f64 * step;
+        let decay = (-x * (t.cosh() - 1.0)).exp();
+        let (a, b) = (decay, decay * t.cosh());
+        k0 += a;
+        k1 += b;
+        if b < 1e-18 * k1 {
+            break;
+        }
+    }
+    let mut k = vec![k0 * step, k1 * step];
+    for n in 1..max_order {
+        k.push(k[n - 1] + 2.0 * n as f64 / x * k[n]);
+    }
+    k.truncate(max_order + 1);
+    k
+}
+
+/// Derivatives Zₙ′ = Zₙ₋₁ − (n/z) Zₙ of integer-order cylinder functions,
+/// with Z₋₁ = −Z₁.
+pub fn cylinder_derivatives(values: &[Complex64], z: Complex64) -> Vec<Complex64> {
+    (0..values.len())
+        .map(|n| {
+            let previous = if n == 0 { -values.get(1).copied().unwrap_or_default() } else { values[n - 1] };
+            previous - n as f64 / z * values[n]
+        })
+        .collect()
+}
+
+/// Logarithmic derivatives Dₙ(z) = ψₙ′(z)/ψₙ(z) of the Riccati–Bessel
+/// function ψₙ(z) = z jₙ(z), by downward recurrence from well above N.
+pub fn riccati_log_derivatives(z: Complex64, max_order: usize) -> Vec<Complex64> {
+    let start = max_order.max(z.norm().ceil() as usize) + 16;
+    let mut d = vec![Complex64::new(0.0, 0.0); start + 1];
+    for n in (1..=start).rev() {
+        let ratio = n as f64 / z;
+        d[n - 1] = ratio - 1.0 / (d[n] + ratio);
+    }
+    d.truncate(max_order + 1);
+    d
+}
+
+/// Riccati–Bessel ψₙ(z) = z jₙ(z), n = 0…N, from the downward product
+/// ψₙ₋₁ = ψₙ (Dₙ + n/z), which is stable for complex z. The result is scaled
+/// to whichever of ψ₀ = sin z and ψ₁ = sin z / z − cos z is larger, so zeros
+/// of sin z (kR = π for a half-wave sphere) lose no accuracy.
+pub fn riccati_psi(z: Complex64, max_order: usize) -> Vec<Complex64> {
+    let d = riccati_log_derivatives(z, max_order.max(1));
+    let mut psi = vec![Complex64::new(1.0, 0.0); max_order.max(1) + 1];
+    for n in (1..psi.len()).rev() {
+        psi[n - 1] = psi[n] * (d[n] + n as f64 / z);
+    }
+    let (psi0, psi1) = (z.sin(), z.sin() / z - z.cos());
+    let scale = if psi0.norm() >= psi1.norm() { psi0 / psi[0] } else { psi1 / psi[1] };
+    psi.truncate(max_order + 1);
+    psi.iter().map(|p| p * scale).collect()
+}
+
+/// Riccati–Bessel ξₙ(x) = x h⁽¹⁾ₙ(x) = ψₙ(x) − iχₙ(x) for real x > 0, with
+/// χₙ = −x yₙ from its upward recurrence.
+pub fn riccati_xi(x: f64, max_order: usize) -> Vec<Complex64> {
+    let psi = riccati_psi(Complex64::new(x, 0.0), max_order);
+    let mut chi = vec![x.cos(), x.cos() / x + x.sin()];
+    for n in 2..=max_order {
+        chi.push((2 * n - 1) as f64 / x * chi[n - 1] - chi[n - 2]);
+    }
+    psi.iter().zip(&chi).map(|(p, c)| Complex64::new(p.re, -c)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cylinder_functions_match_tables() {
+        let j = bessel_j_orders(Complex64::new(1.0, 0.0), 3);
+        assert!((j[0].re - 0.765_197_686_6).abs() < 1e-10);
+        assert!((j[1].re - 0.440_050_585_7).abs() < 1e-10);
+        assert!(j[0].im.abs() < 1e-14);
+        let y = bessel_y_orders(1.0, 2);
+        assert!((y[0] - 0.088_256_964_2).abs() < 1e-10);
+        assert!((y[1] + 0.781_212_821_3).abs() < 1e-10);
+        assert!((y[2] + 1.650_682_606_8).abs() < 1e-9);
+        let y = bessel_y_orders(10.0, 1);
+        assert!((y[0] - 0.055_671_167_3).abs() < 1e-10);
+        assert!((y[1] - 0.249_015_424_2).abs() < 1e-10);
+        // J₀(i) = I₀(1)
+        let j = bessel_j_orders(Complex64::new(0.0, 1.0), 0);
+        assert!((j[0].re - 1.266_065_877_8).abs() < 1e-10);
+        // relative accuracy in small orders, and the scaled form far off the axis
+        let j = bessel_j_orders(Complex64::new(1.0, 0.0), 30);
+        assert!((j[30].re / 3.482_869_794_251_483e-42 - 1.0).abs() < 1e-12);
+        let j = bessel_j_scaled_orders(Complex64::new(3.0, 800.0), 5);
+        assert!((j[5] - Complex64::new(0.001_984_860_208_232_204, -0.013_745_487_799_253_697)).norm() < 1e-12);
+        let k = bessel_k_scaled_orders(1.0, 2);
+        assert!((k[0] / 1f64.exp() - 0.421_024_438_2).abs() < 1e-10);
+        assert!((k[1] / 1f64.exp() - 0.601_907_230_2).abs() < 1e-10);
+        assert!((k[2] / 1f64.exp() - 1.624_838_898_6).abs() < 1e-9);
+        // K₁(x) → 1/x as x → 0
+        let k = bessel_k_scaled_orders(1e-4, 1);
+        assert!((k[1] * (-1e-4f64).exp() * 1e-4 - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_riccati_functions_match_closed_forms() {
+        // ψ₁(z) = sin z / z − cos z, χ₁ = cos x / x + sin x
+        let z = Complex64::new(2.0, 0.7);
+        let psi = riccati_psi(z, 4);
+        assert!((psi[1] - (z.sin() / z - z.cos())).norm() < 1e-12);
+        // at z = π, ψ₀ vanishes but ψ₂ = (3/π² − 1) sin π − 3 cos π / π does not
+        let psi = riccati_psi(Complex64::new(PI, 0.0), 2);
+        assert!((psi[2].re - 3.0 / PI).abs() < 1e-12);
+        let x = 3.0;
+        let xi = riccati_xi(x, 3);
+        assert!((xi[1].im + (x.cos() / x + x.sin())).abs() < 1e-12);
+        // Wronskian ψₙ χₙ′ − ψₙ′ χₙ = −1, checked through ψ₂χ₁ − ψ₁χ₂ = −1
+        assert!((xi[2].re * -xi[1].im - xi[1].re * -xi[2].im + 1.0).abs() < 1e-12);
+    }
+}
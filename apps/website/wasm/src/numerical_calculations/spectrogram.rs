@@ -0,0 +1,277 @@
+use rustfft::{FftPlanner, num_complex::Complex64};
+use wasm_bindgen::prelude::*;
+use serde::Deserialize;
+use std::f64::consts::PI;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WindowKind {
+    Rectangular,
+    Hann,
+    Hamming,
+    Blackman,
+}
+
+impl WindowKind {
+    /// Periodic window of length `n`, which sums to a constant under
+    /// overlap-add at the usual hop sizes.
+    pub fn samples(&self, n: usize) -> Vec<f64> {
+        (0..n)
+            .map(|i| {
+                let x = 2.0 * PI * i as f64 / n as f64;
+                match self {
+                    WindowKind::Rectangular => 1.0,
+                    WindowKind::Hann => 0.5 - 0.5 * x.cos(),
+                    WindowKind::Hamming => 0.54 - 0.46 * x.cos(),
+                    WindowKind::Blackman => 0.42 - 0.5 * x.cos() + 0.08 * (2.0 * x).cos(),
+                }
+            })
+            .collect()
+    }
+}
+
+fn default_window() -> WindowKind {
+    WindowKind::Hann
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct StftConfig {
+    pub sample_rate: f64,
+    pub window_length: usize,
+    #[serde(default = "default_window")]
+    pub window: WindowKind,
+    /// Samples between frames; defaults to a quarter window.
+    #[serde(default)]
+    pub hop: Option<usize>,
+    /// FFT length ≥ window_length; the frame is zero-padded up to it.
+    #[serde(default)]
+    pub fft_length: Option<usize>,
+}
+
+impl StftConfig {
+    fn hop(&self) -> usize {
+        self.hop.unwrap_or(self.window_length / 4).max(1)
+    }
+
+    fn fft_length(&self) -> usize {
+        self.fft_length.unwrap_or(self.window_length)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.window_length == 0 || self.sample_rate <= 0.0 {
+            return Err("window length and sample rate must be positive".into());
+        }
+        if self.fft_length() < self.window_length {
+            return Err("fft_length must be at least the window length".into());
+        }
+        if self.hop() > self.window_length {
+            return Err("hop must not exceed the window length".into());
+        }
+        Ok(())
+    }
+}
+
+/// One-sided STFT, laid out frame-major: entry `frame * bins + bin`.
+#[wasm_bindgen]
+pub struct Spectrogram {
+    frames: usize,
+    bins: usize,
+    times: Vec<f64>,
+    frequencies: Vec<f64>,
+    real: Vec<f64>,
+    imag: Vec<f64>,
+    magnitude: Vec<f64>,
+    phase: Vec<f64>,
+}
+
+#[wasm_bindgen]
+impl Spectrogram {
+    #[wasm_bindgen(getter)]
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn bins(&self) -> usize {
+        self.bins
+    }
+
+    /// Centre time of each frame.
+    #[wasm_bindgen(getter)]
+    pub fn times(&self) -> Vec<f64> {
+        self.times.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn frequencies(&self) -> Vec<f64> {
+        self.frequencies.clone()
+    }
+
+    /// Raw transform, as taken by `compute_istft`.
+    #[wasm_bindgen(getter)]
+    pub fn real(&self) -> Vec<f64> {
+        self.real.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn imag(&self) -> Vec<f64> {
+        self.imag.clone()
+    }
+
+    /// Amplitude-calibrated: a sinusoid of amplitude A peaks at A.
+    #[wasm_bindgen(getter)]
+    pub fn magnitude(&self) -> Vec<f64> {
+        self.magnitude.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn phase(&self) -> Vec<f64> {
+        self.phase.clone()
+    }
+}
+
+/// Short-time Fourier transform X_m[k] = Σ_n x[mH + n] w[n] exp(−2πi kn / N).
+pub fn stft_internal(signal: &[f64], config: &StftConfig) -> Result<Spectrogram, String> {
+    config.validate()?;
+    let length = config.window_length;
+    if signal.len() < length {
+        return Err("signal is shorter than the window".into());
+    }
+    let hop = config.hop();
+    let n = config.fft_length();
+    let bins = n / 2 + 1;
+    let frames = 1 + (signal.len() - length) / hop;
+    let window = config.window.samples(length);
+    let gain: f64 = window.iter().sum();
+
+    let mut planner = FftPlanner::new();
+    let fft = planner.plan_fft_forward(n);
+    let mut buffer = vec![Complex64::new(0.0, 0.0); n];
+    let mut result = Spectrogram {
+        frames,
+        bins,
+        times: (0..frames).map(|m| (m * hop) as f64 / config.sample_rate + 0.5 * length as f64 / config.sample_rate).collect(),
+        frequencies: (0..bins).map(|k| k as f64 * config.sample_rate / n as f64).collect(),
+        real: Vec::with_capacity(frames * bins),
+        imag: Vec::with_capacity(frames * bins),
+        magnitude: Vec::with_capacity(frames * bins),
+        phase: Vec::with_capacity(frames * bins),
+    };
+    for m in 0..frames {
+        let start = m * hop;
+        for (i, b) in buffer.iter_mut().enumerate() {
+            *b = if i < length { Complex64::new(signal[start + i] * window[i], 0.0) } else { Complex64::new(0.0, 0.0) };
+        }
+        fft.process(&mut buffer);
+        for (k, c) in buffer[..bins].iter().enumerate() {
+            let one_sided = if k == 0 || 2 * k == n { 1.0 } else { 2.0 };
+            result.real.push(c.re);
+            result.imag.push(c.im);
+            result.magnitude.push(one_sided * c.norm() / gain);
+            result.phase.push(c.arg());
+        }
+    }
+    Ok(result)
+}
+
+/// Inverse STFT by weighted overlap-add,
+/// x[n] = Σ_m w[n − mH] y_m[n − mH] / Σ_m w²[n − mH],
+/// which inverts `stft_internal` exactly wherever the windows cover the signal.
+pub fn istft_internal(real: &[f64], imag: &[f64], config: &StftConfig) -> Result<Vec<f64>, String> {
+    config.validate()?;
+    let length = config.window_length;
+    let hop = config.hop();
+    let n = config.fft_length();
+    let bins = n / 2 + 1;
+    if real.len() != imag.len() || real.is_empty() || !real.len().is_multiple_of(bins) {
+        return Err(format!("real and imag must hold whole frames of {} bins", bins));
+    }
+    let frames = real.len() / bins;
+    let window = config.window.samples(length);
+
+    let mut planner = FftPlanner::new();
+    let ifft = planner.plan_fft_inverse(n);
+    let mut buffer = vec![Complex64::new(0.0, 0.0); n];
+    let output_length = (frames - 1) * hop + length;
+    let mut signal = vec![0.0; output_length];
+    let mut norm = vec![0.0; output_length];
+    for m in 0..frames {
+        // rebuild the Hermitian spectrum of a real frame
+        for k in 0..n {
+            buffer[k] = if k < bins {
+                Complex64::new(real[m * bins + k], imag[m * bins + k])
+            } else {
+                Complex64::new(real[m * bins + n - k], -imag[m * bins + n - k])
+            };
+        }
+        ifft.process(&mut buffer);
+        let start = m * hop;
+        for i in 0..length {
+            signal[start + i] += window[i] * buffer[i].re / n as f64;
+            norm[start + i] += window[i] * window[i];
+        }
+    }
+    for (x, w) in signal.iter_mut().zip(&norm) {
+        *x = if *w > 1e-12 { *x / w } else { 0.0 };
+    }
+    Ok(signal)
+}
+
+/// Compute the magnitude/phase spectrogram of a real-valued signal
+#[wasm_bindgen]
+pub fn compute_stft(signal: &[f64], config: &JsValue) -> Result<Spectrogram, JsValue> {
+    let config: StftConfig = serde_wasm_bindgen::from_value(config.clone())?;
+    stft_internal(signal, &config).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Reconstruct a signal from its STFT by overlap-add
+#[wasm_bindgen]
+pub fn compute_istft(real: &[f64], imag: &[f64], config: &JsValue) -> Result<Vec<f64>, JsValue> {
+    let config: StftConfig = serde_wasm_bindgen::from_value(config.clone())?;
+    istft_internal(real, imag, &config).map_err(|e| JsValue::from_str(&e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(window: WindowKind) -> StftConfig {
+        StftConfig { sample_rate: 100.0, window_length: 64, window, hop: Some(16), fft_length: Some(256) }
+    }
+
+    #[test]
+    fn test_tracks_chirp_frequency() {
+        // instantaneous frequency 5 + 10 t over 4 s
+        let signal: Vec<f64> = (0..400)
+            .map(|i| {
+                let t = i as f64 / 100.0;
+                1.5 * (2.0 * PI * (5.0 * t + 5.0 * t * t)).sin()
+            })
+            .collect();
+        let spectrogram = stft_internal(&signal, &config(WindowKind::Hann)).unwrap();
+        assert_eq!(spectrogram.frames(), 1 + (400 - 64) / 16);
+        assert_eq!(spectrogram.bins(), 129);
+        let (magnitude, frequencies) = (spectrogram.magnitude(), spectrogram.frequencies());
+        for (m, t) in spectrogram.times().iter().enumerate() {
+            let row = &magnitude[m * 129..(m + 1) * 129];
+            let peak = (0..129).max_by(|&a, &b| row[a].total_cmp(&row[b])).unwrap();
+            assert!((frequencies[peak] - (5.0 + 10.0 * t)).abs() < 1.0, "t = {}", t);
+            assert!((row[peak] - 1.5).abs() < 0.2);
+        }
+    }
+
+    #[test]
+    fn test_inverse_reconstructs_signal() {
+        let signal: Vec<f64> = (0..320).map(|i| (0.3 * i as f64).sin() + 0.01 * (i % 7) as f64).collect();
+        for window in [WindowKind::Hann, WindowKind::Hamming, WindowKind::Rectangular] {
+            let cfg = config(window);
+            let spectrogram = stft_internal(&signal, &cfg).unwrap();
+            let rebuilt = istft_internal(&spectrogram.real(), &spectrogram.imag(), &cfg).unwrap();
+            assert_eq!(rebuilt.len(), signal.len());
+            // the Hann window vanishes at the very first sample
+            for i in 1..signal.len() {
+                assert!((rebuilt[i] - signal[i]).abs() < 1e-10, "{:?} sample {}", window, i);
+            }
+        }
+    }
+}
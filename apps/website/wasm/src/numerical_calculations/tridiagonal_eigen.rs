@@ -0,0 +1,117 @@
+use num_complex::Complex64;
+
+use crate::numerical_calculations::banded_solver::BandedMatrix;
+
+/// Number of eigenvalues smaller than `x` of the symmetric tridiagonal matrix
+/// with diagonal `d` and off-diagonal `e` (Sturm sequence count).
+pub fn sturm_count(d: &[f64], e: &[f64], x: f64) -> usize {
+    let mut count = 0;
+    let mut q = 1.0;
+    for i in 0..d.len() {
+        let coupling = if i > 0 { e[i - 1] * e[i - 1] } else { 0.0 };
+        q = d[i] - x - if i > 0 { coupling / q } else { 0.0 };
+        if q == 0.0 {
+            q = -f64::EPSILON * (d[i].abs() + x.abs()).max(1.0);
+        }
+        if q < 0.0 {
+            count += 1;
+        }
+    }
+    count
+}
+
+/// k-th smallest eigenvalue (0-based) by bisection inside the Gershgorin bounds.
+pub fn tridiagonal_eigenvalue(d: &[f64], e: &[f64], k: usize) -> Result<f64, String> {
+    let n = d.len();
+    if k >= n {
+        return Err(format!("requested eigenvalue {} of a {}x{} matrix", k, n, n));
+    }
+    let mut lo = f64::INFINITY;
+    let mut hi = f64::NEG_INFINITY;
+    for i in 0..n {
+        let r = if i > 0 { e[i - 1].abs() } else { 0.0 } + if i + 1 < n { e[i].abs() } else { 0.0 };
+        lo = lo.min(d[i] - r);
+        hi = hi.max(d[i] + r);
+    }
+    let tol = f64::EPSILON * (lo.abs().max(hi.abs())).max(1e-300);
+    while hi - lo > 2.0 * tol {
+        let mid = 0.5 * (lo + hi);
+        if mid == lo || mid == hi {
+            break;
+        }
+        if sturm_count(d, e, mid) > k {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+    Ok(0.5 * (lo + hi))
+}
+
+/// Unit eigenvector for a known eigenvalue by inverse iteration.
+pub fn tridiagonal_eigenvector(d: &[f64], e: &[f64], lambda: f64) -> Result<Vec<f64>, String> {
+    let n = d.len();
+    let scale = d.iter().chain(e).fold(0.0f64, |m, v| m.max(v.abs())).max(1e-300);
+    // a tiny shift keeps the factorisation regular without spoiling convergence
+    let shift = lambda + 1e-10 * scale;
+
+    let mut a = BandedMatrix::new(n, 1, 1);
+    for i in 0..n {
+        a.add(i, i, Complex64::new(d[i] - shift, 0.0))?;
+        if i + 1 < n {
+            a.add(i, i + 1, Complex64::new(e[i], 0.0))?;
+            a.add(i + 1, i, Complex64::new(e[i], 0.0))?;
+        }
+    }
+    let lu = a.factor()?;
+
+    let mut v: Vec<Complex64> = (0..n)
+        .map(|i| Complex64::new(1.0 + 0.1 * ((i * 7919) % 13) as f64, 0.0))
+        .collect();
+    for _ in 0..4 {
+        v = lu.solve(&v);
+        let norm = v.iter().map(|c| c.norm_sqr()).sum::<f64>().sqrt();
+        if !norm.is_finite() || norm == 0.0 {
+            return Err("inverse iteration failed to converge".into());
+        }
+        v.iter_mut().for_each(|c| *c /= norm);
+    }
+    Ok(v.iter().map(|c| c.re).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    // the second-difference matrix tridiag(−1, 2, −1) of order n has
+    // λ_k = 2 − 2 cos(kπ/(n+1)) with eigenvectors sin(jkπ/(n+1))
+    const N: usize = 12;
+
+    fn exact(k: usize) -> f64 {
+        2.0 - 2.0 * (k as f64 * PI / (N + 1) as f64).cos()
+    }
+
+    #[test]
+    fn test_second_difference_spectrum() {
+        let (d, e) = (vec![2.0; N], vec![-1.0; N - 1]);
+        for k in 0..N {
+            let lambda = tridiagonal_eigenvalue(&d, &e, k).unwrap();
+            assert!((lambda - exact(k + 1)).abs() < 1e-13, "{}: {}", k, lambda);
+        }
+        assert_eq!(sturm_count(&d, &e, 0.5 * (exact(3) + exact(4))), 3);
+        assert!(tridiagonal_eigenvalue(&d, &e, N).is_err());
+    }
+
+    #[test]
+    fn test_inverse_iteration_recovers_sine_modes() {
+        let (d, e) = (vec![2.0; N], vec![-1.0; N - 1]);
+        for k in [1, 5] {
+            let v = tridiagonal_eigenvector(&d, &e, exact(k)).unwrap();
+            let mode: Vec<f64> = (1..=N).map(|j| (j as f64 * k as f64 * PI / (N + 1) as f64).sin()).collect();
+            let norm = mode.iter().map(|m| m * m).sum::<f64>().sqrt();
+            let overlap: f64 = v.iter().zip(&mode).map(|(a, b)| a * b).sum::<f64>() / norm;
+            assert!((overlap.abs() - 1.0).abs() < 1e-10, "{}: {}", k, overlap);
+        }
+    }
+}
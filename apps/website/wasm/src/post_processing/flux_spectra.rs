@@ -0,0 +1,357 @@
+use num_complex::Complex64;
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::field_solvers::fdfd::complex_from_parts;
+
+/// Complex values split into real and imaginary arrays; a missing
+/// imaginary part is zero.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct ComplexArray {
+    pub real: Vec<f64>,
+    #[serde(default)]
+    pub imag: Vec<f64>,
+}
+
+impl ComplexArray {
+    pub fn values(&self) -> Vec<Complex64> {
+        complex_from_parts(&self.real, &self.imag)
+    }
+}
+
+/// DFT fields saved by `get_flux_data`: the tangential components (t₁, t₂)
+/// of E and H on the monitor, with (n, t₁, t₂) right-handed, laid out
+/// frequency-major (`frequency * points + point`). Components absent in a
+/// 2D polarisation may be left empty.
+#[derive(Clone, Debug, Deserialize)]
+pub struct FluxFieldData {
+    #[serde(default)]
+    pub e1: ComplexArray,
+    #[serde(default)]
+    pub e2: ComplexArray,
+    #[serde(default)]
+    pub h1: ComplexArray,
+    #[serde(default)]
+    pub h2: ComplexArray,
+    /// Length (2D) or area (3D) represented by each point.
+    #[serde(default = "unit_weight")]
+    pub cell_area: f64,
+}
+
+fn unit_weight() -> f64 {
+    1.0
+}
+
+fn positive_direction() -> f64 {
+    1.0
+}
+
+/// One flux monitor of one run, as configured by `FluxRegion`.
+#[derive(Clone, Debug, Deserialize)]
+pub struct FluxMonitorData {
+    /// `get_fluxes` output; Meep has already applied `weight` to it.
+    #[serde(default)]
+    pub flux: Vec<f64>,
+    #[serde(default)]
+    pub fields: Option<FluxFieldData>,
+    /// `FluxRegion.weight` and `directionSign`, applied to flux computed
+    /// from `fields`.
+    #[serde(default = "unit_weight")]
+    pub weight: f64,
+    #[serde(default = "positive_direction", rename = "directionSign", alias = "direction_sign")]
+    pub direction_sign: f64,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct FluxRunData {
+    pub reflection: FluxMonitorData,
+    #[serde(default)]
+    pub transmission: Option<FluxMonitorData>,
+}
+
+fn default_tolerance() -> f64 {
+    0.02
+}
+
+#[derive(Deserialize)]
+pub struct FluxNormalizationConfig {
+    pub frequencies: Vec<f64>,
+    /// The empty-cell run that records the incident field.
+    pub normalization: FluxRunData,
+    pub scattering: FluxRunData,
+    /// Set when the scattering run already called `load_minus_flux_data`,
+    /// so its reflection flux is the reflected power alone.
+    #[serde(default)]
+    pub minus_flux_loaded: bool,
+    #[serde(default = "default_tolerance")]
+    pub tolerance: f64,
+    /// Warn whenever 1 − R − T departs from zero, not only when it is negative.
+    #[serde(default)]
+    pub expect_lossless: bool,
+}
+
+#[derive(Serialize)]
+pub struct FluxNormalizationResult {
+    pub frequencies: Vec<f64>,
+    pub incident_flux: Vec<f64>,
+    pub reflected_flux: Vec<f64>,
+    pub transmitted_flux: Vec<f64>,
+    pub reflectance: Vec<f64>,
+    pub transmittance: Vec<f64>,
+    pub loss: Vec<f64>,
+    pub energy_conserved: bool,
+    /// Largest violation of R, T ≥ 0 and R + T ≤ 1 (or |1 − R − T| when lossless).
+    pub max_violation: f64,
+    pub warnings: Vec<String>,
+}
+
+/// Flux Re Σ (E₁* H₂ − E₂* H₁) ΔA through the monitor, per frequency, in the
+/// direction of its normal times `weight · directionSign`.
+pub fn flux_from_fields(fields: &FluxFieldData, frequencies: usize, scale: f64) -> Result<Vec<f64>, String> {
+    flux_of_difference(fields, None, frequencies, scale)
+}
+
+/// As `flux_from_fields`, for the fields minus those of `subtract`.
+fn flux_of_difference(
+    fields: &FluxFieldData,
+    subtract: Option<&FluxFieldData>,
+    frequencies: usize,
+    scale: f64,
+) -> Result<Vec<f64>, String> {
+    let components = |f: &FluxFieldData| [f.e1.values(), f.e2.values(), f.h1.values(), f.h2.values()];
+    let mut own = components(fields);
+    let length = own.iter().map(|c| c.len()).max().unwrap_or(0);
+    if length == 0 || length % frequencies != 0 {
+        return Err(format!("DFT fields must hold {} frequencies × points values", frequencies));
+    }
+    let theirs = subtract.map(components);
+    if let (Some(other), Some(theirs)) = (subtract, &theirs) {
+        // compare before padding, or a run with another point or frequency
+        // count would be silently truncated or zero-filled
+        let sizes = |c: &[Vec<Complex64>; 4]| c.each_ref().map(Vec::len);
+        if sizes(&own) != sizes(theirs) {
+            return Err(format!(
+                "normalisation and scattering fields differ in size: {:?} vs {:?} values per component",
+                sizes(theirs),
+                sizes(&own)
+            ));
+        }
+        if (other.cell_area - fields.cell_area).abs() > 1e-9 * fields.cell_area.abs() {
+            return Err(format!(
+                "normalisation and scattering fields differ in cell_area: {} vs {}",
+                other.cell_area, fields.cell_area
+            ));
+        }
+    }
+    for c in own.iter_mut() {
+        c.resize(length, Complex64::new(0.0, 0.0));
+    }
+    if let Some(theirs) = theirs {
+        for (c, mut o) in own.iter_mut().zip(theirs) {
+            o.resize(length, Complex64::new(0.0, 0.0));
+            for (v, w) in c.iter_mut().zip(&o) {
+                *v -= w;
+            }
+        }
+    }
+    let points = length / frequencies;
+    let [e1, e2, h1, h2] = &own;
+    Ok((0..frequencies)
+        .map(|f| {
+            let range = f * points..(f + 1) * points;
+            let sum: f64 = range.map(|i| (e1[i].conj() * h2[i] - e2[i].conj() * h1[i]).re).sum();
+            scale * fields.cell_area * sum
+        })
+        .collect())
+}
+
+impl FluxMonitorData {
+    fn scale(&self) -> f64 {
+        self.weight * self.direction_sign
+    }
+
+    fn flux(&self, frequencies: usize) -> Result<Vec<f64>, String> {
+        if self.flux.len() == frequencies {
+            return Ok(self.flux.clone());
+        }
+        match &self.fields {
+            Some(fields) => flux_from_fields(fields, frequencies, self.scale()),
+            None => Err(format!("a monitor needs {} flux values or its DFT fields", frequencies)),
+        }
+    }
+}
+
+pub fn normalize_flux_internal(config: &FluxNormalizationConfig) -> Result<FluxNormalizationResult, String> {
+    let n = config.frequencies.len();
+    if n == 0 {
+        return Err("at least one frequency is required".into());
+    }
+    let mut warnings = Vec::new();
+    let incident = config.normalization.reflection.flux(n)?;
+
+    // reflected power: flux of the scattered field E − E_inc at the same monitor
+    let scattered = &config.scattering.reflection;
+    let reflected = match (&scattered.fields, &config.normalization.reflection.fields) {
+        (Some(fields), Some(incident_fields)) if !config.minus_flux_loaded => {
+            flux_of_difference(fields, Some(incident_fields), n, scattered.scale())?
+        }
+        _ if config.minus_flux_loaded => scattered.flux(n)?,
+        _ => {
+            warnings.push(
+                "no DFT fields to subtract; R is taken from the net flux, which ignores incident–reflected interference"
+                    .into(),
+            );
+            scattered.flux(n)?.iter().zip(&incident).map(|(net, inc)| net - inc).collect()
+        }
+    };
+
+    let transmitted = match &config.scattering.transmission {
+        Some(monitor) => monitor.flux(n)?,
+        None => vec![0.0; n],
+    };
+    let peak = incident.iter().fold(0.0f64, |m, v| m.max(v.abs()));
+    if peak == 0.0 {
+        return Err("the incident flux is zero".into());
+    }
+    // the configured weight and direction are kept: absorbing or narrow-band
+    // runs can have a genuinely negative net transmission
+    let orientation: f64 = transmitted.iter().zip(&incident).map(|(t, i)| t * i).sum();
+    if orientation < 0.0 {
+        warnings.push(
+            "the net transmitted flux opposes the incident flux; check the transmission monitor's weight and direction"
+                .into(),
+        );
+    }
+
+    let reflectance: Vec<f64> = reflected.iter().zip(&incident).map(|(r, i)| -r / i).collect();
+    let transmittance: Vec<f64> = transmitted.iter().zip(&incident).map(|(t, i)| t / i).collect();
+    let loss: Vec<f64> = reflectance.iter().zip(&transmittance).map(|(r, t)| 1.0 - r - t).collect();
+
+    let weak = incident.iter().filter(|i| i.abs() < 1e-3 * peak).count();
+    if weak > 0 {
+        warnings.push(format!(
+            "{} frequencies have less than 0.1% of the peak incident flux; their R and T are unreliable",
+            weak
+        ));
+    }
+    let max_violation = (0..n)
+        .map(|k| {
+            let negative = (-reflectance[k]).max(-transmittance[k]).max(0.0);
+            let excess = if config.expect_lossless { loss[k].abs() } else { (-loss[k]).max(0.0) };
+            negative.max(excess)
+        })
+        .fold(0.0, f64::max);
+    let energy_conserved = max_violation <= config.tolerance;
+    if !energy_conserved {
+        warnings.push(format!(
+            "energy conservation is violated by up to {:.1}%; check the normalisation run, monitor placement and PML",
+            100.0 * max_violation
+        ));
+    }
+
+    Ok(FluxNormalizationResult {
+        frequencies: config.frequencies.clone(),
+        incident_flux: incident,
+        reflected_flux: reflected,
+        transmitted_flux: transmitted,
+        reflectance,
+        transmittance,
+        loss,
+        energy_conserved,
+        max_violation,
+        warnings,
+    })
+}
+
+/// Reflectance, transmittance and loss from normalisation and scattering flux data
+#[wasm_bindgen]
+pub fn normalize_flux_spectra(config: &JsValue) -> Result<JsValue, JsValue> {
+    let config: FluxNormalizationConfig = serde_wasm_bindgen::from_value(config.clone())?;
+    let result = normalize_flux_internal(&config).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ez polarisation on a monitor normal to x: t₁ = y, t₂ = z, so the flux
+    /// is −Re(Ez* Hy). A wave along ±x has Hy = ∓Ez.
+    fn monitor(forward: &[Complex64], backward: &[Complex64]) -> FluxMonitorData {
+        let ez: Vec<Complex64> = forward.iter().zip(backward).map(|(f, b)| f + b).collect();
+        let hy: Vec<Complex64> = forward.iter().zip(backward).map(|(f, b)| -f + b).collect();
+        FluxMonitorData {
+            flux: Vec::new(),
+            fields: Some(FluxFieldData {
+                e1: ComplexArray::default(),
+                e2: ComplexArray { real: ez.iter().map(|c| c.re).collect(), imag: ez.iter().map(|c| c.im).collect() },
+                h1: ComplexArray { real: hy.iter().map(|c| c.re).collect(), imag: hy.iter().map(|c| c.im).collect() },
+                h2: ComplexArray::default(),
+                cell_area: 0.1,
+            }),
+            weight: 1.0,
+            direction_sign: 1.0,
+        }
+    }
+
+    fn config(transmission_sign: f64) -> FluxNormalizationConfig {
+        // two frequencies, three points each
+        let incident: Vec<Complex64> = (0..6).map(|i| Complex64::from_polar(1.0 + 0.1 * i as f64, 0.3 * i as f64)).collect();
+        let zero = vec![Complex64::new(0.0, 0.0); 6];
+        let r = Complex64::from_polar(0.6, 1.1);
+        let t = Complex64::from_polar(0.7, -0.4);
+        let reflected: Vec<Complex64> = incident.iter().map(|a| r * a).collect();
+        let transmitted: Vec<Complex64> = incident.iter().map(|a| t * a).collect();
+        let mut transmission = monitor(&transmitted, &zero);
+        transmission.direction_sign = transmission_sign;
+        FluxNormalizationConfig {
+            frequencies: vec![0.9, 1.1],
+            normalization: FluxRunData { reflection: monitor(&incident, &zero), transmission: Some(monitor(&incident, &zero)) },
+            scattering: FluxRunData { reflection: monitor(&incident, &reflected), transmission: Some(transmission) },
+            minus_flux_loaded: false,
+            tolerance: default_tolerance(),
+            expect_lossless: false,
+        }
+    }
+
+    #[test]
+    fn test_subtraction_recovers_reflectance_and_loss() {
+        let result = normalize_flux_internal(&config(1.0)).unwrap();
+        for k in 0..2 {
+            assert!((result.reflectance[k] - 0.36).abs() < 1e-12);
+            assert!((result.transmittance[k] - 0.49).abs() < 1e-12);
+            assert!((result.loss[k] - 0.15).abs() < 1e-12);
+        }
+        assert!(result.energy_conserved);
+        assert!(result.warnings.is_empty());
+
+        let lossless = FluxNormalizationConfig { expect_lossless: true, ..config(1.0) };
+        assert!(!normalize_flux_internal(&lossless).unwrap().energy_conserved);
+    }
+
+    #[test]
+    fn test_reversed_transmission_monitor_is_reported() {
+        let result = normalize_flux_internal(&config(-1.0)).unwrap();
+        assert!((result.transmittance[0] + 0.49).abs() < 1e-12);
+        assert!(result.warnings.iter().any(|w| w.contains("opposes the incident flux")));
+        assert!(!result.energy_conserved);
+    }
+
+    #[test]
+    fn test_mismatched_normalisation_fields_are_rejected() {
+        // a normalisation run with two points per frequency instead of three
+        let mut short = config(1.0);
+        let fields = short.normalization.reflection.fields.as_mut().unwrap();
+        for c in [&mut fields.e2, &mut fields.h1] {
+            c.real.truncate(4);
+            c.imag.truncate(4);
+        }
+        let error = normalize_flux_internal(&short).err().unwrap();
+        assert!(error.contains("differ in size"), "{}", error);
+
+        let mut coarse = config(1.0);
+        coarse.normalization.reflection.fields.as_mut().unwrap().cell_area = 0.2;
+        let error = normalize_flux_internal(&coarse).err().unwrap();
+        assert!(error.contains("cell_area"), "{}", error);
+    }
+}
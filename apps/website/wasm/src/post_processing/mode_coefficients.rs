@@ -0,0 +1,427 @@
+use num_complex::Complex64;
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+use crate::field_solvers::eigenmode_solver::{find_eigenmode, sample_source_line, CrossSectionMode, EigenParity, ModeSpec};
+use crate::field_solvers::fdfd::FdfdPolarization;
+use crate::field_solvers::yee_grid::YeeGrid2D;
+use crate::lattice_calculations::voronoi_cells::Vector2D;
+use crate::material_calculations::material_library::UnitSystem;
+use crate::post_processing::flux_spectra::{ComplexArray, FluxFieldData};
+use crate::post_processing::touchstone::{write_touchstone, NetworkData, TouchstoneWriteOptions};
+
+fn default_band() -> usize {
+    1
+}
+
+fn default_parity() -> String {
+    "NO_PARITY".into()
+}
+
+fn into_positive() -> f64 {
+    1.0
+}
+
+/// A mode monitor (`add_mode_monitor`) on a line of the 2D cell.
+#[derive(Clone, Debug, Deserialize)]
+pub struct ModePort {
+    pub name: String,
+    pub center: Vector2D,
+    /// One of `size.x`, `size.y` is zero; the port normal is along that axis.
+    pub size: Vector2D,
+    #[serde(default = "default_band")]
+    pub eig_band: usize,
+    #[serde(default = "default_parity")]
+    pub eig_parity: String,
+    /// +1 when the device lies on the positive side of the port, −1 otherwise.
+    #[serde(default = "into_positive", rename = "directionSign", alias = "direction_sign")]
+    pub direction_sign: f64,
+}
+
+/// Mode profile and DFT fields of one port over all frequencies.
+pub struct PortOverlap {
+    /// α⁺ (along +normal) and α⁻ per frequency, normalised so |α|² is the
+    /// power carried by the mode.
+    pub forward: Vec<Complex64>,
+    pub backward: Vec<Complex64>,
+    pub n_eff: Vec<f64>,
+}
+
+impl PortOverlap {
+    pub fn incoming(&self, port: &ModePort) -> &[Complex64] {
+        if port.direction_sign >= 0.0 { &self.forward } else { &self.backward }
+    }
+
+    pub fn outgoing(&self, port: &ModePort) -> &[Complex64] {
+        if port.direction_sign >= 0.0 { &self.backward } else { &self.forward }
+    }
+}
+
+/// Tangential (E₁, E₂, H₁, H₂) of a forward mode in the monitor frame
+/// (n, t₁, t₂), normalised to unit flux ∫(E* × H)·n dA = 1. For a normal along
+/// x the frame is (x, y, z); along y it is (y, z, x).
+pub fn mode_tangential_fields(
+    mode: &CrossSectionMode,
+    epsilon: &[f64],
+    normal_is_x: bool,
+    spacing: f64,
+) -> [Vec<Complex64>; 4] {
+    let omega = 2.0 * PI * mode.frequency;
+    // |transverse partner| / out-of-plane component: β/ω for Hₜ of an Ez mode,
+    // β/(ωε) for Eₜ of an Hz mode
+    let ratio: Vec<f64> = match mode.polarization {
+        FdfdPolarization::Ez => vec![mode.beta / omega; epsilon.len()],
+        FdfdPolarization::Hz => epsilon.iter().map(|e| mode.beta / (omega * e)).collect(),
+    };
+    let flux: f64 = mode.profile.iter().zip(&ratio).map(|(v, r)| r * v * v).sum::<f64>() * spacing;
+    let scale = 1.0 / flux.sqrt();
+    let out_of_plane: Vec<Complex64> = mode.profile.iter().map(|v| Complex64::new(scale * v, 0.0)).collect();
+    let partner = |sign: f64| -> Vec<Complex64> {
+        mode.profile.iter().zip(&ratio).map(|(v, r)| Complex64::new(sign * scale * r * v, 0.0)).collect()
+    };
+    let zero = vec![Complex64::new(0.0, 0.0); mode.profile.len()];
+    match (mode.polarization, normal_is_x) {
+        // Hy = −(β/ω) Ez, Hx = (β/ω) Ez
+        (FdfdPolarization::Ez, true) => [zero.clone(), out_of_plane, partner(-1.0), zero],
+        (FdfdPolarization::Ez, false) => [out_of_plane, zero.clone(), zero, partner(1.0)],
+        // Ey = β/(ωε) Hz, Ex = −β/(ωε) Hz
+        (FdfdPolarization::Hz, true) => [partner(1.0), zero.clone(), zero, out_of_plane],
+        (FdfdPolarization::Hz, false) => [zero.clone(), partner(-1.0), out_of_plane, zero],
+    }
+}
+
+/// Meep's `get_eigenmode_coefficients`: with the mode normalised to unit flux,
+/// α± = (∫ E × Hₘ* · n dA ± ∫ Eₘ* × H · n dA) / 2.
+pub fn mode_coefficients(
+    port: &ModePort,
+    grid: &YeeGrid2D,
+    epsilon: &[f64],
+    frequencies: &[f64],
+    fields: &FluxFieldData,
+) -> Result<PortOverlap, String> {
+    let (_, line, _) = sample_source_line(grid, epsilon, port.center, port.size, Vector2D::new(0.0, 0.0))?;
+    let normal_is_x = port.size.x == 0.0;
+    let parity = EigenParity::parse(&port.eig_parity)?;
+    let points = line.len();
+    let component = |c: &ComplexArray| -> Result<Vec<Complex64>, String> {
+        let values = c.values();
+        match values.len() {
+            0 => Ok(vec![Complex64::new(0.0, 0.0); points * frequencies.len()]),
+            n if n == points * frequencies.len() => Ok(values),
+            n => Err(format!(
+                "port {}: DFT fields hold {} values, expected {} frequencies × {} points",
+                port.name,
+                n,
+                frequencies.len(),
+                points
+            )),
+        }
+    };
+    let [e1, e2, h1, h2] = [&fields.e1, &fields.e2, &fields.h1, &fields.h2].map(component);
+    let (e1, e2, h1, h2) = (e1?, e2?, h1?, h2?);
+
+    let mut overlap = PortOverlap { forward: Vec::new(), backward: Vec::new(), n_eff: Vec::new() };
+    for (f, &frequency) in frequencies.iter().enumerate() {
+        let mode = find_eigenmode(&line, grid.dx(), ModeSpec::Frequency(frequency), port.eig_band, parity)
+            .map_err(|e| format!("port {} at f = {}: {}", port.name, frequency, e))?;
+        let [m1, m2, n1, n2] = mode_tangential_fields(&mode, &line, normal_is_x, grid.dx());
+        let range = f * points..(f + 1) * points;
+        let (mut a, mut b) = (Complex64::new(0.0, 0.0), Complex64::new(0.0, 0.0));
+        for (k, i) in range.enumerate() {
+            a += m1[k].conj() * h2[i] - m2[k].conj() * h1[i];
+            b += e1[i] * n2[k].conj() - e2[i] * n1[k].conj();
+        }
+        a *= grid.dx();
+        b *= grid.dx();
+        overlap.forward.push(0.5 * (b + a));
+        overlap.backward.push(0.5 * (b - a));
+        overlap.n_eff.push(mode.n_eff);
+    }
+    Ok(overlap)
+}
+
+/// DFT fields recorded at every port in the run where `source_port` was excited.
+#[derive(Deserialize)]
+pub struct PortExcitation {
+    pub source_port: usize,
+    pub fields: Vec<FluxFieldData>,
+}
+
+fn default_tolerance() -> f64 {
+    0.02
+}
+
+#[derive(Deserialize)]
+pub struct SParameterConfig {
+    pub grid: YeeGrid2D,
+    /// Rasterised permittivity of the cell, used to solve each port's modes.
+    pub epsilon: Vec<f64>,
+    pub frequencies: Vec<f64>,
+    pub ports: Vec<ModePort>,
+    pub excitations: Vec<PortExcitation>,
+    /// Converts frequencies to Hz for the Touchstone file, which is only
+    /// written when the units are known.
+    #[serde(default)]
+    pub units: Option<UnitSystem>,
+    /// Allowed excess of Σᵢ|Sᵢⱼ|² over one.
+    #[serde(default = "default_tolerance")]
+    pub tolerance: f64,
+}
+
+#[derive(Serialize)]
+pub struct PortCoefficients {
+    pub source_port: usize,
+    pub port: String,
+    pub forward_real: Vec<f64>,
+    pub forward_imag: Vec<f64>,
+    pub backward_real: Vec<f64>,
+    pub backward_imag: Vec<f64>,
+    pub n_eff: Vec<f64>,
+}
+
+#[derive(Serialize)]
+pub struct SParameterResult {
+    pub ports: Vec<String>,
+    pub frequencies: Vec<f64>,
+    /// Sᵢⱼ at entry `(f * N + i) * N + j`; columns without an excitation are zero.
+    pub s_real: Vec<f64>,
+    pub s_imag: Vec<f64>,
+    /// Σᵢ |Sᵢⱼ|² at entry `f * N + j`: the fraction of power leaving in the
+    /// monitored modes when port j is driven.
+    pub column_power: Vec<f64>,
+    pub coefficients: Vec<PortCoefficients>,
+    /// Touchstone export in Hz; `None` without `units`.
+    pub touchstone: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+fn split(values: &[Complex64]) -> (Vec<f64>, Vec<f64>) {
+    (values.iter().map(|c| c.re).collect(), values.iter().map(|c| c.im).collect())
+}
+
+pub fn extract_s_parameters_internal(config: &SParameterConfig) -> Result<SParameterResult, String> {
+    let n = config.ports.len();
+    let nf = config.frequencies.len();
+    if n == 0 || nf == 0 {
+        return Err("at least one port and one frequency are required".into());
+    }
+    let mut matrices = vec![vec![Complex64::new(0.0, 0.0); n * n]; nf];
+    let mut coefficients = Vec::new();
+    let mut warnings = Vec::new();
+    let mut driven = vec![false; n];
+
+    for excitation in &config.excitations {
+        let j = excitation.source_port;
+        if j >= n || excitation.fields.len() != n {
+            return Err(format!("each excitation needs a source port below {} and fields at all {} ports", n, n));
+        }
+        if driven[j] {
+            warnings.push(format!("port {} is excited twice; the last run is used", config.ports[j].name));
+        }
+        driven[j] = true;
+        let overlaps: Vec<PortOverlap> = config
+            .ports
+            .iter()
+            .zip(&excitation.fields)
+            .map(|(port, fields)| mode_coefficients(port, &config.grid, &config.epsilon, &config.frequencies, fields))
+            .collect::<Result<_, _>>()?;
+
+        let incident = overlaps[j].incoming(&config.ports[j]);
+        if incident.iter().any(|a| a.norm() == 0.0) {
+            return Err(format!("no incoming mode at port {}", config.ports[j].name));
+        }
+        for (i, (port, overlap)) in config.ports.iter().zip(&overlaps).enumerate() {
+            for (f, matrix) in matrices.iter_mut().enumerate() {
+                matrix[i * n + j] = overlap.outgoing(port)[f] / incident[f];
+            }
+            let (forward_real, forward_imag) = split(&overlap.forward);
+            let (backward_real, backward_imag) = split(&overlap.backward);
+            coefficients.push(PortCoefficients {
+                source_port: j,
+                port: port.name.clone(),
+                forward_real,
+                forward_imag,
+                backward_real,
+                backward_imag,
+                n_eff: overlap.n_eff.clone(),
+            });
+        }
+    }
+    for (port, _) in config.ports.iter().zip(&driven).filter(|(_, &d)| !d) {
+        warnings.push(format!("port {} was never excited; its S-matrix column is zero", port.name));
+    }
+
+    let column_power: Vec<f64> = matrices
+        .iter()
+        .flat_map(|m| (0..n).map(move |j| (0..n).map(|i| m[i * n + j].norm_sqr()).sum::<f64>()))
+        .collect();
+    let excess = column_power.iter().fold(0.0f64, |m, &p| m.max(p - 1.0));
+    if excess > config.tolerance {
+        warnings.push(format!(
+            "Σ|Sᵢⱼ|² exceeds one by {:.1}%; check the normalisation run and that the ports see a single mode",
+            100.0 * excess
+        ));
+    }
+
+    let names = config.ports.iter().map(|p| p.name.clone()).collect::<Vec<_>>();
+    // Touchstone frequencies are physical, so c/a values cannot be written as Hz
+    let touchstone = match &config.units {
+        Some(units) => {
+            let comments = vec![
+                format!("frequencies for a = {} {}", units.a, units.unit.symbol()),
+                format!("ports: {}", names.join(", ")),
+            ];
+            let hz = config.frequencies.iter().map(|&f| units.frequency_to_hz(f)).collect();
+            let network = NetworkData::from_matrices(hz, &matrices, n, comments);
+            Some(write_touchstone(&network, &TouchstoneWriteOptions::default())?)
+        }
+        None => {
+            warnings.push("no units given; the Touchstone export needs frequencies in Hz and was skipped".into());
+            None
+        }
+    };
+
+    let flat: Vec<Complex64> = matrices.into_iter().flatten().collect();
+    let (s_real, s_imag) = split(&flat);
+    Ok(SParameterResult {
+        ports: names,
+        frequencies: config.frequencies.clone(),
+        s_real,
+        s_imag,
+        column_power,
+        coefficients,
+        touchstone,
+        warnings,
+    })
+}
+
+/// Extract mode coefficients at each port and assemble the S-matrix with a Touchstone export
+#[wasm_bindgen]
+pub fn extract_s_parameters(config: &JsValue) -> Result<JsValue, JsValue> {
+    let config: SParameterConfig = serde_wasm_bindgen::from_value(config.clone())?;
+    let result = extract_s_parameters_internal(&config).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material_calculations::material_library::{LengthUnit, SPEED_OF_LIGHT};
+    use crate::post_processing::flux_spectra::{flux_from_fields, ComplexArray};
+
+    /// A guide along x, or along y for `along_x == false`.
+    fn waveguide(along_x: bool) -> (YeeGrid2D, Vec<f64>) {
+        let grid = if along_x { YeeGrid2D::new(6.0, 4.0, 10.0) } else { YeeGrid2D::new(4.0, 6.0, 10.0) };
+        let epsilon = (0..grid.pixel_count())
+            .map(|k| {
+                let across = if along_x { grid.y((k / grid.nx()) as f64) } else { grid.x((k % grid.nx()) as f64) };
+                if across.abs() < 0.4 { 12.0 } else { 1.0 }
+            })
+            .collect();
+        (grid, epsilon)
+    }
+
+    fn port(name: &str, x: f64, direction_sign: f64, parity: &str) -> ModePort {
+        ModePort {
+            name: name.into(),
+            center: Vector2D::new(x, 0.0),
+            size: Vector2D::new(0.0, 3.0),
+            eig_band: 1,
+            eig_parity: parity.into(),
+            direction_sign,
+        }
+    }
+
+    /// DFT fields a·(forward mode) + b·(backward mode) on the port line.
+    fn mode_fields(port: &ModePort, frequencies: &[f64], amplitudes: &[(Complex64, Complex64)]) -> FluxFieldData {
+        let normal_is_x = port.size.x == 0.0;
+        let (grid, epsilon) = waveguide(normal_is_x);
+        let (_, line, _) = sample_source_line(&grid, &epsilon, port.center, port.size, Vector2D::new(0.0, 0.0)).unwrap();
+        let parity = EigenParity::parse(&port.eig_parity).unwrap();
+        let mut parts: [Vec<Complex64>; 4] = Default::default();
+        for (&f, &(a, b)) in frequencies.iter().zip(amplitudes) {
+            let mode = find_eigenmode(&line, grid.dx(), ModeSpec::Frequency(f), 1, parity).unwrap();
+            let modal = mode_tangential_fields(&mode, &line, normal_is_x, grid.dx());
+            for (c, (part, values)) in parts.iter_mut().zip(&modal).enumerate() {
+                // the backward mode has the same E and reversed H
+                let sign = if c < 2 { 1.0 } else { -1.0 };
+                part.extend(values.iter().map(|v| a * v + sign * b * v));
+            }
+        }
+        let array = |v: &Vec<Complex64>| ComplexArray { real: v.iter().map(|c| c.re).collect(), imag: v.iter().map(|c| c.im).collect() };
+        FluxFieldData { e1: array(&parts[0]), e2: array(&parts[1]), h1: array(&parts[2]), h2: array(&parts[3]), cell_area: grid.dx() }
+    }
+
+    #[test]
+    fn test_recovers_forward_and_backward_amplitudes() {
+        let frequencies = [0.3, 0.35];
+        let amplitudes = [(Complex64::new(0.8, 0.3), Complex64::new(-0.1, 0.2)), (Complex64::new(0.0, 1.0), Complex64::new(0.05, 0.0))];
+        let y_normal = |parity: &str| ModePort { center: Vector2D::new(0.0, -2.0), size: Vector2D::new(3.0, 0.0), ..port("in", 0.0, 1.0, parity) };
+        for parity in ["ODD_Z", "EVEN_Z"] {
+            for p in [port("in", -2.0, 1.0, parity), y_normal(parity)] {
+                let (grid, epsilon) = waveguide(p.size.x == 0.0);
+                let fields = mode_fields(&p, &frequencies, &amplitudes);
+                let overlap = mode_coefficients(&p, &grid, &epsilon, &frequencies, &fields).unwrap();
+                // the forward mode carries unit power along +normal in the
+                // (n, t₁, t₂) frame, independently of the overlap formula
+                let flux = flux_from_fields(&fields, frequencies.len(), 1.0).unwrap();
+                for (f, &(a, b)) in amplitudes.iter().enumerate() {
+                    assert!((overlap.forward[f] - a).norm() < 1e-9, "{} {:?} {}", parity, p.size, overlap.forward[f]);
+                    assert!((overlap.backward[f] - b).norm() < 1e-9);
+                    assert!((flux[f] - (a.norm_sqr() - b.norm_sqr())).abs() < 1e-9, "{} {:?} {}", parity, p.size, flux[f]);
+                    assert!(overlap.n_eff[f] > 1.0 && overlap.n_eff[f] < 12f64.sqrt());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_two_port_s_matrix_and_touchstone() {
+        let (grid, epsilon) = waveguide(true);
+        let frequencies = vec![0.3, 0.35];
+        let ports = vec![port("in", -2.0, 1.0, "ODD_Z"), port("out", 2.0, -1.0, "ODD_Z")];
+        let t = Complex64::from_polar(0.9, 0.7);
+        let r = Complex64::new(0.1, -0.05);
+        let incident = Complex64::from_polar(2.0, -0.4);
+        let excitation = PortExcitation {
+            source_port: 0,
+            fields: vec![
+                mode_fields(&ports[0], &frequencies, &[(incident, r * incident); 2]),
+                mode_fields(&ports[1], &frequencies, &[(t * incident, Complex64::new(0.0, 0.0)); 2]),
+            ],
+        };
+        let mut config = SParameterConfig {
+            grid,
+            epsilon,
+            frequencies,
+            ports,
+            excitations: vec![excitation],
+            units: Some(UnitSystem { a: 1.0, unit: LengthUnit::Um }),
+            tolerance: default_tolerance(),
+        };
+        let result = extract_s_parameters_internal(&config).unwrap();
+        for f in 0..2 {
+            let s = |i: usize, j: usize| Complex64::new(result.s_real[(f * 2 + i) * 2 + j], result.s_imag[(f * 2 + i) * 2 + j]);
+            assert!((s(0, 0) - r).norm() < 1e-9);
+            assert!((s(1, 0) - t).norm() < 1e-9);
+            assert!((result.column_power[f * 2] - 0.8225).abs() < 1e-9);
+        }
+        // the unexcited second port is reported
+        assert_eq!(result.warnings.len(), 1);
+        let touchstone = result.touchstone.unwrap();
+        let lines: Vec<&str> = touchstone.lines().filter(|l| !l.starts_with('!')).collect();
+        assert_eq!(lines[0], "# HZ S RI R 50");
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1].split_whitespace().count(), 9);
+        // 0.3 c/µm is 89.9 THz
+        let hz: f64 = lines[1].split_whitespace().next().unwrap().parse().unwrap();
+        assert!((hz / (0.3 * SPEED_OF_LIGHT / 1e-6) - 1.0).abs() < 1e-6, "{}", hz);
+
+        // without units the frequencies are c/a, which Touchstone cannot hold
+        config.units = None;
+        let result = extract_s_parameters_internal(&config).unwrap();
+        assert!(result.touchstone.is_none());
+        assert!(result.warnings.iter().any(|w| w.contains("Touchstone")));
+    }
+}
@@ -0,0 +1,481 @@
+use num_complex::Complex64;
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+use crate::lattice_calculations::matrix_calculations::Vector3D;
+use crate::post_processing::flux_spectra::{ComplexArray, FluxFieldData};
+
+type CVec3 = [Complex64; 3];
+
+fn zero3() -> CVec3 {
+    [Complex64::new(0.0, 0.0); 3]
+}
+
+fn cross(a: [f64; 3], b: &CVec3) -> CVec3 {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+fn dot(a: [f64; 3], b: &CVec3) -> Complex64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn unit_weight() -> f64 {
+    1.0
+}
+
+/// A `Near2FarRegion`: a line (2D) or plane (3D) with one zero size, whose
+/// normal points along +axis times the sign of `weight · directionSign`.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Near2FarSurface {
+    pub center: Vector3D,
+    pub size: Vector3D,
+    #[serde(default = "unit_weight")]
+    pub weight: f64,
+    #[serde(default = "unit_weight", rename = "directionSign", alias = "direction_sign")]
+    pub direction_sign: f64,
+    /// Points along t₁ and t₂ in 3D, with t₁ fastest; in 2D the point count
+    /// follows from the field length.
+    #[serde(default)]
+    pub shape: Option<[usize; 2]>,
+    /// Tangential DFT fields in the (n, t₁, t₂) frame of `FluxFieldData`,
+    /// sampled at the midpoints of a uniform grid over `size`; each point is
+    /// weighted by `fields.cell_area`.
+    pub fields: FluxFieldData,
+}
+
+/// Sampled equivalent currents J = n̂ × H and M = −n̂ × E of one surface.
+struct SurfaceCurrents {
+    positions: Vec<[f64; 3]>,
+    /// Integration weight of each point, and the grid spacing it should match.
+    area: f64,
+    spacing: f64,
+    /// Per frequency, per point.
+    j: Vec<Vec<CVec3>>,
+    m: Vec<Vec<CVec3>>,
+    /// Re ∫ (E* × H)·n̂ dA per frequency.
+    flux: Vec<f64>,
+}
+
+impl Near2FarSurface {
+    fn currents(&self, dimensions: usize, frequencies: usize) -> Result<SurfaceCurrents, String> {
+        let size = self.size.to_array();
+        let center = self.center.to_array();
+        let normal = (0..dimensions)
+            .find(|&d| size[d] == 0.0)
+            .filter(|&d| (0..dimensions).all(|e| e == d || size[e] > 0.0))
+            .ok_or("a near-to-far surface needs exactly one zero size")?;
+        let (t1, t2) = ((normal + 1) % 3, (normal + 2) % 3);
+
+        let components = [&self.fields.e1, &self.fields.e2, &self.fields.h1, &self.fields.h2].map(ComplexArray::values);
+        let length = components.iter().map(Vec::len).max().unwrap_or(0);
+        if length == 0 || length % frequencies != 0 {
+            return Err(format!("surface fields must hold {} frequencies × points values", frequencies));
+        }
+        let points = length / frequencies;
+        // (count, axis) pairs spanning the surface
+        let axes: Vec<(usize, usize)> = if dimensions == 2 {
+            vec![(points, if normal == 0 { t1 } else { t2 })]
+        } else {
+            let [n1, n2] = self.shape.ok_or("3D surfaces need a shape [n1, n2]")?;
+            if n1 * n2 != points {
+                return Err(format!("shape {}×{} does not match {} points", n1, n2, points));
+            }
+            vec![(n1, t1), (n2, t2)]
+        };
+        let spacing: f64 = axes.iter().map(|&(n, axis)| size[axis] / n as f64).product();
+        let area = self.fields.cell_area;
+        let positions: Vec<[f64; 3]> = (0..points)
+            .map(|p| {
+                let mut r = center;
+                let mut index = p;
+                for &(n, axis) in &axes {
+                    let k = index % n;
+                    index /= n;
+                    r[axis] += -0.5 * size[axis] + (k as f64 + 0.5) * size[axis] / n as f64;
+                }
+                r
+            })
+            .collect();
+
+        let scale = self.weight * self.direction_sign;
+        let mut n_hat = [0.0; 3];
+        n_hat[normal] = scale.signum();
+        let value = |c: &Vec<Complex64>, i: usize| c.get(i).copied().unwrap_or_default();
+        let mut currents = SurfaceCurrents { positions, area, spacing, j: Vec::new(), m: Vec::new(), flux: Vec::new() };
+        for f in 0..frequencies {
+            let (mut j, mut m) = (Vec::with_capacity(points), Vec::with_capacity(points));
+            let mut flux = 0.0;
+            for p in 0..points {
+                let i = f * points + p;
+                let (mut e, mut h) = (zero3(), zero3());
+                e[t1] = value(&components[0], i);
+                e[t2] = value(&components[1], i);
+                h[t1] = value(&components[2], i);
+                h[t2] = value(&components[3], i);
+                flux += (e[t1].conj() * h[t2] - e[t2].conj() * h[t1]).re;
+                j.push(cross(n_hat, &h).map(|c| c * scale.abs()));
+                m.push(cross(n_hat, &e).map(|c| -c * scale.abs()));
+            }
+            currents.j.push(j);
+            currents.m.push(m);
+            currents.flux.push(scale * area * flux);
+        }
+        Ok(currents)
+    }
+}
+
+fn default_dimensions() -> usize {
+    2
+}
+
+fn unit_medium() -> f64 {
+    1.0
+}
+
+fn default_points() -> usize {
+    360
+}
+
+fn default_theta_points() -> usize {
+    90
+}
+
+fn default_phi_points() -> usize {
+    180
+}
+
+#[derive(Deserialize)]
+pub struct Near2FarConfig {
+    #[serde(default = "default_dimensions")]
+    pub dimensions: usize,
+    pub frequencies: Vec<f64>,
+    /// Homogeneous, lossless background the surfaces radiate into.
+    #[serde(default = "unit_medium")]
+    pub epsilon: f64,
+    #[serde(default = "unit_medium")]
+    pub mu: f64,
+    pub surfaces: Vec<Near2FarSurface>,
+    /// Angles φ = 2πk/points on the far-field circle (2D).
+    #[serde(default = "default_points")]
+    pub points: usize,
+    /// Midpoint θ samples and φ samples on the far-field sphere (3D).
+    #[serde(default = "default_theta_points")]
+    pub theta_points: usize,
+    #[serde(default = "default_phi_points")]
+    pub phi_points: usize,
+}
+
+#[derive(Serialize)]
+pub struct FarFieldPattern {
+    pub frequency: f64,
+    /// Far-field E with the spherical (1/r) or cylindrical (1/√r) spreading
+    /// and the e^{ikr} phase removed, along the two transverse unit vectors:
+    /// (φ̂, ẑ) in 2D and (θ̂, φ̂) in 3D.
+    pub e1_real: Vec<f64>,
+    pub e1_imag: Vec<f64>,
+    pub e2_real: Vec<f64>,
+    pub e2_imag: Vec<f64>,
+    /// Radiated power per radian (2D) or steradian (3D), Re(E* × H) as in Meep's flux.
+    pub power: Vec<f64>,
+    pub total_power: f64,
+    /// Net flux out of the surfaces, for comparison with `total_power`.
+    pub surface_flux: f64,
+    pub directivity: Vec<f64>,
+    pub max_directivity: f64,
+    pub max_direction: usize,
+}
+
+#[derive(Serialize)]
+pub struct Near2FarResult {
+    /// φ in degrees (2D), or θ per sample with φ in `phi` (3D), sample `t * phi_points + p`.
+    pub theta: Vec<f64>,
+    pub phi: Vec<f64>,
+    pub patterns: Vec<FarFieldPattern>,
+    pub warnings: Vec<String>,
+}
+
+/// Observation directions with their transverse unit vectors and the solid
+/// angle (or angle) each sample represents.
+struct Direction {
+    r: [f64; 3],
+    t1: [f64; 3],
+    t2: [f64; 3],
+    measure: f64,
+}
+
+fn directions(config: &Near2FarConfig) -> (Vec<Direction>, Vec<f64>, Vec<f64>) {
+    if config.dimensions == 2 {
+        let step = 2.0 * PI / config.points as f64;
+        let dirs = (0..config.points)
+            .map(|k| {
+                let (s, c) = (k as f64 * step).sin_cos();
+                Direction { r: [c, s, 0.0], t1: [-s, c, 0.0], t2: [0.0, 0.0, 1.0], measure: step }
+            })
+            .collect();
+        let phi = (0..config.points).map(|k| k as f64 * 360.0 / config.points as f64).collect();
+        return (dirs, Vec::new(), phi);
+    }
+    let (dt, dp) = (PI / config.theta_points as f64, 2.0 * PI / config.phi_points as f64);
+    let mut dirs = Vec::new();
+    let (mut theta, mut phi) = (Vec::new(), Vec::new());
+    for t in 0..config.theta_points {
+        let th = (t as f64 + 0.5) * dt;
+        let (st, ct) = th.sin_cos();
+        for p in 0..config.phi_points {
+            let (sp, cp) = (p as f64 * dp).sin_cos();
+            dirs.push(Direction {
+                r: [st * cp, st * sp, ct],
+                t1: [ct * cp, ct * sp, -st],
+                t2: [-sp, cp, 0.0],
+                measure: st * dt * dp,
+            });
+            theta.push(th.to_degrees());
+            phi.push((p as f64 * dp).to_degrees());
+        }
+    }
+    (dirs, theta, phi)
+}
+
+/// Far-field projection of the equivalent surface currents. With the
+/// radiation vectors N = ∫ J e^{−ik r̂·r'} dA and L = ∫ M e^{−ik r̂·r'} dA,
+/// E = iωμ g [N − r̂(r̂·N) − r̂ × L / η], where g is the asymptotic Green's
+/// function: e^{ikr}/(4πr) in 3D and (i/4)√(2/(πkρ)) e^{i(kρ − π/4)} in 2D.
+pub fn near_to_far_internal(config: &Near2FarConfig) -> Result<Near2FarResult, String> {
+    if config.dimensions != 2 && config.dimensions != 3 {
+        return Err("dimensions must be 2 or 3".into());
+    }
+    if config.frequencies.is_empty() || config.frequencies.iter().any(|&f| f <= 0.0) {
+        return Err("frequencies must be positive".into());
+    }
+    if config.epsilon <= 0.0 || config.mu <= 0.0 {
+        return Err("the background needs positive epsilon and mu".into());
+    }
+    if config.surfaces.is_empty() {
+        return Err("at least one surface is required".into());
+    }
+    let nf = config.frequencies.len();
+    let surfaces: Vec<SurfaceCurrents> =
+        config.surfaces.iter().map(|s| s.currents(config.dimensions, nf)).collect::<Result<_, _>>()?;
+    let (dirs, theta, phi) = directions(config);
+    let index = (config.epsilon * config.mu).sqrt();
+    let eta = (config.mu / config.epsilon).sqrt();
+    let solid_angle = if config.dimensions == 2 { 2.0 * PI } else { 4.0 * PI };
+
+    let mut warnings: Vec<String> = surfaces
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| (s.area - s.spacing).abs() > 1e-3 * s.spacing)
+        .map(|(i, s)| {
+            format!(
+                "surface {}: cell_area {:.4e} differs from the point spacing {:.4e}; \
+                 the points are placed uniformly over the surface size",
+                i, s.area, s.spacing
+            )
+        })
+        .collect();
+    let mut patterns = Vec::with_capacity(nf);
+    for (f, &frequency) in config.frequencies.iter().enumerate() {
+        let omega = 2.0 * PI * frequency;
+        let k = omega * index;
+        let g = if config.dimensions == 2 {
+            Complex64::new(0.0, 0.25) * (2.0 / (PI * k)).sqrt() * Complex64::from_polar(1.0, -0.25 * PI)
+        } else {
+            Complex64::new(1.0 / (4.0 * PI), 0.0)
+        };
+        let prefactor = Complex64::new(0.0, omega * config.mu) * g;
+        let mut pattern = FarFieldPattern {
+            frequency,
+            e1_real: Vec::with_capacity(dirs.len()),
+            e1_imag: Vec::with_capacity(dirs.len()),
+            e2_real: Vec::with_capacity(dirs.len()),
+            e2_imag: Vec::with_capacity(dirs.len()),
+            power: Vec::with_capacity(dirs.len()),
+            total_power: 0.0,
+            surface_flux: surfaces.iter().map(|s| s.flux[f]).sum(),
+            directivity: Vec::new(),
+            max_directivity: 0.0,
+            max_direction: 0,
+        };
+        for dir in &dirs {
+            let (mut n, mut l) = (zero3(), zero3());
+            for surface in &surfaces {
+                for ((position, j), m) in surface.positions.iter().zip(&surface.j[f]).zip(&surface.m[f]) {
+                    let r_dot: f64 = (0..3).map(|d| dir.r[d] * position[d]).sum();
+                    let phase = Complex64::from_polar(surface.area, -k * r_dot);
+                    for d in 0..3 {
+                        n[d] += j[d] * phase;
+                        l[d] += m[d] * phase;
+                    }
+                }
+            }
+            let r_cross_l = cross(dir.r, &l);
+            let e1 = prefactor * (dot(dir.t1, &n) - dot(dir.t1, &r_cross_l) / eta);
+            let e2 = prefactor * (dot(dir.t2, &n) - dot(dir.t2, &r_cross_l) / eta);
+            let power = (e1.norm_sqr() + e2.norm_sqr()) / eta;
+            pattern.e1_real.push(e1.re);
+            pattern.e1_imag.push(e1.im);
+            pattern.e2_real.push(e2.re);
+            pattern.e2_imag.push(e2.im);
+            pattern.power.push(power);
+            pattern.total_power += power * dir.measure;
+        }
+        if pattern.total_power > 0.0 {
+            pattern.directivity = pattern.power.iter().map(|p| solid_angle * p / pattern.total_power).collect();
+            let (best, &max) =
+                pattern.directivity.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1)).unwrap_or((0, &0.0));
+            pattern.max_directivity = max;
+            pattern.max_direction = best;
+        } else {
+            pattern.directivity = vec![0.0; dirs.len()];
+        }
+        let mismatch = (pattern.total_power - pattern.surface_flux).abs() / pattern.surface_flux.abs().max(1e-300);
+        if pattern.surface_flux > 0.0 && mismatch > 0.1 {
+            warnings.push(format!(
+                "at f = {}: far-field power {:.3e} differs from the surface flux {:.3e} by {:.0}%; \
+                 the surfaces may not enclose the sources or the angular sampling is too coarse",
+                frequency,
+                pattern.total_power,
+                pattern.surface_flux,
+                100.0 * mismatch
+            ));
+        }
+        patterns.push(pattern);
+    }
+    Ok(Near2FarResult { theta, phi, patterns, warnings })
+}
+
+/// Project surface DFT fields to far-field patterns, radiated power and directivity
+#[wasm_bindgen]
+pub fn project_near_to_far(config: &JsValue) -> Result<JsValue, JsValue> {
+    let config: Near2FarConfig = serde_wasm_bindgen::from_value(config.clone())?;
+    let result = near_to_far_internal(&config).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn array(values: Vec<Complex64>) -> ComplexArray {
+        ComplexArray { real: values.iter().map(|c| c.re).collect(), imag: values.iter().map(|c| c.im).collect() }
+    }
+
+    fn surface(center: Vector3D, size: Vector3D, weight: f64, fields: [Vec<Complex64>; 4]) -> Near2FarSurface {
+        let points = fields.iter().map(Vec::len).max().unwrap_or(1);
+        let cell_area = size.to_array().iter().filter(|&&l| l > 0.0).product::<f64>() / points as f64;
+        let [e1, e2, h1, h2] = fields.map(array);
+        Near2FarSurface {
+            center,
+            size,
+            weight,
+            direction_sign: 1.0,
+            shape: None,
+            fields: FluxFieldData { e1, e2, h1, h2, cell_area },
+        }
+    }
+
+    fn config(dimensions: usize, surfaces: Vec<Near2FarSurface>) -> Near2FarConfig {
+        Near2FarConfig {
+            dimensions,
+            frequencies: vec![1.0],
+            epsilon: 1.0,
+            mu: 1.0,
+            surfaces,
+            points: default_points(),
+            theta_points: default_theta_points(),
+            phi_points: default_phi_points(),
+        }
+    }
+
+    #[test]
+    fn test_uniform_aperture_directivity() {
+        // 2D: a slit of width W lit by Ez = 1, Hy = −1 radiates
+        // |Ez|² ∝ ((1 + cos φ)/2)² sinc²(kW sin φ / 2), with directivity kW
+        let (width, points) = (10.0, 200);
+        let one = vec![Complex64::new(1.0, 0.0); points];
+        let zero = vec![Complex64::new(0.0, 0.0); points];
+        let slit = surface(
+            Vector3D::new(0.0, 0.0, 0.0),
+            Vector3D::new(0.0, width, 0.0),
+            1.0,
+            [zero.clone(), one.clone(), one.iter().map(|c| -c).collect(), zero.clone()],
+        );
+        let mut result = near_to_far_internal(&config(2, vec![slit.clone()])).unwrap();
+        assert!(result.warnings.is_empty());
+        let pattern = &result.patterns[0];
+        let k = 2.0 * PI;
+        assert_eq!(pattern.max_direction, 0);
+        assert!((pattern.max_directivity / (k * width) - 1.0).abs() < 0.03, "{}", pattern.max_directivity);
+        assert!((pattern.surface_flux - width).abs() < 1e-9);
+        assert!((pattern.total_power / width - 1.0).abs() < 0.03);
+        let phi = 3f64.to_radians();
+        let x = 0.5 * k * width * phi.sin();
+        let expected = (0.25 * (1.0 + phi.cos()).powi(2)) * (x.sin() / x).powi(2);
+        assert!((pattern.power[3] / pattern.power[0] - expected).abs() < 1e-3);
+        assert!(pattern.e1_real.iter().chain(&pattern.e1_imag).all(|v| v.abs() < 1e-9));
+
+        // the samples carry the supplied cell_area, with a warning when it
+        // disagrees with their spacing
+        let mut doubled = slit;
+        doubled.fields.cell_area *= 2.0;
+        let reference = result.patterns.remove(0);
+        result = near_to_far_internal(&config(2, vec![doubled])).unwrap();
+        assert!((result.patterns[0].surface_flux / reference.surface_flux - 2.0).abs() < 1e-12);
+        assert!((result.patterns[0].total_power / reference.total_power - 4.0).abs() < 1e-9);
+        assert!(result.warnings[0].contains("cell_area"));
+
+        // 3D: a square aperture of area A has directivity 4πA/λ²
+        let (side, n) = (4.0, 40);
+        let one = vec![Complex64::new(1.0, 0.0); n * n];
+        let zero = vec![Complex64::new(0.0, 0.0); n * n];
+        let mut aperture = surface(
+            Vector3D::new(0.0, 0.0, 0.0),
+            Vector3D::new(side, side, 0.0),
+            1.0,
+            [one.clone(), zero.clone(), zero, one],
+        );
+        aperture.shape = Some([n, n]);
+        let result = near_to_far_internal(&config(3, vec![aperture])).unwrap();
+        let pattern = &result.patterns[0];
+        assert!(result.theta[pattern.max_direction] < 1.5);
+        assert!((pattern.max_directivity / (4.0 * PI * side * side) - 1.0).abs() < 0.05, "{}", pattern.max_directivity);
+        assert!((pattern.total_power / (side * side) - 1.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_closed_contour_without_sources_radiates_nothing() {
+        // a plane wave crossing a closed square: the equivalent currents
+        // cancel outside, and the net flux vanishes
+        let (half, n) = (1.0, 80);
+        let k = 2.0 * PI;
+        let coordinates: Vec<f64> = (0..n).map(|i| -half + (i as f64 + 0.5) * 2.0 * half / n as f64).collect();
+        let wave = |x: f64| Complex64::from_polar(1.0, k * x);
+        let zero = vec![Complex64::new(0.0, 0.0); n];
+        let mut surfaces = Vec::new();
+        for sign in [-1.0, 1.0] {
+            // x faces: (n, t₁, t₂) = (x, y, z), Ez = e₂, Hy = h₁
+            let ez = vec![wave(sign * half); n];
+            let hy = ez.iter().map(|c| -c).collect();
+            surfaces.push(surface(
+                Vector3D::new(sign * half, 0.0, 0.0),
+                Vector3D::new(0.0, 2.0 * half, 0.0),
+                sign,
+                [zero.clone(), ez, hy, zero.clone()],
+            ));
+            // y faces: (n, t₁, t₂) = (y, z, x), Ez = e₁, and H has no x or z part
+            let ez = coordinates.iter().map(|&x| wave(x)).collect();
+            surfaces.push(surface(
+                Vector3D::new(0.0, sign * half, 0.0),
+                Vector3D::new(2.0 * half, 0.0, 0.0),
+                sign,
+                [ez, zero.clone(), zero.clone(), zero.clone()],
+            ));
+        }
+        let result = near_to_far_internal(&config(2, surfaces)).unwrap();
+        let pattern = &result.patterns[0];
+        assert!(pattern.surface_flux.abs() < 1e-9);
+        assert!(pattern.total_power < 1e-3 * 2.0 * half, "{}", pattern.total_power);
+        assert!(result.warnings.is_empty());
+    }
+}
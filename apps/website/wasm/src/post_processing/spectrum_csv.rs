@@ -0,0 +1,335 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::material_calculations::material_library::{UnitSystem, EV_UM};
+
+/// Physical quantity on the spectral axis of a measured spectrum.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpectralAxis {
+    Wavelength,
+    Frequency,
+    Wavenumber,
+    Energy,
+    /// Already in Meep units of c/a.
+    Meep,
+}
+
+/// Axis quantity with the size of its unit: metres for wavelengths, Hz for
+/// frequencies, m⁻¹ for wavenumbers and eV for energies.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct AxisUnit {
+    pub axis: SpectralAxis,
+    pub scale: f64,
+    pub label: String,
+}
+
+impl AxisUnit {
+    fn new(axis: SpectralAxis, scale: f64, label: &str) -> Self {
+        AxisUnit { axis, scale, label: label.into() }
+    }
+
+    /// Unit from a symbol such as `nm`, `THz`, `cm^-1`, `eV` or `c/a`.
+    pub fn parse(symbol: &str) -> Option<Self> {
+        let s = symbol.trim().replace(['µ', 'μ'], "u").replace(['⁻', '−'], "-").replace('¹', "1");
+        let lower = s.to_lowercase();
+        let wavelength = |scale: f64| Some(AxisUnit::new(SpectralAxis::Wavelength, scale, symbol.trim()));
+        let frequency = |scale: f64| Some(AxisUnit::new(SpectralAxis::Frequency, scale, symbol.trim()));
+        match lower.as_str() {
+            "pm" => wavelength(1e-12),
+            "a" | "å" | "angstrom" | "angstroms" => wavelength(1e-10),
+            "nm" => wavelength(1e-9),
+            "um" | "micron" | "microns" => wavelength(1e-6),
+            // "mm" and "m" are lengths; "MHz" is caught below
+            "mm" => wavelength(1e-3),
+            "m" => wavelength(1.0),
+            "hz" => frequency(1.0),
+            "khz" => frequency(1e3),
+            "mhz" => frequency(1e6),
+            "ghz" => frequency(1e9),
+            "thz" => frequency(1e12),
+            "phz" => frequency(1e15),
+            "cm-1" | "cm^-1" | "1/cm" | "/cm" => Some(AxisUnit::new(SpectralAxis::Wavenumber, 100.0, symbol.trim())),
+            "ev" => Some(AxisUnit::new(SpectralAxis::Energy, 1.0, symbol.trim())),
+            "mev" => Some(AxisUnit::new(SpectralAxis::Energy, 1e-3, symbol.trim())),
+            "c/a" | "meep" => Some(AxisUnit::new(SpectralAxis::Meep, 1.0, symbol.trim())),
+            _ => None,
+        }
+    }
+
+    /// Unit from a column header such as `Wavelength (nm)`, `freq [THz]`,
+    /// `lambda/um` or `Energy_eV`; the quantity alone gives no unit.
+    pub fn from_header(header: &str) -> Option<Self> {
+        let delimited = header
+            .find(['(', '['])
+            .and_then(|open| header[open + 1..].find([')', ']']).map(|close| &header[open + 1..open + 1 + close]));
+        let candidates = delimited
+            .into_iter()
+            .chain(header.rsplit_once(['/', '_', ' ']).map(|(_, unit)| unit))
+            .chain(std::iter::once(header));
+        candidates.filter_map(AxisUnit::parse).next()
+    }
+
+    /// Meep frequency (c/a) of a value on this axis.
+    pub fn to_meep_frequency(&self, value: f64, units: &UnitSystem) -> f64 {
+        match self.axis {
+            SpectralAxis::Wavelength => units.a_in_meters() / (value * self.scale),
+            SpectralAxis::Frequency => units.hz_to_frequency(value * self.scale),
+            SpectralAxis::Wavenumber => value * self.scale * units.a_in_meters(),
+            SpectralAxis::Energy => units.frequency_scale() * value * self.scale / EV_UM,
+            SpectralAxis::Meep => value,
+        }
+    }
+}
+
+/// Guess the unit of a bare column from its typical magnitude.
+fn guess_unit(values: &[f64]) -> Option<AxisUnit> {
+    let mut sorted: Vec<f64> = values.iter().map(|v| v.abs()).collect();
+    sorted.sort_by(f64::total_cmp);
+    let median = *sorted.get(sorted.len() / 2)?;
+    match median {
+        m if (100.0..=20_000.0).contains(&m) => AxisUnit::parse("nm"),
+        m if (0.1..=30.0).contains(&m) => AxisUnit::parse("um"),
+        m if (1e9..1e18).contains(&m) => AxisUnit::parse("Hz"),
+        _ => None,
+    }
+}
+
+fn default_x_column() -> usize {
+    0
+}
+
+#[derive(Deserialize)]
+pub struct CsvSpectrumConfig {
+    #[serde(flatten)]
+    pub units: UnitSystem,
+    /// Unit of the spectral column, overriding the header.
+    #[serde(default)]
+    pub x_unit: Option<String>,
+    #[serde(default = "default_x_column")]
+    pub x_column: usize,
+    /// Simulated frequencies (c/a) to resample the data onto for overlays.
+    #[serde(default)]
+    pub target_frequencies: Vec<f64>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SpectrumColumn {
+    pub name: String,
+    pub values: Vec<f64>,
+}
+
+#[derive(Serialize)]
+pub struct SpectrumTable {
+    pub x_unit: AxisUnit,
+    /// "config", "header" or "guess".
+    pub unit_source: String,
+    /// Spectral column as read, reordered with the rows.
+    pub x: Vec<f64>,
+    /// Meep frequencies (c/a), ascending.
+    pub frequencies: Vec<f64>,
+    pub columns: Vec<SpectrumColumn>,
+    /// Columns linearly interpolated onto `target_frequencies`; NaN outside the data.
+    pub resampled: Vec<SpectrumColumn>,
+    pub skipped_rows: usize,
+    pub warnings: Vec<String>,
+}
+
+fn split_fields(line: &str, delimiter: Option<char>) -> Vec<String> {
+    let fields: Vec<&str> = match delimiter {
+        Some(d) => line.split(d).collect(),
+        None => line.split_whitespace().collect(),
+    };
+    fields.iter().map(|f| f.trim().trim_matches('"').trim().to_string()).collect()
+}
+
+fn parse_number(field: &str, decimal_comma: bool) -> Option<f64> {
+    let field = if decimal_comma { field.replace(',', ".") } else { field.to_string() };
+    field.parse::<f64>().ok().filter(|v| v.is_finite())
+}
+
+/// Linear interpolation of (x, y) with ascending x at `at`.
+fn interpolate(x: &[f64], y: &[f64], at: f64) -> f64 {
+    if x.is_empty() || at < x[0] || at > x[x.len() - 1] {
+        return f64::NAN;
+    }
+    let k = x.partition_point(|&v| v < at);
+    if k == 0 {
+        return y[0];
+    }
+    let t = (at - x[k - 1]) / (x[k] - x[k - 1]);
+    y[k - 1] + t * (y[k] - y[k - 1])
+}
+
+/// Read a delimited spectrum: comment lines (`#`, `%`, `//`) and blank
+/// lines are ignored, the delimiter is tab, semicolon (with decimal commas),
+/// comma or whitespace, and the last non-numeric line before the data is
+/// taken as the header. Rows that do not parse are skipped.
+pub fn parse_spectrum_csv(text: &str, config: &CsvSpectrumConfig) -> Result<SpectrumTable, String> {
+    if config.units.a <= 0.0 {
+        return Err("length scale a must be positive".into());
+    }
+    let lines: Vec<&str> = text
+        .trim_start_matches('\u{feff}')
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#') && !l.starts_with('%') && !l.starts_with("//"))
+        .collect();
+    let sample = lines.iter().rev().find(|l| l.chars().any(|c| c.is_ascii_digit())).ok_or("no numeric data")?;
+    let delimiter = ['\t', ';', ','].into_iter().find(|&d| sample.contains(d));
+    let decimal_comma = delimiter == Some(';') || (delimiter == Some('\t') && sample.contains(','));
+
+    let mut header: Option<Vec<String>> = None;
+    let mut rows: Vec<Vec<f64>> = Vec::new();
+    let mut skipped_rows = 0;
+    for line in &lines {
+        let fields = split_fields(line, delimiter);
+        let numbers: Vec<Option<f64>> = fields.iter().map(|f| parse_number(f, decimal_comma)).collect();
+        if numbers.iter().all(Option::is_some) && numbers.len() > config.x_column {
+            rows.push(numbers.into_iter().flatten().collect());
+        } else if rows.is_empty() {
+            header = Some(fields);
+        } else {
+            skipped_rows += 1;
+        }
+    }
+    let width = rows.iter().map(Vec::len).max().ok_or("no numeric data")?;
+    if width < 2 {
+        return Err("a spectrum needs the spectral column and at least one data column".into());
+    }
+    let before = rows.len();
+    rows.retain(|r| r.len() == width);
+    skipped_rows += before - rows.len();
+
+    let mut warnings = Vec::new();
+    let x_raw: Vec<f64> = rows.iter().map(|r| r[config.x_column]).collect();
+    let header_unit = header.as_ref().and_then(|h| h.get(config.x_column)).and_then(|h| AxisUnit::from_header(h));
+    let (x_unit, unit_source) = match (&config.x_unit, header_unit) {
+        (Some(symbol), _) => (AxisUnit::parse(symbol).ok_or_else(|| format!("unknown unit '{}'", symbol))?, "config"),
+        (None, Some(unit)) => (unit, "header"),
+        (None, None) => {
+            let unit = guess_unit(&x_raw).ok_or("cannot tell the unit of the spectral column; set x_unit")?;
+            warnings.push(format!("no unit in the header; the spectral column was read as {}", unit.label));
+            (unit, "guess")
+        }
+    };
+
+    let names: Vec<String> = (0..width)
+        .filter(|&c| c != config.x_column)
+        .map(|c| match header.as_ref().and_then(|h| h.get(c)).filter(|h| !h.is_empty()) {
+            Some(name) => name.clone(),
+            None => format!("column {}", c + 1),
+        })
+        .collect();
+
+    // ascending frequency
+    let mut order: Vec<usize> = (0..rows.len()).collect();
+    let frequency = |k: usize| x_unit.to_meep_frequency(x_raw[k], &config.units);
+    order.retain(|&k| frequency(k).is_finite() && frequency(k) > 0.0);
+    skipped_rows += rows.len() - order.len();
+    order.sort_by(|&a, &b| frequency(a).total_cmp(&frequency(b)));
+    let frequencies: Vec<f64> = order.iter().map(|&k| frequency(k)).collect();
+    if frequencies.windows(2).any(|w| w[1] == w[0]) {
+        warnings.push("repeated spectral values; interpolation uses the first of each".into());
+    }
+
+    let columns: Vec<SpectrumColumn> = (0..width)
+        .filter(|&c| c != config.x_column)
+        .zip(names)
+        .map(|(c, name)| {
+            // percentages become fractions, matching normalised flux spectra
+            let scale = if name.contains('%') { 0.01 } else { 1.0 };
+            SpectrumColumn { name, values: order.iter().map(|&k| scale * rows[k][c]).collect() }
+        })
+        .collect();
+    let resampled = if config.target_frequencies.is_empty() {
+        Vec::new()
+    } else {
+        columns
+            .iter()
+            .map(|c| SpectrumColumn {
+                name: c.name.clone(),
+                values: config.target_frequencies.iter().map(|&f| interpolate(&frequencies, &c.values, f)).collect(),
+            })
+            .collect()
+    };
+    if skipped_rows > 0 {
+        warnings.push(format!("{} rows could not be read and were skipped", skipped_rows));
+    }
+
+    Ok(SpectrumTable {
+        x_unit,
+        unit_source: unit_source.into(),
+        x: order.iter().map(|&k| x_raw[k]).collect(),
+        frequencies,
+        columns,
+        resampled,
+        skipped_rows,
+        warnings,
+    })
+}
+
+/// Read a measured spectrum from CSV text and convert its axis to Meep frequencies
+#[wasm_bindgen]
+pub fn read_spectrum_csv(text: &str, config: &JsValue) -> Result<JsValue, JsValue> {
+    let config: CsvSpectrumConfig = serde_wasm_bindgen::from_value(config.clone())?;
+    let result = parse_spectrum_csv(text, &config).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material_calculations::material_library::LengthUnit;
+
+    fn config(x_unit: Option<&str>) -> CsvSpectrumConfig {
+        CsvSpectrumConfig {
+            units: UnitSystem { a: 500.0, unit: LengthUnit::Nm },
+            x_unit: x_unit.map(String::from),
+            x_column: 0,
+            target_frequencies: vec![0.4, 0.45, 0.9],
+        }
+    }
+
+    #[test]
+    fn test_reads_wavelength_table_with_header() {
+        let text = "# spectrometer export\nWavelength (nm),Transmission (%),R\n\
+                    1250,80,0.1\n1000,60,0.3\nn/a,1,2\n1111.1111,70,0.2\n";
+        let table = parse_spectrum_csv(text, &config(None)).unwrap();
+        assert_eq!(table.unit_source, "header");
+        assert_eq!(table.x_unit.axis, SpectralAxis::Wavelength);
+        // f = a / λ with a = 500 nm, sorted ascending
+        assert_eq!(table.x, vec![1250.0, 1111.1111, 1000.0]);
+        assert!((table.frequencies[0] - 0.4).abs() < 1e-12);
+        assert!((table.frequencies[2] - 0.5).abs() < 1e-12);
+        assert_eq!(table.columns[0].name, "Transmission (%)");
+        assert!((table.columns[0].values[0] - 0.8).abs() < 1e-12);
+        assert_eq!(table.skipped_rows, 1);
+        assert!((table.resampled[1].values[0] - 0.1).abs() < 1e-12);
+        assert!((table.resampled[0].values[1] - 0.7).abs() < 1e-5);
+        assert!(table.resampled[0].values[2].is_nan());
+    }
+
+    #[test]
+    fn test_detects_units_and_european_decimals() {
+        let units = UnitSystem { a: 1.0, unit: LengthUnit::Um };
+        for (symbol, value) in [("THz", 299.792458), ("cm^-1", 1e4), ("eV", EV_UM), ("µm", 1.0), ("c/a", 1.0)] {
+            let unit = AxisUnit::parse(symbol).unwrap();
+            assert!((unit.to_meep_frequency(value, &units) - 1.0).abs() < 1e-9, "{}", symbol);
+        }
+        assert_eq!(AxisUnit::from_header("freq [GHz]").unwrap().scale, 1e9);
+        assert_eq!(AxisUnit::from_header("lambda/um").unwrap().axis, SpectralAxis::Wavelength);
+        assert_eq!(AxisUnit::from_header("Energy_eV").unwrap().axis, SpectralAxis::Energy);
+        assert!(AxisUnit::from_header("Signal").is_none());
+
+        // headerless, semicolon-separated with decimal commas: µm guessed
+        let text = "1,5;0,25\n1,25;0,5\n";
+        let table = parse_spectrum_csv(text, &config(None)).unwrap();
+        assert_eq!(table.unit_source, "guess");
+        assert!((table.frequencies[0] - 0.5 / 1.5).abs() < 1e-12);
+        assert_eq!(table.columns[0].name, "column 2");
+        assert_eq!(table.warnings.len(), 1);
+        let explicit = parse_spectrum_csv(text, &config(Some("PHz"))).unwrap();
+        assert_eq!(explicit.unit_source, "config");
+    }
+}
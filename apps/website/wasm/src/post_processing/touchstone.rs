@@ -0,0 +1,510 @@
+use num_complex::Complex64;
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::field_solvers::fdfd::complex_from_parts;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TouchstoneFormat {
+    #[serde(rename = "RI")]
+    RealImaginary,
+    #[serde(rename = "MA")]
+    MagnitudeAngle,
+    #[serde(rename = "DB")]
+    DecibelAngle,
+}
+
+impl TouchstoneFormat {
+    fn parse(token: &str) -> Option<Self> {
+        match token {
+            "RI" => Some(TouchstoneFormat::RealImaginary),
+            "MA" => Some(TouchstoneFormat::MagnitudeAngle),
+            "DB" => Some(TouchstoneFormat::DecibelAngle),
+            _ => None,
+        }
+    }
+
+    fn keyword(&self) -> &'static str {
+        match self {
+            TouchstoneFormat::RealImaginary => "RI",
+            TouchstoneFormat::MagnitudeAngle => "MA",
+            TouchstoneFormat::DecibelAngle => "DB",
+        }
+    }
+
+    /// Angles are in degrees, magnitudes in DB are 20 log₁₀|z|.
+    fn decode(&self, a: f64, b: f64) -> Complex64 {
+        match self {
+            TouchstoneFormat::RealImaginary => Complex64::new(a, b),
+            TouchstoneFormat::MagnitudeAngle => Complex64::from_polar(a, b.to_radians()),
+            TouchstoneFormat::DecibelAngle => Complex64::from_polar(10f64.powf(a / 20.0), b.to_radians()),
+        }
+    }
+
+    fn encode(&self, z: Complex64) -> (f64, f64) {
+        match self {
+            TouchstoneFormat::RealImaginary => (z.re, z.im),
+            TouchstoneFormat::MagnitudeAngle => (z.norm(), z.arg().to_degrees()),
+            TouchstoneFormat::DecibelAngle => (20.0 * z.norm().log10(), z.arg().to_degrees()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TouchstoneFrequencyUnit {
+    #[serde(rename = "Hz", alias = "HZ")]
+    Hz,
+    #[serde(rename = "kHz", alias = "KHZ")]
+    KHz,
+    #[serde(rename = "MHz", alias = "MHZ")]
+    MHz,
+    #[serde(rename = "GHz", alias = "GHZ")]
+    GHz,
+}
+
+impl TouchstoneFrequencyUnit {
+    fn parse(token: &str) -> Option<Self> {
+        match token {
+            "HZ" => Some(TouchstoneFrequencyUnit::Hz),
+            "KHZ" => Some(TouchstoneFrequencyUnit::KHz),
+            "MHZ" => Some(TouchstoneFrequencyUnit::MHz),
+            "GHZ" => Some(TouchstoneFrequencyUnit::GHz),
+            _ => None,
+        }
+    }
+
+    fn keyword(&self) -> &'static str {
+        match self {
+            TouchstoneFrequencyUnit::Hz => "HZ",
+            TouchstoneFrequencyUnit::KHz => "KHZ",
+            TouchstoneFrequencyUnit::MHz => "MHZ",
+            TouchstoneFrequencyUnit::GHz => "GHZ",
+        }
+    }
+
+    fn hertz(&self) -> f64 {
+        match self {
+            TouchstoneFrequencyUnit::Hz => 1.0,
+            TouchstoneFrequencyUnit::KHz => 1e3,
+            TouchstoneFrequencyUnit::MHz => 1e6,
+            TouchstoneFrequencyUnit::GHz => 1e9,
+        }
+    }
+}
+
+fn default_parameter() -> String {
+    "S".into()
+}
+
+fn default_reference() -> Vec<f64> {
+    vec![50.0]
+}
+
+/// Network parameters over frequency, as read from or written to a
+/// Touchstone file.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NetworkData {
+    pub ports: usize,
+    /// S, Y, Z, H or G.
+    #[serde(default = "default_parameter")]
+    pub parameter: String,
+    /// Frequencies in Hz.
+    pub frequencies: Vec<f64>,
+    /// Parameter ij at frequency f is entry `(f * ports + i) * ports + j`.
+    pub real: Vec<f64>,
+    pub imag: Vec<f64>,
+    /// Reference impedance in ohms, one value or one per port.
+    #[serde(default = "default_reference")]
+    pub reference: Vec<f64>,
+    #[serde(default)]
+    pub comments: Vec<String>,
+}
+
+impl NetworkData {
+    pub fn from_matrices(frequencies: Vec<f64>, matrices: &[Vec<Complex64>], ports: usize, comments: Vec<String>) -> Self {
+        let flat: Vec<&Complex64> = matrices.iter().flatten().collect();
+        NetworkData {
+            ports,
+            parameter: default_parameter(),
+            frequencies,
+            real: flat.iter().map(|z| z.re).collect(),
+            imag: flat.iter().map(|z| z.im).collect(),
+            reference: default_reference(),
+            comments,
+        }
+    }
+
+    /// Row-major N × N matrix at frequency index `f`.
+    pub fn matrix(&self, f: usize) -> Vec<Complex64> {
+        let n2 = self.ports * self.ports;
+        complex_from_parts(&self.real[f * n2..(f + 1) * n2], &self.imag[f * n2..(f + 1) * n2])
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let expected = self.frequencies.len() * self.ports * self.ports;
+        if self.ports == 0 || self.real.len() != expected || self.imag.len() != expected {
+            return Err(format!("network data needs {} values per frequency for {} ports", self.ports * self.ports, self.ports));
+        }
+        if self.reference.len() != 1 && self.reference.len() != self.ports {
+            return Err("give one reference impedance or one per port".into());
+        }
+        Ok(())
+    }
+}
+
+fn default_version() -> u8 {
+    1
+}
+
+fn default_format() -> TouchstoneFormat {
+    TouchstoneFormat::RealImaginary
+}
+
+fn default_frequency_unit() -> TouchstoneFrequencyUnit {
+    TouchstoneFrequencyUnit::Hz
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct TouchstoneWriteOptions {
+    /// 1 or 2.
+    #[serde(default = "default_version")]
+    pub version: u8,
+    #[serde(default = "default_format")]
+    pub format: TouchstoneFormat,
+    #[serde(default = "default_frequency_unit")]
+    pub frequency_unit: TouchstoneFrequencyUnit,
+}
+
+impl Default for TouchstoneWriteOptions {
+    fn default() -> Self {
+        TouchstoneWriteOptions { version: 1, format: default_format(), frequency_unit: default_frequency_unit() }
+    }
+}
+
+/// Touchstone text. Version 1 uses the two-port order S11 S21 S12 S22 and
+/// otherwise writes one matrix row per line, wrapped after four pairs;
+/// version 2 writes rows in order with the keyword sections.
+pub fn write_touchstone(data: &NetworkData, options: &TouchstoneWriteOptions) -> Result<String, String> {
+    data.validate()?;
+    let n = data.ports;
+    let uniform = data.reference.iter().all(|&r| r == data.reference[0]);
+    let mut text: String = data.comments.iter().map(|c| format!("! {}\n", c)).collect();
+    match options.version {
+        1 if !uniform => return Err("Touchstone v1 supports a single reference impedance".into()),
+        1 => {}
+        2 => text.push_str("[Version] 2.0\n"),
+        v => return Err(format!("unknown Touchstone version {}", v)),
+    }
+    text.push_str(&format!(
+        "# {} {} {} R {}\n",
+        options.frequency_unit.keyword(),
+        data.parameter.to_uppercase(),
+        options.format.keyword(),
+        data.reference[0]
+    ));
+    if options.version == 2 {
+        text.push_str(&format!("[Number of Ports] {}\n", n));
+        if n == 2 {
+            text.push_str("[Two-Port Data Order] 12_21\n");
+        }
+        text.push_str(&format!("[Number of Frequencies] {}\n", data.frequencies.len()));
+        if !uniform {
+            let values: Vec<String> = data.reference.iter().map(|r| r.to_string()).collect();
+            text.push_str(&format!("[Reference] {}\n", values.join(" ")));
+        }
+        text.push_str("[Network Data]\n");
+    }
+
+    for (f, &frequency) in data.frequencies.iter().enumerate() {
+        let matrix = data.matrix(f);
+        let pair = |i: usize, j: usize| {
+            let (a, b) = options.format.encode(matrix[i * n + j]);
+            format!(" {:.9e} {:.9e}", a, b)
+        };
+        text.push_str(&format!("{:.9e}", frequency / options.frequency_unit.hertz()));
+        if options.version == 1 && n <= 2 {
+            // column-major for one and two ports
+            for j in 0..n {
+                for i in 0..n {
+                    text.push_str(&pair(i, j));
+                }
+            }
+            text.push('\n');
+            continue;
+        }
+        for i in 0..n {
+            for (count, j) in (0..n).enumerate() {
+                if options.version == 1 && count > 0 && count % 4 == 0 {
+                    text.push('\n');
+                }
+                text.push_str(&pair(i, j));
+            }
+            text.push('\n');
+        }
+    }
+    if options.version == 2 {
+        text.push_str("[End]\n");
+    }
+    Ok(text)
+}
+
+/// Option line `# <unit> <parameter> <format> R <impedance>`, with the
+/// Touchstone defaults GHz, S, MA and 50 Ω.
+struct OptionLine {
+    unit: TouchstoneFrequencyUnit,
+    parameter: String,
+    format: TouchstoneFormat,
+    reference: f64,
+}
+
+impl OptionLine {
+    fn parse(line: &str) -> Result<Self, String> {
+        let mut option = OptionLine {
+            unit: TouchstoneFrequencyUnit::GHz,
+            parameter: "S".into(),
+            format: TouchstoneFormat::MagnitudeAngle,
+            reference: 50.0,
+        };
+        let mut tokens = line.trim_start_matches('#').split_whitespace().map(str::to_uppercase);
+        while let Some(token) = tokens.next() {
+            if let Some(unit) = TouchstoneFrequencyUnit::parse(&token) {
+                option.unit = unit;
+            } else if let Some(format) = TouchstoneFormat::parse(&token) {
+                option.format = format;
+            } else if token == "R" {
+                let value = tokens.next().ok_or("option line ends after R")?;
+                option.reference = value.parse().map_err(|_| format!("bad reference impedance '{}'", value))?;
+            } else if ["S", "Y", "Z", "H", "G"].contains(&token.as_str()) {
+                option.parameter = token;
+            } else {
+                return Err(format!("unknown option '{}'", token));
+            }
+        }
+        Ok(option)
+    }
+}
+
+fn parse_numbers(line: &str) -> Result<Vec<f64>, String> {
+    line.split_whitespace()
+        .map(|t| t.parse::<f64>().map_err(|_| format!("'{}' is not a number", t)))
+        .collect()
+}
+
+/// Read a Touchstone v1 or v2 file. For v1 the port count follows from the
+/// layout: each frequency starts on a line with an odd number of values, so
+/// a record of 1 + 2N² values gives N. Two-port noise data, which restarts
+/// the frequency sweep, is skipped.
+pub fn parse_touchstone(text: &str) -> Result<NetworkData, String> {
+    let mut option: Option<OptionLine> = None;
+    let mut comments = Vec::new();
+    let mut version = 1;
+    let mut ports: Option<usize> = None;
+    let mut two_port_21_12 = true;
+    let mut matrix_format = "FULL".to_string();
+    let mut reference_override: Option<Vec<f64>> = None;
+    let mut in_network_data = false;
+    let mut records: Vec<Vec<f64>> = Vec::new();
+
+    for raw in text.lines() {
+        let (line, comment) = match raw.find('!') {
+            Some(k) => (&raw[..k], Some(raw[k + 1..].trim())),
+            None => (raw, None),
+        };
+        if let Some(c) = comment.filter(|c| !c.is_empty()) {
+            comments.push(c.to_string());
+        }
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line.starts_with('#') {
+            if option.is_none() {
+                option = Some(OptionLine::parse(line)?);
+            }
+            continue;
+        }
+        if line.starts_with('[') {
+            let close = line.find(']').ok_or_else(|| format!("unterminated keyword '{}'", line))?;
+            let keyword = line[1..close].trim().to_uppercase();
+            let argument = line[close + 1..].trim();
+            in_network_data = false;
+            match keyword.as_str() {
+                "VERSION" => version = 2,
+                "NUMBER OF PORTS" => ports = Some(argument.parse().map_err(|_| "bad [Number of Ports]")?),
+                "TWO-PORT DATA ORDER" => two_port_21_12 = argument == "21_12",
+                "MATRIX FORMAT" => matrix_format = argument.to_uppercase(),
+                "REFERENCE" => reference_override = Some(parse_numbers(argument)?),
+                "NETWORK DATA" => in_network_data = true,
+                "NOISE DATA" | "END" => break,
+                _ => {}
+            }
+            continue;
+        }
+        let values = parse_numbers(line)?;
+        if version == 2 {
+            if in_network_data {
+                records.push(values);
+            } else if let Some(reference) = reference_override.as_mut() {
+                // [Reference] may continue on the following lines
+                reference.extend(values);
+            }
+            continue;
+        }
+        // v1: a new frequency starts on every line with an odd count
+        if values.len() % 2 == 1 || records.is_empty() {
+            if let (Some(last), Some(&f)) = (records.last(), values.first()) {
+                if f <= last[0] && values.len() == 5 {
+                    break;
+                }
+            }
+            records.push(values);
+        } else {
+            records.last_mut().unwrap().extend(values);
+        }
+    }
+
+    let option = option.ok_or("missing option line '# ...'")?;
+    // merge v2 lines into records of a known size
+    let n = match (version, ports) {
+        (2, Some(n)) => n,
+        (2, None) => return Err("[Number of Ports] is required in Touchstone v2".into()),
+        _ => {
+            let length = records.first().ok_or("no network data")?.len();
+            let n = (((length - 1) / 2) as f64).sqrt().round() as usize;
+            if n == 0 || 1 + 2 * n * n != length {
+                return Err(format!("a record of {} values does not match any port count", length));
+            }
+            n
+        }
+    };
+    let stored = match matrix_format.as_str() {
+        "FULL" => n * n,
+        "LOWER" | "UPPER" => n * (n + 1) / 2,
+        other => return Err(format!("unknown [Matrix Format] {}", other)),
+    };
+    let values: Vec<f64> = if version == 2 { records.concat() } else { Vec::new() };
+    let records: Vec<Vec<f64>> = if version == 2 {
+        values.chunks(1 + 2 * stored).map(|c| c.to_vec()).collect()
+    } else {
+        records
+    };
+
+    let mut data = NetworkData {
+        ports: n,
+        parameter: option.parameter.clone(),
+        frequencies: Vec::new(),
+        real: Vec::new(),
+        imag: Vec::new(),
+        reference: reference_override.unwrap_or_else(|| vec![option.reference]),
+        comments,
+    };
+    for record in records {
+        if record.len() != 1 + 2 * stored {
+            return Err(format!("frequency {} has {} values, expected {}", record[0], record.len() - 1, 2 * stored));
+        }
+        let entries: Vec<Complex64> = record[1..].chunks(2).map(|p| option.format.decode(p[0], p[1])).collect();
+        let mut matrix = vec![Complex64::new(0.0, 0.0); n * n];
+        match matrix_format.as_str() {
+            "LOWER" | "UPPER" => {
+                let mut k = 0;
+                for i in 0..n {
+                    let columns = if matrix_format == "LOWER" { 0..i + 1 } else { i..n };
+                    for j in columns {
+                        matrix[i * n + j] = entries[k];
+                        matrix[j * n + i] = entries[k];
+                        k += 1;
+                    }
+                }
+            }
+            _ if n == 2 && (version == 1 || two_port_21_12) => {
+                matrix = vec![entries[0], entries[2], entries[1], entries[3]];
+            }
+            _ => matrix = entries,
+        }
+        data.frequencies.push(record[0] * option.unit.hertz());
+        data.real.extend(matrix.iter().map(|z| z.re));
+        data.imag.extend(matrix.iter().map(|z| z.im));
+    }
+    if data.frequencies.is_empty() {
+        return Err("no network data".into());
+    }
+    data.validate()?;
+    Ok(data)
+}
+
+/// Parse a Touchstone v1/v2 file into network parameters in Hz
+#[wasm_bindgen]
+pub fn read_touchstone(text: &str) -> Result<JsValue, JsValue> {
+    let data = parse_touchstone(text).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&data)?)
+}
+
+#[derive(Deserialize)]
+pub struct TouchstoneExportConfig {
+    pub data: NetworkData,
+    #[serde(flatten)]
+    pub options: TouchstoneWriteOptions,
+}
+
+/// Write network parameters as a Touchstone v1/v2 file in RI, MA or DB format
+#[wasm_bindgen]
+pub fn export_touchstone(config: &JsValue) -> Result<String, JsValue> {
+    let config: TouchstoneExportConfig = serde_wasm_bindgen::from_value(config.clone())?;
+    write_touchstone(&config.data, &config.options).map_err(|e| JsValue::from_str(&e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn three_port() -> NetworkData {
+        let matrices: Vec<Vec<Complex64>> = (0..2)
+            .map(|f| (0..9).map(|k| Complex64::from_polar(0.1 + 0.05 * k as f64, 0.3 * (k + f) as f64 - 1.0)).collect())
+            .collect();
+        NetworkData::from_matrices(vec![1.9e14, 2.0e14], &matrices, 3, vec!["test".into()])
+    }
+
+    #[test]
+    fn test_round_trip_all_formats_and_versions() {
+        let data = three_port();
+        for version in [1, 2] {
+            for format in [TouchstoneFormat::RealImaginary, TouchstoneFormat::MagnitudeAngle, TouchstoneFormat::DecibelAngle] {
+                let options = TouchstoneWriteOptions { version, format, frequency_unit: TouchstoneFrequencyUnit::GHz };
+                let text = write_touchstone(&data, &options).unwrap();
+                let read = parse_touchstone(&text).unwrap();
+                assert_eq!(read.ports, 3);
+                assert_eq!(read.comments, vec!["test".to_string()]);
+                for f in 0..2 {
+                    assert!((read.frequencies[f] / data.frequencies[f] - 1.0).abs() < 1e-9);
+                    for (a, b) in read.matrix(f).iter().zip(data.matrix(f)) {
+                        assert!((a - b).norm() < 1e-8, "v{} {:?}", version, format);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_reads_two_port_order_and_defaults() {
+        // v1 defaults: GHz, MA; the two-port order is S11 S21 S12 S22, and
+        // the trailing noise block restarts the frequencies
+        let v1 = "! amplifier\n#\n1.0 0.5 0 0.9 90 0.1 0 0.4 180\n2.0 0.5 0 0.8 90 0.1 0 0.4 180\n1.0 1.2 0.5 10 50\n";
+        let data = parse_touchstone(v1).unwrap();
+        assert_eq!(data.ports, 2);
+        assert_eq!(data.frequencies, vec![1e9, 2e9]);
+        assert_eq!(data.reference, vec![50.0]);
+        let m = data.matrix(0);
+        assert!((m[2] - Complex64::new(0.0, 0.9)).norm() < 1e-12); // S21
+        assert!((m[1] - Complex64::new(0.1, 0.0)).norm() < 1e-12); // S12
+
+        let v2 = "[Version] 2.0\n# MHz S RI R 50\n[Number of Ports] 2\n[Two-Port Data Order] 12_21\n\
+                  [Number of Frequencies] 1\n[Matrix Format] Upper\n[Reference] 50\n75\n[Network Data]\n\
+                  100 0.1 0.0 0.2 0.0\n0.3 0.0\n[End]\n";
+        let data = parse_touchstone(v2).unwrap();
+        assert_eq!(data.reference, vec![50.0, 75.0]);
+        assert_eq!(data.frequencies, vec![1e8]);
+        let m = data.matrix(0);
+        assert_eq!((m[1].re, m[2].re, m[3].re), (0.2, 0.2, 0.3));
+        assert!(write_touchstone(&data, &TouchstoneWriteOptions::default()).is_err());
+    }
+}
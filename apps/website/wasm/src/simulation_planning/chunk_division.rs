@@ -0,0 +1,253 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::lattice_calculations::matrix_calculations::Vector3D;
+use crate::simulation_planning::cost_estimator::PmlPlacement;
+
+fn split_evenly() -> bool {
+    true
+}
+
+fn default_pml_cost() -> f64 {
+    0.5
+}
+
+#[derive(Deserialize)]
+pub struct ChunkConfig {
+    /// Meep `cell_size` including the PML; zero sizes collapse a dimension.
+    pub cell_size: Vector3D,
+    pub resolution: f64,
+    #[serde(default)]
+    pub pml: Vec<PmlPlacement>,
+    /// Number of MPI processes, i.e. chunks.
+    pub num_chunks: usize,
+    /// Meep's `split_chunks_evenly`: bisect by pixel count rather than cost.
+    #[serde(default = "split_evenly")]
+    pub split_chunks_evenly: bool,
+    /// Extra cost of a PML pixel relative to an ordinary one, for the
+    /// auxiliary-field updates.
+    #[serde(default = "default_pml_cost")]
+    pub pml_cost: f64,
+}
+
+#[derive(Serialize)]
+pub struct Chunk {
+    pub index: usize,
+    pub min: Vector3D,
+    pub max: Vector3D,
+    /// Pixel ranges [start, end) along x, y, z.
+    pub pixel_start: [usize; 3],
+    pub pixel_end: [usize; 3],
+    pub points: usize,
+    pub pml_points: usize,
+    pub cost: f64,
+}
+
+#[derive(Serialize)]
+pub struct ChunkDecomposition {
+    pub grid: [usize; 3],
+    pub chunks: Vec<Chunk>,
+    pub total_cost: f64,
+    pub max_cost: f64,
+    pub mean_cost: f64,
+    /// max / mean chunk cost; the run is paced by the slowest chunk.
+    pub imbalance: f64,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct PixelBox {
+    lo: [usize; 3],
+    hi: [usize; 3],
+}
+
+/// Pixel and PML counts of boxes in a grid with PML slabs on its faces.
+struct CostModel {
+    grid: [usize; 3],
+    /// Pixel range of the non-PML interior along each axis.
+    interior: [(usize, usize); 3],
+    pml_cost: f64,
+}
+
+impl CostModel {
+    fn points(&self, b: &PixelBox) -> usize {
+        (0..3).map(|d| b.hi[d] - b.lo[d]).product()
+    }
+
+    fn pml_points(&self, b: &PixelBox) -> usize {
+        let inside: usize = (0..3)
+            .map(|d| {
+                let (start, end) = self.interior[d];
+                b.hi[d].min(end).saturating_sub(b.lo[d].max(start))
+            })
+            .product();
+        self.points(b) - inside
+    }
+
+    fn cost(&self, b: &PixelBox) -> f64 {
+        self.points(b) as f64 + self.pml_cost * self.pml_points(b) as f64
+    }
+}
+
+/// Recursive bisection in the manner of Meep's `split_by_cost`: the box is cut
+/// where the left part carries ⌊n/2⌋/n of the weight, along whichever axis
+/// comes closest to that fraction (the longest axis on ties), and both halves
+/// are divided further.
+fn bisect(model: &CostModel, b: PixelBox, n: usize, by_cost: bool, chunks: &mut Vec<PixelBox>) -> bool {
+    if n <= 1 {
+        chunks.push(b);
+        return true;
+    }
+    let weight = |b: &PixelBox| if by_cost { model.cost(b) } else { model.points(b) as f64 };
+    let total = weight(&b);
+    let n_left = n / 2;
+    let target = n_left as f64 / n as f64;
+
+    let mut axes = [0, 1, 2];
+    axes.sort_by_key(|&d| std::cmp::Reverse(b.hi[d] - b.lo[d]));
+    let mut best: Option<(f64, usize, usize)> = None;
+    for d in axes {
+        for cut in b.lo[d] + 1..b.hi[d] {
+            let mut left = b;
+            left.hi[d] = cut;
+            let measure = (weight(&left) / total - target).abs();
+            if best.is_none_or(|(m, _, _)| measure < m - 1e-12) {
+                best = Some((measure, d, cut));
+            }
+        }
+    }
+    let Some((_, d, cut)) = best else {
+        chunks.push(b);
+        return false;
+    };
+    let (mut left, mut right) = (b, b);
+    left.hi[d] = cut;
+    right.lo[d] = cut;
+    let left_ok = bisect(model, left, n_left, by_cost, chunks);
+    let right_ok = bisect(model, right, n - n_left, by_cost, chunks);
+    left_ok && right_ok
+}
+
+pub fn divide_chunks_internal(config: &ChunkConfig) -> Result<ChunkDecomposition, String> {
+    if config.resolution <= 0.0 {
+        return Err("resolution must be positive".into());
+    }
+    if config.num_chunks == 0 {
+        return Err("at least one chunk is required".into());
+    }
+    let size = config.cell_size.to_array();
+    if size.iter().any(|&s| s < 0.0) || size.iter().all(|&s| s == 0.0) {
+        return Err("cell size must be non-negative with at least one extended dimension".into());
+    }
+    let pixels = |length: f64| (length * config.resolution).round() as usize;
+    let grid = [0, 1, 2].map(|d| if size[d] > 0.0 { pixels(size[d]).max(1) } else { 1 });
+
+    let mut faces = [0.0f64; 6];
+    for placement in &config.pml {
+        for (face, t) in faces.iter_mut().zip(placement.faces()?) {
+            *face = face.max(t);
+        }
+    }
+    let interior = [0, 1, 2].map(|d| {
+        if size[d] == 0.0 {
+            return (0, 1);
+        }
+        let start = pixels(faces[2 * d]).min(grid[d]);
+        (start, grid[d].saturating_sub(pixels(faces[2 * d + 1])).max(start))
+    });
+    let model = CostModel { grid, interior, pml_cost: config.pml_cost };
+
+    let mut boxes = Vec::with_capacity(config.num_chunks);
+    let complete = bisect(&model, PixelBox { lo: [0; 3], hi: model.grid }, config.num_chunks, !config.split_chunks_evenly, &mut boxes);
+    let mut warnings = Vec::new();
+    if !complete {
+        warnings.push(format!("the grid has too few pixels for {} chunks; only {} were made", config.num_chunks, boxes.len()));
+    }
+
+    let coordinate = |d: usize, i: usize| if size[d] > 0.0 { -0.5 * size[d] + i as f64 / config.resolution } else { 0.0 };
+    let chunks: Vec<Chunk> = boxes
+        .iter()
+        .enumerate()
+        .map(|(index, b)| Chunk {
+            index,
+            min: Vector3D::from_array([0, 1, 2].map(|d| coordinate(d, b.lo[d]))),
+            max: Vector3D::from_array([0, 1, 2].map(|d| coordinate(d, b.hi[d]))),
+            pixel_start: b.lo,
+            pixel_end: b.hi,
+            points: model.points(b),
+            pml_points: model.pml_points(b),
+            cost: model.cost(b),
+        })
+        .collect();
+
+    let total_cost: f64 = chunks.iter().map(|c| c.cost).sum();
+    let max_cost = chunks.iter().map(|c| c.cost).fold(0.0, f64::max);
+    let mean_cost = total_cost / chunks.len() as f64;
+    let imbalance = max_cost / mean_cost;
+    if imbalance > 1.2 {
+        let worst = chunks.iter().max_by(|a, b| a.cost.total_cmp(&b.cost)).unwrap();
+        warnings.push(format!(
+            "chunk {} carries {:.0}% more work than the average ({:.0}% of it PML); the run is paced by it",
+            worst.index,
+            100.0 * (imbalance - 1.0),
+            100.0 * worst.pml_points as f64 / worst.points as f64
+        ));
+    }
+
+    Ok(ChunkDecomposition { grid, chunks, total_cost, max_cost, mean_cost, imbalance, warnings })
+}
+
+/// Preview Meep's chunk division of the cell for a given process count
+#[wasm_bindgen]
+pub fn divide_chunks(config: &JsValue) -> Result<JsValue, JsValue> {
+    let config: ChunkConfig = serde_wasm_bindgen::from_value(config.clone())?;
+    let result = divide_chunks_internal(&config).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strip(split_chunks_evenly: bool) -> ChunkConfig {
+        ChunkConfig {
+            cell_size: Vector3D::new(12.0, 4.0, 0.0),
+            resolution: 10.0,
+            pml: vec![PmlPlacement { thickness: 1.0, direction: "X".into() }],
+            num_chunks: 4,
+            split_chunks_evenly,
+            pml_cost: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_even_split_tiles_the_cell() {
+        let result = divide_chunks_internal(&strip(true)).unwrap();
+        assert_eq!(result.chunks.len(), 4);
+        assert_eq!(result.chunks.iter().map(|c| c.points).sum::<usize>(), 120 * 40);
+        // equal volumes along the long axis
+        for c in &result.chunks {
+            assert_eq!(c.points, 30 * 40);
+            assert_eq!(c.pixel_end[1] - c.pixel_start[1], 40);
+        }
+        assert!((result.chunks[0].min.x + 6.0).abs() < 1e-12);
+        assert!((result.chunks[3].max.x - 6.0).abs() < 1e-12);
+        // the outer chunks hold the PML and pace the run
+        assert_eq!(result.chunks[0].pml_points, 10 * 40);
+        assert!(result.imbalance > 1.1);
+    }
+
+    #[test]
+    fn test_cost_split_balances_pml() {
+        let even = divide_chunks_internal(&strip(true)).unwrap();
+        let balanced = divide_chunks_internal(&strip(false)).unwrap();
+        assert!(balanced.imbalance < even.imbalance);
+        assert!(balanced.imbalance < 1.05, "{}", balanced.imbalance);
+        assert!(balanced.warnings.is_empty());
+
+        let tiny = ChunkConfig { cell_size: Vector3D::new(0.2, 0.0, 0.0), num_chunks: 4, ..strip(true) };
+        let result = divide_chunks_internal(&tiny).unwrap();
+        assert_eq!(result.chunks.len(), 2);
+        assert!(!result.warnings.is_empty());
+    }
+}
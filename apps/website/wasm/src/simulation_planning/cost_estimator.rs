@@ -0,0 +1,470 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::lattice_calculations::matrix_calculations::Vector3D;
+use crate::material_calculations::material_library::SourceBand;
+
+/// PML placement as written by the boundary panel: a thickness and a
+/// direction of "X", "+X", "-X", … or "ALL".
+#[derive(Clone, Debug, Deserialize)]
+pub struct PmlPlacement {
+    pub thickness: f64,
+    #[serde(default = "all_directions")]
+    pub direction: String,
+}
+
+fn all_directions() -> String {
+    "ALL".into()
+}
+
+impl PmlPlacement {
+    /// Thickness added to each of the faces (−x, +x, −y, +y, −z, +z).
+    pub fn faces(&self) -> Result<[f64; 6], String> {
+        let mut faces = [0.0; 6];
+        let direction = self.direction.trim().to_uppercase();
+        let (sign, axis) = match direction.as_str() {
+            "ALL" => return Ok([self.thickness; 6]),
+            d if d.starts_with('+') => (Some(1), &d[1..]),
+            d if d.starts_with('-') => (Some(0), &d[1..]),
+            d => (None, d),
+        };
+        let axis = match axis {
+            "X" => 0,
+            "Y" => 1,
+            "Z" => 2,
+            _ => return Err(format!("unknown PML direction '{}'", self.direction)),
+        };
+        for side in 0..2 {
+            if sign.is_none_or(|s| s == side) {
+                faces[2 * axis + side] = self.thickness;
+            }
+        }
+        Ok(faces)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DftRegionKind {
+    Flux,
+    Mode,
+    Energy,
+    Force,
+    Fields,
+}
+
+/// A flux, energy, force or field DFT region (`meepRegionTypes.ts`).
+#[derive(Clone, Debug, Deserialize)]
+pub struct DftRegionSpec {
+    pub kind: DftRegionKind,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default = "zero_size")]
+    pub size: Vector3D,
+    #[serde(default = "single_frequency")]
+    pub nfreq: usize,
+    /// Number of field components for `fields` regions.
+    #[serde(default)]
+    pub components: Option<usize>,
+}
+
+fn zero_size() -> Vector3D {
+    Vector3D::new(0.0, 0.0, 0.0)
+}
+
+fn single_frequency() -> usize {
+    1
+}
+
+/// `stop_when_fields_decayed(dt, c, pt, decay_by)` after the sources end.
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct FieldDecaySpec {
+    #[serde(default = "default_decay_interval")]
+    pub dt: f64,
+    #[serde(default = "default_decay_by")]
+    pub decay_by: f64,
+    /// Quality factor of the slowest resonance, if known.
+    #[serde(default)]
+    pub quality_factor: Option<f64>,
+}
+
+fn default_decay_interval() -> f64 {
+    50.0
+}
+
+fn default_decay_by() -> f64 {
+    1e-3
+}
+
+fn default_courant() -> f64 {
+    0.5
+}
+
+fn default_polarizations() -> usize {
+    1
+}
+
+fn default_cutoff() -> f64 {
+    5.0
+}
+
+fn unit_index() -> f64 {
+    1.0
+}
+
+fn default_update_rate() -> f64 {
+    2e8
+}
+
+fn single_core() -> usize {
+    1
+}
+
+#[derive(Deserialize)]
+pub struct CostConfig {
+    /// Meep `cell_size` including the PML; zero sizes collapse a dimension.
+    pub cell_size: Vector3D,
+    pub resolution: f64,
+    #[serde(rename = "Courant", alias = "courant", default = "default_courant")]
+    pub courant: f64,
+    #[serde(default)]
+    pub pml: Vec<PmlPlacement>,
+    /// Independent 2D polarisations that are excited (1 or 2).
+    #[serde(default = "default_polarizations")]
+    pub polarizations: usize,
+    /// Fields are complex when a Bloch `k_point` is set or forced.
+    #[serde(default)]
+    pub complex_fields: bool,
+    #[serde(default)]
+    pub anisotropic: bool,
+    /// Lorentzian/Drude terms of the dispersive materials in the cell.
+    #[serde(default)]
+    pub susceptibility_terms: usize,
+    #[serde(default)]
+    pub regions: Vec<DftRegionSpec>,
+    #[serde(default)]
+    pub sources: Vec<SourceBand>,
+    /// Gaussian sources switch off at 2 · cutoff / fwidth.
+    #[serde(default = "default_cutoff")]
+    pub source_cutoff: f64,
+    #[serde(default)]
+    pub until: Option<f64>,
+    #[serde(default)]
+    pub until_after_sources: Option<f64>,
+    #[serde(default)]
+    pub decay: Option<FieldDecaySpec>,
+    /// Highest index in the cell, used for the transit time when decaying.
+    #[serde(default = "unit_index")]
+    pub max_index: f64,
+    /// Field-component updates per second on one core.
+    #[serde(default = "default_update_rate")]
+    pub updates_per_core_second: f64,
+    #[serde(default = "single_core")]
+    pub cores: usize,
+}
+
+#[derive(Serialize)]
+pub struct RegionCost {
+    pub kind: DftRegionKind,
+    pub name: String,
+    pub points: usize,
+    pub components: usize,
+    pub nfreq: usize,
+    pub bytes: f64,
+}
+
+#[derive(Serialize)]
+pub struct CostEstimate {
+    pub dimensions: usize,
+    pub grid: [usize; 3],
+    pub grid_points: usize,
+    pub pml_points: usize,
+    pub field_bytes: f64,
+    pub material_bytes: f64,
+    pub pml_bytes: f64,
+    pub dispersion_bytes: f64,
+    pub dft_bytes: f64,
+    pub total_bytes: f64,
+    pub regions: Vec<RegionCost>,
+    pub dt: f64,
+    /// Time at which the last source has switched off.
+    pub source_end_time: f64,
+    pub run_time: f64,
+    pub time_steps: usize,
+    pub estimated_seconds: f64,
+    /// e.g. "≈3.2 GB, ≈40 min on 8 cores".
+    pub summary: String,
+    pub warnings: Vec<String>,
+}
+
+const REAL_BYTES: f64 = 8.0;
+const COMPLEX_BYTES: f64 = 16.0;
+
+/// Number of E and H components stored per voxel in Meep.
+fn field_components(dimensions: usize, polarizations: usize) -> (usize, usize) {
+    match dimensions {
+        1 => (1, 1),
+        // TE (Ex, Ey, Hz) is the larger polarisation
+        2 if polarizations < 2 => (2, 1),
+        _ => (3, 3),
+    }
+}
+
+pub fn format_bytes(bytes: f64) -> String {
+    let units = ["B", "kB", "MB", "GB", "TB"];
+    let mut value = bytes;
+    let mut unit = 0;
+    while value >= 1000.0 && unit < units.len() - 1 {
+        value /= 1000.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, units[unit])
+}
+
+pub fn format_duration(seconds: f64) -> String {
+    if seconds < 60.0 {
+        format!("{:.0} s", seconds.max(1.0))
+    } else if seconds < 3600.0 {
+        format!("{:.0} min", seconds / 60.0)
+    } else if seconds < 86400.0 {
+        format!("{:.1} h", seconds / 3600.0)
+    } else {
+        format!("{:.1} days", seconds / 86400.0)
+    }
+}
+
+pub fn estimate_cost_internal(config: &CostConfig) -> Result<CostEstimate, String> {
+    if config.resolution <= 0.0 {
+        return Err("resolution must be positive".into());
+    }
+    if config.courant <= 0.0 {
+        return Err("Courant factor must be positive".into());
+    }
+    let size = config.cell_size.to_array();
+    if size.iter().any(|&s| s < 0.0) || size.iter().all(|&s| s == 0.0) {
+        return Err("cell size must be non-negative with at least one extended dimension".into());
+    }
+    let active: Vec<bool> = size.iter().map(|&s| s > 0.0).collect();
+    let dimensions = active.iter().filter(|&&a| a).count();
+    let mut warnings = Vec::new();
+
+    let pixels = |length: f64| (length * config.resolution).round() as usize;
+    let grid = [0, 1, 2].map(|d| if active[d] { pixels(size[d]).max(1) } else { 1 });
+    let grid_points: usize = grid.iter().product();
+
+    let mut faces = [0.0f64; 6];
+    for placement in &config.pml {
+        for (face, t) in faces.iter_mut().zip(placement.faces()?) {
+            *face = face.max(t);
+        }
+    }
+    let interior: usize = (0..3)
+        .map(|d| {
+            if !active[d] {
+                return 1;
+            }
+            grid[d].saturating_sub(pixels(faces[2 * d]) + pixels(faces[2 * d + 1]))
+        })
+        .product();
+    if interior == 0 {
+        warnings.push("the PML fills the whole cell".into());
+    }
+    let pml_points = grid_points - interior;
+
+    let (e_components, h_components) = field_components(dimensions, config.polarizations);
+    let eh = e_components + h_components;
+    let value_bytes = if config.complex_fields { COMPLEX_BYTES } else { REAL_BYTES };
+    // E, D, H and B for every component
+    let field_bytes = grid_points as f64 * 2.0 * eh as f64 * value_bytes;
+    // ε⁻¹ and μ⁻¹ per component, a full row when anisotropic
+    let material_bytes = grid_points as f64 * eh as f64 * if config.anisotropic { 3.0 } else { 1.0 } * REAL_BYTES;
+    // one auxiliary array per D and B component inside the PML
+    let pml_bytes = pml_points as f64 * eh as f64 * value_bytes;
+    // P and P_prev per E component and susceptibility term
+    let dispersion_bytes = grid_points as f64 * config.susceptibility_terms as f64 * 2.0 * e_components as f64 * value_bytes;
+
+    let tangential = match dimensions {
+        1 => 2,
+        2 if config.polarizations < 2 => 2,
+        _ => 4,
+    };
+    let regions: Vec<RegionCost> = config
+        .regions
+        .iter()
+        .map(|region| {
+            let extent = region.size.to_array();
+            let points: usize =
+                (0..3).map(|d| if active[d] { pixels(extent[d].min(size[d])) + 1 } else { 1 }).product();
+            let components = match region.kind {
+                DftRegionKind::Flux | DftRegionKind::Mode => tangential,
+                DftRegionKind::Energy => 2 * eh,
+                DftRegionKind::Force => eh,
+                DftRegionKind::Fields => region.components.unwrap_or(eh),
+            };
+            let nfreq = region.nfreq.max(1);
+            RegionCost {
+                kind: region.kind,
+                name: region.name.clone(),
+                points,
+                components,
+                nfreq,
+                bytes: (points * components * nfreq) as f64 * COMPLEX_BYTES,
+            }
+        })
+        .collect();
+    let dft_bytes: f64 = regions.iter().map(|r| r.bytes).sum();
+    let total_bytes = field_bytes + material_bytes + pml_bytes + dispersion_bytes + dft_bytes;
+
+    let dt = config.courant / config.resolution;
+    let continuous = config.sources.iter().any(|s| s.fwidth <= 0.0);
+    let source_end_time = config
+        .sources
+        .iter()
+        .filter(|s| s.fwidth > 0.0)
+        .map(|s| 2.0 * config.source_cutoff / s.fwidth)
+        .fold(0.0, f64::max);
+    let run_time = if let Some(until) = config.until {
+        until
+    } else if let Some(decay) = config.decay {
+        if continuous {
+            return Err("fields never decay while a continuous source is running; use `until`".into());
+        }
+        let f_min = config.sources.iter().map(|s| s.range().min).filter(|&f| f > 0.0).fold(f64::INFINITY, f64::min);
+        // |E|² decays as exp(−ωt/Q); without Q, assume a few transits of the cell
+        let decay_time = match decay.quality_factor {
+            Some(q) if f_min.is_finite() => q * (1.0 / decay.decay_by).ln() / (2.0 * std::f64::consts::PI * f_min),
+            _ => {
+                warnings.push("no quality factor given; the decay time assumes two transits of the cell".into());
+                2.0 * config.max_index * config.cell_size.norm()
+            }
+        };
+        // the criterion is checked every dt, and needs at least one interval
+        source_end_time + (decay_time / decay.dt).ceil().max(1.0) * decay.dt
+    } else if let Some(after) = config.until_after_sources {
+        if continuous {
+            return Err("continuous sources never switch off; use `until`".into());
+        }
+        source_end_time + after
+    } else {
+        return Err("one of until, until_after_sources or decay is required".into());
+    };
+    if run_time <= 0.0 {
+        return Err("run time must be positive".into());
+    }
+    if config.until.is_some_and(|t| t < source_end_time) {
+        warnings.push(format!("the run stops at t = {} before the sources end at t = {:.1}", run_time, source_end_time));
+    }
+    let time_steps = (run_time / dt).ceil() as usize;
+
+    let dft_updates: usize = regions.iter().map(|r| r.points * r.components * r.nfreq).sum();
+    let updates_per_step = grid_points as f64 * (2.0 * eh as f64 + 2.0 * (config.susceptibility_terms * e_components) as f64)
+        + pml_points as f64 * eh as f64
+        + dft_updates as f64;
+    let cores = config.cores.max(1);
+    let estimated_seconds = updates_per_step * time_steps as f64 / (config.updates_per_core_second * cores as f64);
+    let summary = format!(
+        "≈{}, ≈{} on {} core{}",
+        format_bytes(total_bytes),
+        format_duration(estimated_seconds),
+        cores,
+        if cores == 1 { "" } else { "s" }
+    );
+
+    Ok(CostEstimate {
+        dimensions,
+        grid,
+        grid_points,
+        pml_points,
+        field_bytes,
+        material_bytes,
+        pml_bytes,
+        dispersion_bytes,
+        dft_bytes,
+        total_bytes,
+        regions,
+        dt,
+        source_end_time,
+        run_time,
+        time_steps,
+        estimated_seconds,
+        summary,
+        warnings,
+    })
+}
+
+/// Estimate memory, time steps and wall time of a Meep run
+#[wasm_bindgen]
+pub fn estimate_simulation_cost(config: &JsValue) -> Result<JsValue, JsValue> {
+    let config: CostConfig = serde_wasm_bindgen::from_value(config.clone())?;
+    let result = estimate_cost_internal(&config).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell_2d() -> CostConfig {
+        CostConfig {
+            cell_size: Vector3D::new(16.0, 8.0, 0.0),
+            resolution: 10.0,
+            courant: default_courant(),
+            pml: vec![PmlPlacement { thickness: 1.0, direction: all_directions() }],
+            polarizations: 1,
+            complex_fields: false,
+            anisotropic: false,
+            susceptibility_terms: 0,
+            regions: vec![DftRegionSpec {
+                kind: DftRegionKind::Flux,
+                name: "trans".into(),
+                size: Vector3D::new(0.0, 4.0, 0.0),
+                nfreq: 100,
+                components: None,
+            }],
+            sources: vec![SourceBand { frequency: 0.15, fwidth: 0.1 }],
+            source_cutoff: default_cutoff(),
+            until: None,
+            until_after_sources: Some(200.0),
+            decay: None,
+            max_index: unit_index(),
+            updates_per_core_second: default_update_rate(),
+            cores: 1,
+        }
+    }
+
+    #[test]
+    fn test_counts_grid_pml_and_dft_storage() {
+        let estimate = estimate_cost_internal(&cell_2d()).unwrap();
+        assert_eq!(estimate.dimensions, 2);
+        assert_eq!(estimate.grid, [160, 80, 1]);
+        assert_eq!(estimate.grid_points, 12800);
+        assert_eq!(estimate.pml_points, 12800 - 140 * 60);
+        // TE: Ex, Ey, Hz with D and B
+        assert_eq!(estimate.field_bytes, 12800.0 * 6.0 * 8.0);
+        assert_eq!(estimate.regions[0].points, 41);
+        assert_eq!(estimate.dft_bytes, 41.0 * 2.0 * 100.0 * 16.0);
+        // the Gaussian ends at 2 · 5 / 0.1 = 100
+        assert_eq!(estimate.run_time, 300.0);
+        assert_eq!(estimate.time_steps, 6000);
+        assert!(estimate.summary.starts_with('≈'));
+    }
+
+    #[test]
+    fn test_decay_time_and_one_sided_pml() {
+        let config = CostConfig {
+            cell_size: Vector3D::new(4.0, 4.0, 4.0),
+            pml: vec![PmlPlacement { thickness: 1.0, direction: "+Z".into() }],
+            until_after_sources: None,
+            decay: Some(FieldDecaySpec { dt: 50.0, decay_by: 1e-3, quality_factor: Some(1000.0) }),
+            ..cell_2d()
+        };
+        let estimate = estimate_cost_internal(&config).unwrap();
+        assert_eq!(estimate.pml_points, 40 * 40 * 10);
+        // Q ln(1000) / (2π · 0.1) ≈ 10994, checked every 50
+        assert_eq!(estimate.run_time, 100.0 + 11000.0);
+        assert!(estimate.warnings.is_empty());
+
+        let continuous = CostConfig { sources: vec![SourceBand { frequency: 0.15, fwidth: 0.0 }], ..config };
+        assert!(estimate_cost_internal(&continuous).is_err());
+    }
+}
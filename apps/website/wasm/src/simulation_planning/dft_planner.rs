@@ -0,0 +1,200 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::material_calculations::dispersion::FrequencyRange;
+use crate::material_calculations::material_library::SourceBand;
+use crate::numerical_calculations::fourier_transform::gaussian_envelope_spectrum;
+
+/// Frequencies of a DFT monitor as passed to `add_flux(fcen, df, nfreq)`.
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct MonitorBand {
+    pub fcen: f64,
+    pub df: f64,
+    pub nfreq: usize,
+}
+
+impl MonitorBand {
+    pub fn frequencies(&self) -> Vec<f64> {
+        if self.nfreq <= 1 {
+            return vec![self.fcen];
+        }
+        (0..self.nfreq).map(|i| self.fcen - 0.5 * self.df + self.df * i as f64 / (self.nfreq - 1) as f64).collect()
+    }
+
+    pub fn spacing(&self) -> f64 {
+        if self.nfreq <= 1 { 0.0 } else { self.df / (self.nfreq - 1) as f64 }
+    }
+}
+
+fn default_power_threshold() -> f64 {
+    1e-3
+}
+
+#[derive(Deserialize)]
+pub struct DftPlanConfig {
+    /// Gaussian sources (Meep `GaussianSource(frequency, fwidth)`).
+    pub sources: Vec<SourceBand>,
+    /// Time over which the DFT accumulates, normally the whole run.
+    pub run_time: f64,
+    pub monitor: MonitorBand,
+    /// Source power, relative to its peak, below which normalised spectra
+    /// divide noise by almost nothing.
+    #[serde(default = "default_power_threshold")]
+    pub power_threshold: f64,
+}
+
+#[derive(Serialize)]
+pub struct DftPlan {
+    /// Fourier limit 1 / T of the run.
+    pub resolvable_spacing: f64,
+    pub spacing: f64,
+    pub monitor_band: FrequencyRange,
+    /// Where the combined source power stays above the threshold.
+    pub usable_band: FrequencyRange,
+    pub exceeds_source_bandwidth: bool,
+    pub frequencies: Vec<f64>,
+    /// Combined source power |Σ S(f)|² relative to its peak, per frequency.
+    pub source_power: Vec<f64>,
+    /// Monitor frequencies at which the source power is below the threshold.
+    pub low_power_frequencies: Vec<f64>,
+    pub recommended_nfreq: usize,
+    /// The monitor band clipped to the usable source band.
+    pub recommended_band: FrequencyRange,
+    pub warnings: Vec<String>,
+}
+
+/// Combined spectral power of Gaussian sources, |Σ exp(−2π² w² (f − f₀)²)|²
+/// with w = 1/fwidth, up to the normalisation applied by the caller.
+fn source_power(sources: &[SourceBand], f: f64) -> f64 {
+    sources.iter().map(|s| gaussian_envelope_spectrum(s.frequency, 1.0 / s.fwidth, f)).sum::<f64>().powi(2)
+}
+
+pub fn plan_dft_internal(config: &DftPlanConfig) -> Result<DftPlan, String> {
+    if config.sources.is_empty() || config.sources.iter().any(|s| s.fwidth <= 0.0 || s.frequency <= 0.0) {
+        return Err("at least one Gaussian source with positive frequency and fwidth is required".into());
+    }
+    if config.run_time <= 0.0 {
+        return Err("run time must be positive".into());
+    }
+    let monitor = config.monitor;
+    if monitor.nfreq == 0 || monitor.df < 0.0 {
+        return Err("monitor needs nfreq ≥ 1 and df ≥ 0".into());
+    }
+    if !(config.power_threshold > 0.0 && config.power_threshold < 1.0) {
+        return Err("power threshold must lie in (0, 1)".into());
+    }
+
+    // peak power and the threshold band, on a grid spanning all sources
+    let low = config.sources.iter().map(|s| s.frequency - 2.0 * s.fwidth).fold(f64::INFINITY, f64::min).max(0.0);
+    let high = config.sources.iter().map(|s| s.frequency + 2.0 * s.fwidth).fold(0.0, f64::max);
+    let grid: Vec<f64> = (0..=4000).map(|i| low + (high - low) * i as f64 / 4000.0).collect();
+    let peak = grid.iter().map(|&f| source_power(&config.sources, f)).fold(0.0, f64::max);
+    let relative = |f: f64| source_power(&config.sources, f) / peak;
+    let usable: Vec<f64> = grid.iter().cloned().filter(|&f| relative(f) >= config.power_threshold).collect();
+    let usable_band = FrequencyRange { min: usable[0], max: usable[usable.len() - 1] };
+
+    let frequencies = monitor.frequencies();
+    let source_power: Vec<f64> = frequencies.iter().map(|&f| relative(f)).collect();
+    let low_power_frequencies: Vec<f64> = frequencies
+        .iter()
+        .zip(&source_power)
+        .filter(|(_, &p)| p < config.power_threshold)
+        .map(|(&f, _)| f)
+        .collect();
+    let monitor_band = FrequencyRange { min: monitor.fcen - 0.5 * monitor.df, max: monitor.fcen + 0.5 * monitor.df };
+    let exceeds_source_bandwidth = monitor_band.min < usable_band.min || monitor_band.max > usable_band.max;
+
+    let resolvable_spacing = 1.0 / config.run_time;
+    let spacing = monitor.spacing();
+    let recommended_band = FrequencyRange {
+        min: monitor_band.min.max(usable_band.min),
+        max: monitor_band.max.min(usable_band.max),
+    };
+    let recommended_nfreq = if recommended_band.max > recommended_band.min {
+        ((recommended_band.max - recommended_band.min) / resolvable_spacing).ceil() as usize + 1
+    } else {
+        1
+    };
+
+    let mut warnings = Vec::new();
+    if exceeds_source_bandwidth {
+        warnings.push(format!(
+            "the monitor band {:.4}–{:.4} extends beyond {:.4}–{:.4}, where the source power is above {:.0e}; \
+             {} of {} normalised values will be dominated by noise",
+            monitor_band.min,
+            monitor_band.max,
+            usable_band.min,
+            usable_band.max,
+            config.power_threshold,
+            low_power_frequencies.len(),
+            frequencies.len()
+        ));
+    }
+    if monitor.nfreq > 1 && spacing < resolvable_spacing {
+        warnings.push(format!(
+            "frequency spacing {:.3e} is finer than the 1/T = {:.3e} resolvable in a run of {}; \
+             neighbouring points are not independent",
+            spacing, resolvable_spacing, config.run_time
+        ));
+    }
+
+    Ok(DftPlan {
+        resolvable_spacing,
+        spacing,
+        monitor_band,
+        usable_band,
+        exceeds_source_bandwidth,
+        frequencies,
+        source_power,
+        low_power_frequencies,
+        recommended_nfreq,
+        recommended_band,
+        warnings,
+    })
+}
+
+/// Check a flux/mode monitor's frequency grid against the source spectrum and run time
+#[wasm_bindgen]
+pub fn plan_dft_frequencies(config: &JsValue) -> Result<JsValue, JsValue> {
+    let config: DftPlanConfig = serde_wasm_bindgen::from_value(config.clone())?;
+    let result = plan_dft_internal(&config).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    fn config(df: f64, nfreq: usize) -> DftPlanConfig {
+        DftPlanConfig {
+            sources: vec![SourceBand { frequency: 1.0, fwidth: 0.5 }],
+            run_time: 200.0,
+            monitor: MonitorBand { fcen: 1.0, df, nfreq },
+            power_threshold: default_power_threshold(),
+        }
+    }
+
+    #[test]
+    fn test_usable_band_follows_gaussian_spectrum() {
+        let plan = plan_dft_internal(&config(0.4, 41)).unwrap();
+        // power exp(−4π² w² Δf²) = 1e-3 at Δf = √(ln 1000) / (2π w)
+        let half_width = (1000f64).ln().sqrt() / (2.0 * PI * 2.0);
+        assert!((plan.usable_band.max - 1.0 - half_width).abs() < 1e-3);
+        assert!((plan.usable_band.min - 1.0 + half_width).abs() < 1e-3);
+        assert!(!plan.exceeds_source_bandwidth);
+        assert!(plan.warnings.is_empty());
+        assert!((plan.source_power[20] - 1.0).abs() < 1e-12);
+        assert_eq!(plan.recommended_nfreq, 81);
+    }
+
+    #[test]
+    fn test_flags_wide_and_oversampled_monitor() {
+        let plan = plan_dft_internal(&config(1.2, 2001)).unwrap();
+        assert!(plan.exceeds_source_bandwidth);
+        assert!(!plan.low_power_frequencies.is_empty());
+        assert!(plan.low_power_frequencies.iter().all(|&f| (f - 1.0).abs() > 0.2));
+        assert_eq!(plan.warnings.len(), 2);
+        assert!(plan.recommended_band.max - plan.recommended_band.min < 1.2);
+    }
+}
@@ -0,0 +1,236 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize)]
+pub struct FieldDecayConfig {
+    /// Sample times of the probe, ascending.
+    pub times: Vec<f64>,
+    /// The component `c` at point `pt`; `values_imag` for complex fields.
+    pub values: Vec<f64>,
+    #[serde(default)]
+    pub values_imag: Option<Vec<f64>>,
+    pub dt: f64,
+    pub decay_by: f64,
+    /// When the criterion starts running, e.g. the end of the sources for
+    /// `until_after_sources`.
+    #[serde(default)]
+    pub start_time: f64,
+    /// Further `decay_by` values to compare at the same `dt`.
+    #[serde(default)]
+    pub sweep_decay_by: Vec<f64>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct DecayCheck {
+    pub time: f64,
+    /// max |f|² over the interval that just ended.
+    pub interval_max: f64,
+    /// max |f|² over all intervals so far.
+    pub overall_max: f64,
+    pub ratio: f64,
+}
+
+#[derive(Serialize)]
+pub struct DecaySweepEntry {
+    pub decay_by: f64,
+    pub stop_time: Option<f64>,
+    pub residual_energy: Option<f64>,
+}
+
+#[derive(Serialize)]
+pub struct FieldDecayResult {
+    pub stopped: bool,
+    pub stop_time: Option<f64>,
+    pub checks: Vec<DecayCheck>,
+    /// Fraction of ∫|f|² dt after `start_time` that is still to come at the
+    /// stop, including the fitted exponential tail beyond the record.
+    pub residual_energy: Option<f64>,
+    /// Fitted decay rate of |f|², from the interval maxima after the peak.
+    pub decay_rate: Option<f64>,
+    /// Extrapolated stop time when the record ends before the criterion is met.
+    pub predicted_stop_time: Option<f64>,
+    pub sweep: Vec<DecaySweepEntry>,
+    pub warnings: Vec<String>,
+}
+
+/// Replays Meep's `stop_when_fields_decayed`: |f|² is tracked per interval,
+/// and once more than `dt` has passed since the last check the interval max
+/// is compared with the running maximum. Returns the checks and the index of
+/// the sample at which the run stops.
+fn replay(times: &[f64], intensity: &[f64], start_time: f64, dt: f64, decay_by: f64) -> (Vec<DecayCheck>, Option<usize>) {
+    let (mut overall_max, mut interval_max, mut t0) = (0.0f64, 0.0f64, 0.0);
+    let mut checks = Vec::new();
+    for (i, (&t, &value)) in times.iter().zip(intensity).enumerate() {
+        if t < start_time {
+            continue;
+        }
+        interval_max = interval_max.max(value);
+        if t <= dt + t0 {
+            continue;
+        }
+        let finished = interval_max;
+        interval_max = 0.0;
+        t0 = t;
+        overall_max = overall_max.max(finished);
+        checks.push(DecayCheck {
+            time: t,
+            interval_max: finished,
+            overall_max,
+            ratio: if overall_max > 0.0 { finished / overall_max } else { 0.0 },
+        });
+        if finished <= overall_max * decay_by {
+            return (checks, Some(i));
+        }
+    }
+    (checks, None)
+}
+
+/// Least-squares slope of −ln(interval max) against time after the peak.
+fn fit_decay_rate(checks: &[DecayCheck]) -> Option<f64> {
+    let peak = checks.iter().enumerate().max_by(|a, b| a.1.interval_max.total_cmp(&b.1.interval_max))?.0;
+    let points: Vec<(f64, f64)> =
+        checks[peak..].iter().filter(|c| c.interval_max > 0.0).map(|c| (c.time, c.interval_max.ln())).collect();
+    if points.len() < 3 {
+        return None;
+    }
+    let n = points.len() as f64;
+    let mean_t = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let sxy: f64 = points.iter().map(|p| (p.0 - mean_t) * (p.1 - mean_y)).sum();
+    let sxx: f64 = points.iter().map(|p| (p.0 - mean_t).powi(2)).sum();
+    let rate = -sxy / sxx;
+    (rate > 0.0).then_some(rate)
+}
+
+/// Trapezoidal ∫|f|² dt from sample `from` to the end of the record.
+fn energy_from(times: &[f64], intensity: &[f64], from: usize) -> f64 {
+    (from + 1..times.len()).map(|i| 0.5 * (intensity[i] + intensity[i - 1]) * (times[i] - times[i - 1])).sum()
+}
+
+pub fn field_decay_internal(config: &FieldDecayConfig) -> Result<FieldDecayResult, String> {
+    let n = config.times.len();
+    if n < 2 || config.values.len() != n || config.values_imag.as_ref().is_some_and(|v| v.len() != n) {
+        return Err("times and values must have the same length of at least two".into());
+    }
+    if config.times.windows(2).any(|w| w[1] <= w[0]) {
+        return Err("times must be strictly increasing".into());
+    }
+    if config.dt <= 0.0 || !(config.decay_by > 0.0 && config.decay_by < 1.0) {
+        return Err("dt must be positive and decay_by in (0, 1)".into());
+    }
+    let intensity: Vec<f64> = match &config.values_imag {
+        Some(imag) => config.values.iter().zip(imag).map(|(re, im)| re * re + im * im).collect(),
+        None => config.values.iter().map(|v| v * v).collect(),
+    };
+    let first = config.times.partition_point(|&t| t < config.start_time);
+    if first >= n {
+        return Err("the record ends before start_time".into());
+    }
+
+    let (checks, stop) = replay(&config.times, &intensity, config.start_time, config.dt, config.decay_by);
+    let decay_rate = fit_decay_rate(&checks);
+    // beyond the record, |f|² ≈ (last interval max / 2) exp(−rate t) on average
+    let tail = match (decay_rate, checks.last()) {
+        (Some(rate), Some(last)) => 0.5 * last.interval_max / rate,
+        _ => 0.0,
+    };
+    let total = energy_from(&config.times, &intensity, first) + tail;
+    let residual = |index: usize| (total > 0.0).then(|| (energy_from(&config.times, &intensity, index) + tail) / total);
+
+    let mut warnings = Vec::new();
+    if stop.is_some() && checks.len() == 1 {
+        warnings.push("the field at the probe is zero, so Meep stops at the first check; move the point".into());
+    }
+    let predicted_stop_time = match (stop, decay_rate, checks.last()) {
+        (None, Some(rate), Some(last)) if last.interval_max > 0.0 => {
+            let interval = config.dt + (config.times[n - 1] - config.times[0]) / (n - 1) as f64;
+            let decades = (last.interval_max / (last.overall_max * config.decay_by)).ln();
+            Some(last.time + (decades / (rate * interval)).ceil().max(1.0) * interval)
+        }
+        _ => None,
+    };
+    if stop.is_none() {
+        warnings.push(match predicted_stop_time {
+            Some(t) => format!("the record ends before the fields decay by {:.0e}; Meep would stop near t = {:.0}", config.decay_by, t),
+            None => format!("the record ends before the fields decay by {:.0e}", config.decay_by),
+        });
+    }
+
+    let sweep = config
+        .sweep_decay_by
+        .iter()
+        .map(|&decay_by| {
+            let (_, index) = replay(&config.times, &intensity, config.start_time, config.dt, decay_by);
+            DecaySweepEntry {
+                decay_by,
+                stop_time: index.map(|i| config.times[i]),
+                residual_energy: index.and_then(residual),
+            }
+        })
+        .collect();
+
+    Ok(FieldDecayResult {
+        stopped: stop.is_some(),
+        stop_time: stop.map(|i| config.times[i]),
+        residual_energy: stop.and_then(residual),
+        checks,
+        decay_rate,
+        predicted_stop_time,
+        sweep,
+        warnings,
+    })
+}
+
+/// Replay Meep's stop_when_fields_decayed on a recorded probe time series
+#[wasm_bindgen]
+pub fn check_field_decay(config: &JsValue) -> Result<JsValue, JsValue> {
+    let config: FieldDecayConfig = serde_wasm_bindgen::from_value(config.clone())?;
+    let result = field_decay_internal(&config).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    /// Ring-down at f = 1 with Q = 50, so |f|² ∝ exp(−2πt / 50), sampled
+    /// every 0.05 up to `end`.
+    fn ring_down(end: f64) -> FieldDecayConfig {
+        let times: Vec<f64> = (0..=(end / 0.05).round() as usize).map(|i| i as f64 * 0.05).collect();
+        let values = times.iter().map(|&t| (-PI * t / 50.0).exp() * (2.0 * PI * t).cos()).collect();
+        FieldDecayConfig {
+            times,
+            values,
+            values_imag: None,
+            dt: 10.0,
+            decay_by: 1e-3,
+            start_time: 0.0,
+            sweep_decay_by: vec![1e-2, 1e-3],
+        }
+    }
+
+    #[test]
+    fn test_stops_when_interval_max_decays() {
+        let result = field_decay_internal(&ring_down(150.0)).unwrap();
+        // interval (50, 60] still holds exp(−2π) ≈ 1.9e-3; (60, 70] does not
+        let stop = result.stop_time.unwrap();
+        assert!(stop > 70.0 && stop < 70.5, "{}", stop);
+        assert!((result.decay_rate.unwrap() / (2.0 * PI / 50.0) - 1.0).abs() < 0.05);
+        // the energy left is about exp(−rate · 60)
+        let residual = result.residual_energy.unwrap();
+        assert!(residual > 1e-4 && residual < 1e-3, "{}", residual);
+        assert!(result.sweep[0].stop_time.unwrap() < stop);
+        assert_eq!(result.sweep[1].stop_time, result.stop_time);
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_predicts_stop_beyond_short_record() {
+        let result = field_decay_internal(&ring_down(45.0)).unwrap();
+        assert!(!result.stopped);
+        let predicted = result.predicted_stop_time.unwrap();
+        assert!((predicted - 70.0).abs() < 11.0, "{}", predicted);
+        assert_eq!(result.warnings.len(), 1);
+    }
+}
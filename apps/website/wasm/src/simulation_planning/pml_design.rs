@@ -0,0 +1,302 @@
+use num_complex::Complex64;
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+use crate::field_solvers::fdfd::PmlSpec;
+use crate::material_calculations::material_library::SourceBand;
+
+/// One PML parameter set as assigned to an edge in the boundary panel.
+#[derive(Clone, Debug, Deserialize)]
+pub struct PmlEdgeSpec {
+    #[serde(default)]
+    pub edge: String,
+    #[serde(flatten)]
+    pub pml: PmlSpec,
+}
+
+fn unit_index() -> f64 {
+    1.0
+}
+
+fn default_angles() -> Vec<f64> {
+    vec![0.0, 30.0, 60.0, 80.0]
+}
+
+fn default_target_reflection() -> f64 {
+    1e-6
+}
+
+fn default_profile_points() -> usize {
+    50
+}
+
+#[derive(Deserialize)]
+pub struct PmlDesignConfig {
+    pub resolution: f64,
+    pub layers: Vec<PmlEdgeSpec>,
+    /// The lowest frequency of these bands is the design frequency.
+    pub sources: Vec<SourceBand>,
+    /// Refractive index of the medium adjacent to the PML.
+    #[serde(default = "unit_index")]
+    pub index: f64,
+    /// Angles of incidence in degrees.
+    #[serde(default = "default_angles")]
+    pub angles: Vec<f64>,
+    #[serde(default = "default_target_reflection")]
+    pub target_reflection: f64,
+    #[serde(default = "default_profile_points")]
+    pub profile_points: usize,
+}
+
+#[derive(Serialize)]
+pub struct PmlProfileSample {
+    pub depth: f64,
+    pub sigma: f64,
+}
+
+#[derive(Serialize)]
+pub struct PmlAngleReflection {
+    pub angle: f64,
+    /// Reflection coefficient of the continuous profile, R^(n cos θ).
+    pub theoretical: f64,
+    /// Reflection of the discretised layer at the current resolution.
+    pub discrete: f64,
+}
+
+#[derive(Serialize)]
+pub struct PmlEdgeReport {
+    pub edge: String,
+    pub thickness: f64,
+    pub power: f64,
+    #[serde(rename = "R_asymptotic")]
+    pub r_asymptotic: f64,
+    pub sigma_max: f64,
+    pub cells: usize,
+    /// Thickness in wavelengths of the adjacent medium at the design frequency.
+    pub thickness_in_wavelengths: f64,
+    pub profile: Vec<PmlProfileSample>,
+    pub reflections: Vec<PmlAngleReflection>,
+    /// Smallest whole-cell thickness meeting the target at normal incidence.
+    pub recommended_thickness: Option<f64>,
+    #[serde(rename = "recommended_R_asymptotic")]
+    pub recommended_r_asymptotic: f64,
+    pub adequate: bool,
+}
+
+#[derive(Serialize)]
+pub struct PmlDesignResult {
+    pub design_frequency: f64,
+    pub wavelength: f64,
+    pub edges: Vec<PmlEdgeReport>,
+    pub warnings: Vec<String>,
+}
+
+/// Round-trip reflection coefficient |r| of the continuous graded PML. The
+/// stretch 1 + iσ/ω attenuates a wave with kₓ = nω cos θ by exp(−n cos θ ∫σ)
+/// each way, and σ₀ is chosen so that exp(−2∫σ) = R_asymptotic.
+pub fn theoretical_pml_reflection(pml: &PmlSpec, index: f64, angle: f64) -> f64 {
+    if pml.thickness <= 0.0 {
+        return 1.0;
+    }
+    pml.r_asymptotic.powf(index * angle.cos().max(0.0))
+}
+
+/// Reflection coefficient |r| of a PEC-backed PML discretised on a 1D Yee grid, for a plane
+/// wave at `angle` in a medium of index `index`. The discrete Helmholtz
+/// equation (1/s) d/dx (1/s dE/dx) + kₓ² E = 0 is marched from the wall back
+/// into the uniform region, where the field is split into the discrete
+/// incident and reflected waves.
+pub fn discrete_pml_reflection(pml: &PmlSpec, resolution: f64, frequency: f64, index: f64, angle: f64) -> f64 {
+    let dx = 1.0 / resolution;
+    let omega = 2.0 * PI * frequency;
+    let kx = index * omega * angle.cos();
+    let cos_k = 1.0 - 0.5 * (kx * dx).powi(2);
+    if cos_k.abs() >= 1.0 || pml.thickness <= 0.0 {
+        return 1.0;
+    }
+    let cells = (pml.thickness * resolution).round().max(1.0) as i64;
+    let stretch = |j: f64| Complex64::new(1.0, pml.sigma_at_depth(j * dx) / omega);
+
+    // E_j for j = cells, cells − 1, …, −2 with the PEC wall at j = cells
+    let mut next = Complex64::new(0.0, 0.0);
+    let mut current = Complex64::new(1.0, 0.0);
+    let mut history = Vec::with_capacity(2);
+    for j in (-1..cells).rev() {
+        let jf = j as f64;
+        let flux = (next - current) / stretch(jf + 0.5) + kx * kx * dx * dx * stretch(jf) * current;
+        let previous = current - stretch(jf - 0.5) * flux;
+        next = current;
+        current = previous;
+        if j <= 0 {
+            history.push(current);
+        }
+    }
+    // E_{−1}, E_{−2} = a z^j + b z^{−j} with z = exp(ikΔ)
+    let (e1, e2) = (history[0], history[1]);
+    let z = Complex64::from_polar(1.0, cos_k.acos());
+    let det = z.inv() - z;
+    let a = (e2 * z - e1 * z * z) / det;
+    let b = (e1 * z.inv() * z.inv() - e2 * z.inv()) / det;
+    (b / a).norm().min(1.0)
+}
+
+/// Discrete reflection at normal incidence, the figure checked against the target.
+fn normal_reflection(pml: &PmlSpec, resolution: f64, frequency: f64, index: f64) -> f64 {
+    discrete_pml_reflection(pml, resolution, frequency, index, 0.0)
+}
+
+pub fn design_pml_internal(config: &PmlDesignConfig) -> Result<PmlDesignResult, String> {
+    if config.resolution <= 0.0 {
+        return Err("resolution must be positive".into());
+    }
+    if config.index < 1.0 {
+        return Err("index of the adjacent medium must be at least 1".into());
+    }
+    if !(config.target_reflection > 0.0 && config.target_reflection < 1.0) {
+        return Err("target reflection must lie in (0, 1)".into());
+    }
+    let design_frequency = config.sources.iter().map(|s| s.range().min).fold(f64::INFINITY, f64::min);
+    if !design_frequency.is_finite() || design_frequency <= 0.0 {
+        return Err("at least one source with a positive lowest frequency is required".into());
+    }
+    let wavelength = 1.0 / (config.index * design_frequency);
+    let label = |edge: &PmlEdgeSpec, i: usize| if edge.edge.is_empty() { format!("layer {}", i + 1) } else { edge.edge.clone() };
+
+    let mut warnings = Vec::new();
+    let mut edges = Vec::with_capacity(config.layers.len());
+    for (i, layer) in config.layers.iter().enumerate() {
+        let pml = layer.pml;
+        let name = label(layer, i);
+        if pml.thickness <= 0.0 || !(pml.r_asymptotic > 0.0 && pml.r_asymptotic < 1.0) {
+            return Err(format!("{}: thickness must be positive and R_asymptotic in (0, 1)", name));
+        }
+        let profile_points = config.profile_points.max(2);
+        let profile = (0..profile_points)
+            .map(|k| {
+                let depth = pml.thickness * k as f64 / (profile_points - 1) as f64;
+                PmlProfileSample { depth, sigma: pml.sigma_at_depth(depth) }
+            })
+            .collect();
+        let reflections = config
+            .angles
+            .iter()
+            .map(|&angle| PmlAngleReflection {
+                angle,
+                theoretical: theoretical_pml_reflection(&pml, config.index, angle.to_radians()),
+                discrete: discrete_pml_reflection(&pml, config.resolution, design_frequency, config.index, angle.to_radians()),
+            })
+            .collect();
+
+        // keep the asymptotic floor two decades below the target, then grow
+        // the layer cell by cell until the discretisation error is small enough
+        let floor = config.target_reflection * 1e-2;
+        let recommended_r_asymptotic = pml.r_asymptotic.min(floor.powf(1.0 / config.index));
+        let max_cells = (4.0 * wavelength * config.resolution).ceil().max(1.0) as usize;
+        let recommended_thickness = (1..=max_cells).map(|m| m as f64 / config.resolution).find(|&thickness| {
+            let trial = PmlSpec { thickness, r_asymptotic: recommended_r_asymptotic, ..pml };
+            normal_reflection(&trial, config.resolution, design_frequency, config.index) <= config.target_reflection
+        });
+
+        let reflection = normal_reflection(&pml, config.resolution, design_frequency, config.index);
+        let adequate = reflection <= config.target_reflection;
+        if !adequate {
+            warnings.push(format!(
+                "{}: reflection {:.1e} at f = {:.4} exceeds the target {:.0e}{}",
+                name,
+                reflection,
+                design_frequency,
+                config.target_reflection,
+                match recommended_thickness {
+                    Some(d) => format!("; use thickness ≥ {:.3}", d),
+                    None => "; no thickness up to four wavelengths suffices, increase the resolution".into(),
+                }
+            ));
+        }
+        if theoretical_pml_reflection(&pml, config.index, 0.0) > config.target_reflection {
+            warnings.push(format!(
+                "{}: R_asymptotic = {:.0e} alone exceeds the target; use {:.0e}",
+                name, pml.r_asymptotic, recommended_r_asymptotic
+            ));
+        }
+        if pml.thickness < 0.5 * wavelength {
+            warnings.push(format!(
+                "{}: thickness {:.3} is below half a wavelength ({:.3}) at the lowest source frequency",
+                name,
+                pml.thickness,
+                0.5 * wavelength
+            ));
+        }
+
+        edges.push(PmlEdgeReport {
+            edge: name,
+            thickness: pml.thickness,
+            power: pml.power,
+            r_asymptotic: pml.r_asymptotic,
+            sigma_max: pml.sigma_max(),
+            cells: (pml.thickness * config.resolution).round() as usize,
+            thickness_in_wavelengths: pml.thickness / wavelength,
+            profile,
+            reflections,
+            recommended_thickness,
+            recommended_r_asymptotic,
+            adequate,
+        });
+    }
+
+    Ok(PmlDesignResult { design_frequency, wavelength, edges, warnings })
+}
+
+/// Report PML grading, reflection and recommended thickness per edge
+#[wasm_bindgen]
+pub fn design_pml(config: &JsValue) -> Result<JsValue, JsValue> {
+    let config: PmlDesignConfig = serde_wasm_bindgen::from_value(config.clone())?;
+    let result = design_pml_internal(&config).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pml(thickness: f64, r_asymptotic: f64) -> PmlSpec {
+        PmlSpec { thickness, power: 2.0, r_asymptotic }
+    }
+
+    #[test]
+    fn test_discrete_reflection_converges_to_theory() {
+        let layer = pml(2.0, 1e-4);
+        for angle in [0.0f64, 60.0] {
+            let theory = theoretical_pml_reflection(&layer, 1.5, angle.to_radians());
+            let discrete = discrete_pml_reflection(&layer, 400.0, 1.0, 1.5, angle.to_radians());
+            assert!((discrete / theory - 1.0).abs() < 0.05, "{} vs {}", discrete, theory);
+        }
+        // a thin layer at coarse resolution is dominated by discretisation
+        let coarse = discrete_pml_reflection(&pml(0.5, 1e-8), 10.0, 1.0, 1.0, 0.0);
+        let fine = discrete_pml_reflection(&pml(0.5, 1e-8), 40.0, 1.0, 1.0, 0.0);
+        assert!(coarse > 1e-4 && fine < coarse / 10.0, "{} {}", coarse, fine);
+    }
+
+    #[test]
+    fn test_recommended_thickness_meets_target() {
+        let config = PmlDesignConfig {
+            resolution: 20.0,
+            layers: vec![PmlEdgeSpec { edge: "left".into(), pml: pml(0.3, 1e-15) }],
+            sources: vec![SourceBand { frequency: 1.0, fwidth: 0.4 }],
+            index: 1.0,
+            angles: default_angles(),
+            target_reflection: 1e-6,
+            profile_points: default_profile_points(),
+        };
+        let result = design_pml_internal(&config).unwrap();
+        assert!((result.design_frequency - 0.8).abs() < 1e-12);
+        let edge = &result.edges[0];
+        assert!(!edge.adequate);
+        assert!(!result.warnings.is_empty());
+        let thickness = edge.recommended_thickness.unwrap();
+        assert!(thickness > 0.3);
+        let trial = pml(thickness, edge.recommended_r_asymptotic);
+        assert!(discrete_pml_reflection(&trial, 20.0, 0.8, 1.0, 0.0) <= 1e-6);
+        assert_eq!(edge.profile.last().unwrap().sigma, edge.sigma_max);
+    }
+}
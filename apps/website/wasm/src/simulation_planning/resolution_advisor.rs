@@ -0,0 +1,304 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::field_solvers::transfer_matrix::LayerMedium;
+use crate::material_calculations::dispersion::{complex_refractive_index, FrequencyRange};
+use crate::material_calculations::material_library::{find_material, SourceBand, UnitSystem};
+
+/// A project material that is not in the library, already in Meep units.
+#[derive(Clone, Debug, Deserialize)]
+pub struct NamedMedium {
+    pub name: String,
+    #[serde(flatten)]
+    pub medium: LayerMedium,
+}
+
+/// Smallest dimension of a geometry object (a wall, gap, radius or period).
+#[derive(Clone, Debug, Deserialize)]
+pub struct GeometryFeature {
+    #[serde(default)]
+    pub name: String,
+    pub size: f64,
+}
+
+fn default_courant() -> f64 {
+    0.5
+}
+
+fn default_dimensions() -> usize {
+    2
+}
+
+fn default_points_per_wavelength() -> f64 {
+    10.0
+}
+
+fn default_pixels_per_feature() -> f64 {
+    4.0
+}
+
+fn default_band_samples() -> usize {
+    16
+}
+
+#[derive(Deserialize)]
+pub struct ResolutionConfig {
+    /// Library materials by name or abbreviation.
+    #[serde(default)]
+    pub materials: Vec<String>,
+    #[serde(default)]
+    pub custom_materials: Vec<NamedMedium>,
+    #[serde(flatten)]
+    pub units: UnitSystem,
+    pub sources: Vec<SourceBand>,
+    #[serde(default)]
+    pub features: Vec<GeometryFeature>,
+    pub resolution: f64,
+    #[serde(rename = "Courant", alias = "courant", default = "default_courant")]
+    pub courant: f64,
+    #[serde(default = "default_dimensions")]
+    pub dimensions: usize,
+    #[serde(default = "default_points_per_wavelength")]
+    pub points_per_wavelength: f64,
+    #[serde(default = "default_pixels_per_feature")]
+    pub pixels_per_feature: f64,
+    /// Frequencies sampled across each source band.
+    #[serde(default = "default_band_samples")]
+    pub band_samples: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResolutionWarningKind {
+    Wavelength,
+    Feature,
+    Courant,
+    MaterialRange,
+}
+
+#[derive(Serialize)]
+pub struct ResolutionWarning {
+    pub kind: ResolutionWarningKind,
+    pub subject: String,
+    pub message: String,
+}
+
+#[derive(Serialize)]
+pub struct MaterialResolution {
+    pub name: String,
+    /// Largest |n + ik| over the source bands.
+    pub max_index: f64,
+    /// Shortest wavelength 1 / (|n| f) inside the material.
+    pub min_wavelength: f64,
+    pub points_per_wavelength: f64,
+}
+
+#[derive(Serialize)]
+pub struct FeatureResolution {
+    pub name: String,
+    pub size: f64,
+    pub pixels: f64,
+}
+
+#[derive(Serialize)]
+pub struct ResolutionAdvice {
+    pub band: FrequencyRange,
+    /// Vacuum is always included, so this is at most 1 / f_max.
+    pub min_wavelength: f64,
+    pub limiting_material: String,
+    pub points_per_wavelength: f64,
+    pub min_feature_pixels: Option<f64>,
+    /// Meep's time step Δt = S Δx.
+    pub dt: f64,
+    /// Stability limit 1/√D of the Courant factor in vacuum.
+    pub courant_limit: f64,
+    pub materials: Vec<MaterialResolution>,
+    pub features: Vec<FeatureResolution>,
+    pub recommended_resolution: f64,
+    pub warnings: Vec<ResolutionWarning>,
+}
+
+/// Frequencies spread over each band, excluding non-positive ones.
+fn band_frequencies(sources: &[SourceBand], samples: usize) -> Vec<f64> {
+    let samples = samples.max(2);
+    sources
+        .iter()
+        .flat_map(|s| {
+            let band = s.range();
+            (0..samples).map(move |i| band.min + (band.max - band.min) * i as f64 / (samples - 1) as f64)
+        })
+        .filter(|&f| f > 0.0)
+        .collect()
+}
+
+pub fn advise_resolution_internal(config: &ResolutionConfig) -> Result<ResolutionAdvice, String> {
+    if config.resolution <= 0.0 {
+        return Err("resolution must be positive".into());
+    }
+    if config.units.a <= 0.0 {
+        return Err("length scale a must be positive".into());
+    }
+    if !(1..=3).contains(&config.dimensions) {
+        return Err("dimensions must be 1, 2 or 3".into());
+    }
+    let frequencies = band_frequencies(&config.sources, config.band_samples);
+    if frequencies.is_empty() {
+        return Err("at least one source with positive frequency content is required".into());
+    }
+    let band = FrequencyRange {
+        min: frequencies.iter().cloned().fold(f64::INFINITY, f64::min),
+        max: frequencies.iter().cloned().fold(0.0, f64::max),
+    };
+
+    let mut warnings = Vec::new();
+    let mut media = vec![("Vacuum".to_string(), LayerMedium::constant(1.0))];
+    for key in &config.materials {
+        let material = find_material(key)?;
+        for source in &config.sources {
+            if let Some(message) = material.frequency_warning(&config.units, source.range()) {
+                warnings.push(ResolutionWarning {
+                    kind: ResolutionWarningKind::MaterialRange,
+                    subject: material.name.to_string(),
+                    message,
+                });
+            }
+        }
+        media.push((material.name.to_string(), material.medium(&config.units)));
+    }
+    media.extend(config.custom_materials.iter().map(|m| (m.name.clone(), m.medium.clone())));
+
+    let materials: Vec<MaterialResolution> = media
+        .iter()
+        .map(|(name, medium)| {
+            // the index magnitude also covers metals, whose skin depth is λ / (2π k)
+            let (max_index, min_wavelength) = frequencies.iter().fold((0.0f64, f64::INFINITY), |(n_max, l_min), &f| {
+                let n = complex_refractive_index(medium.epsilon_at(f)).norm();
+                (n_max.max(n), l_min.min(1.0 / (n * f)))
+            });
+            MaterialResolution {
+                name: name.clone(),
+                max_index,
+                min_wavelength,
+                points_per_wavelength: min_wavelength * config.resolution,
+            }
+        })
+        .collect();
+    let limiting = materials.iter().min_by(|a, b| a.min_wavelength.total_cmp(&b.min_wavelength)).unwrap();
+    let min_wavelength = limiting.min_wavelength;
+    for m in materials.iter().filter(|m| m.points_per_wavelength < config.points_per_wavelength) {
+        warnings.push(ResolutionWarning {
+            kind: ResolutionWarningKind::Wavelength,
+            subject: m.name.clone(),
+            message: format!(
+                "{}: {:.1} pixels per wavelength (λ = {:.4}, n = {:.3}) is below {}",
+                m.name, m.points_per_wavelength, m.min_wavelength, m.max_index, config.points_per_wavelength
+            ),
+        });
+    }
+
+    let features: Vec<FeatureResolution> = config
+        .features
+        .iter()
+        .filter(|f| f.size > 0.0)
+        .map(|f| FeatureResolution { name: f.name.clone(), size: f.size, pixels: f.size * config.resolution })
+        .collect();
+    for f in features.iter().filter(|f| f.pixels < config.pixels_per_feature) {
+        let subject = if f.name.is_empty() { format!("feature of size {}", f.size) } else { f.name.clone() };
+        warnings.push(ResolutionWarning {
+            kind: ResolutionWarningKind::Feature,
+            message: format!("{}: {:.1} pixels across, below {}", subject, f.pixels, config.pixels_per_feature),
+            subject,
+        });
+    }
+    let min_feature = features.iter().map(|f| f.size).fold(f64::INFINITY, f64::min);
+
+    let courant_limit = 1.0 / (config.dimensions as f64).sqrt();
+    if config.courant <= 0.0 || config.courant > courant_limit {
+        warnings.push(ResolutionWarning {
+            kind: ResolutionWarningKind::Courant,
+            subject: "Courant".into(),
+            message: format!(
+                "Courant factor {} is outside (0, {:.4}] and the {}D update will be unstable",
+                config.courant, courant_limit, config.dimensions
+            ),
+        });
+    }
+
+    let mut recommended = config.points_per_wavelength / min_wavelength;
+    if min_feature.is_finite() {
+        recommended = recommended.max(config.pixels_per_feature / min_feature);
+    }
+
+    Ok(ResolutionAdvice {
+        band,
+        min_wavelength,
+        limiting_material: limiting.name.clone(),
+        points_per_wavelength: min_wavelength * config.resolution,
+        min_feature_pixels: min_feature.is_finite().then_some(min_feature * config.resolution),
+        dt: config.courant / config.resolution,
+        courant_limit,
+        recommended_resolution: recommended.ceil(),
+        materials,
+        features,
+        warnings,
+    })
+}
+
+/// Check a project's resolution against its materials, features and sources
+#[wasm_bindgen]
+pub fn advise_resolution(config: &JsValue) -> Result<JsValue, JsValue> {
+    let config: ResolutionConfig = serde_wasm_bindgen::from_value(config.clone())?;
+    let result = advise_resolution_internal(&config).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material_calculations::material_library::LengthUnit;
+
+    fn silicon_waveguide(resolution: f64) -> ResolutionConfig {
+        ResolutionConfig {
+            materials: vec!["Si".into(), "SiO₂".into()],
+            custom_materials: Vec::new(),
+            units: UnitSystem { a: 1.0, unit: LengthUnit::Um },
+            sources: vec![SourceBand { frequency: 1.0 / 1.55, fwidth: 0.0 }],
+            features: vec![GeometryFeature { name: "core".into(), size: 0.22 }],
+            resolution,
+            courant: default_courant(),
+            dimensions: 2,
+            points_per_wavelength: default_points_per_wavelength(),
+            pixels_per_feature: default_pixels_per_feature(),
+            band_samples: default_band_samples(),
+        }
+    }
+
+    #[test]
+    fn test_silicon_limits_the_resolution() {
+        let advice = advise_resolution_internal(&silicon_waveguide(10.0)).unwrap();
+        assert_eq!(advice.limiting_material, "Silicon");
+        assert!((advice.min_wavelength - 1.55 / 3.48).abs() < 2e-3);
+        // 10 pixels per λ/n beats 4 pixels across the 220 nm core
+        assert_eq!(advice.recommended_resolution, 23.0);
+        assert!((advice.dt - 0.05).abs() < 1e-15);
+        let kinds: Vec<_> = advice.warnings.iter().map(|w| w.kind).collect();
+        assert!(kinds.contains(&ResolutionWarningKind::Wavelength));
+        assert!(kinds.contains(&ResolutionWarningKind::Feature));
+
+        let resolved = advise_resolution_internal(&silicon_waveguide(advice.recommended_resolution)).unwrap();
+        assert!(resolved.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_small_feature_and_unstable_courant() {
+        let config = ResolutionConfig {
+            features: vec![GeometryFeature { name: "slot".into(), size: 0.05 }],
+            courant: 0.6,
+            dimensions: 3,
+            ..silicon_waveguide(30.0)
+        };
+        let advice = advise_resolution_internal(&config).unwrap();
+        assert_eq!(advice.recommended_resolution, 80.0);
+        assert!(advice.warnings.iter().any(|w| w.kind == ResolutionWarningKind::Courant));
+    }
+}